@@ -38,6 +38,12 @@ pub enum ErrorCode {
     InvalidVotingPeriod,
     #[msg("Invalid stake amount")]
     InvalidStakeAmount,
+    #[msg("Voting period has not ended")]
+    VotingPeriodNotEnded,
+    #[msg("Invalid policy parameters")]
+    InvalidPolicyParams,
+    #[msg("Voting power account does not match the proposal's source")]
+    InvalidVotingPowerSource,
 
     // Circuit breaker errors
     #[msg("Circuit breaker is active")]
@@ -47,6 +53,10 @@ pub enum ErrorCode {
     #[msg("Insufficient deposit for griefing protection (minimum 10 ARU)")]
     InsufficientDeposit,
 
+    // Agent reward errors
+    #[msg("No rewards to claim")]
+    NoRewardsToClaim,
+
     // Slashing errors
     #[msg("Slash amount exceeds agent stake")]
     SlashAmountTooHigh,
@@ -58,6 +68,12 @@ pub enum ErrorCode {
     InvalidMintBurnCap,
     #[msg("Invalid VHR threshold")]
     InvalidVHRThreshold,
+    #[msg("Invalid stability fee")]
+    InvalidStabilityFee,
+    #[msg("Invalid savings fee share")]
+    InvalidSavingsFeeShare,
+    #[msg("Savings vault destination required")]
+    SavingsDestinationRequired,
     #[msg("Invalid ILI value")]
     InvalidILIValue,
     #[msg("Invalid yield rate")]
@@ -83,6 +99,10 @@ pub enum ErrorCode {
     #[msg("Epoch duration not complete")]
     EpochNotComplete,
 
+    // Settlement errors
+    #[msg("Global settlement is active")]
+    SettlementActive,
+
     // Percolator integration errors
     #[msg("Invalid Percolator program ID")]
     InvalidPercolatorProgram,
@@ -92,4 +112,10 @@ pub enum ErrorCode {
     InvalidPercolatorSlab,
     #[msg("Overflow in calculation")]
     Overflow,
+
+    // Migration errors
+    #[msg("Global state already uses the current layout")]
+    AlreadyMigrated,
+    #[msg("Account is not the global state")]
+    InvalidGlobalStateAccount,
 }
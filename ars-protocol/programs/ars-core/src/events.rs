@@ -1,5 +1,5 @@
 use anchor_lang::prelude::*;
-use crate::state::{AgentTier, PolicyType};
+use crate::state::{AgentTier, ParameterUpdate, PolicyType, ProposalStatus, VotingPowerSource};
 
 #[event]
 pub struct ProtocolInitialized {
@@ -41,6 +41,7 @@ pub struct ProposalCreated {
     pub proposal_id: u64,
     pub proposer: Pubkey,
     pub policy_type: PolicyType,
+    pub voting_power_source: VotingPowerSource,
     pub timestamp: i64,
 }
 
@@ -68,6 +69,55 @@ pub struct AgentSlashed {
     pub new_reputation: i32,
 }
 
+#[event]
+pub struct ProposalExecuted {
+    pub proposal_id: u64,
+    pub status: ProposalStatus,
+    pub quadratic_yes: u64,
+    pub quadratic_no: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ParameterUpdated {
+    pub proposal_id: u64,
+    pub update: ParameterUpdate,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct StabilityFeeCollected {
+    pub amount: u64,
+    pub savings_amount: u64,
+    pub remaining_accrued: u64,
+    pub stability_fee_index: u128,
+    pub destination: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct GlobalSettlementInitiated {
+    pub authority: Pubkey,
+    pub aru_supply: u64,
+    pub ili: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AgentRewardsDistributed {
+    pub amount: u64,
+    pub reward_per_update: u64,
+    pub unallocated: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AgentRewardsClaimed {
+    pub agent: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
 // Percolator Integration Events
 
 #[event]
@@ -2,7 +2,11 @@
 // Copy this to lib.rs when ready to build
 
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use anchor_lang::system_program;
+use anchor_lang::Discriminator;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use ars_token::program::ArsToken;
+use ars_token::{BalanceCheckpoints, EmissionSchedule, MintState, Minter, VoteEscrow};
 
 declare_id!("ARSFehdYbZhSgoQ2p82cHxPLGKrutXezJbYgDwJJA5My");
 
@@ -30,7 +34,7 @@ pub mod ars_core {
         
         require!(epoch_duration > 0, ErrorCode::InvalidEpochDuration);
         require!(mint_burn_cap_bps <= 10000, ErrorCode::InvalidMintBurnCap);
-        require!(vhr_threshold <= 10000, ErrorCode::InvalidVHRThreshold);
+        require!(vhr_threshold >= 10000, ErrorCode::InvalidVHRThreshold);
 
         global_state.authority = ctx.accounts.authority.key();
         global_state.pending_authority = None;
@@ -38,15 +42,27 @@ pub mod ars_core {
         global_state.ili_oracle = ctx.accounts.ili_oracle.key();
         global_state.reserve_vault = ctx.accounts.reserve_vault.key();
         global_state.aru_mint = ctx.accounts.aru_mint.key();
+        // ars-token must be initialized by the same authority, which fixes its MintState PDA
+        global_state.aru_mint_state = Pubkey::find_program_address(
+            &[b"mint_state", ctx.accounts.authority.key().as_ref()],
+            &ars_token::ID,
+        ).0;
         global_state.epoch_duration = epoch_duration;
         global_state.mint_burn_cap_bps = mint_burn_cap_bps;
         global_state.stability_fee_bps = 0;
-        global_state.vhr_threshold = vhr_threshold;
+        global_state.vhr_threshold = u64::from(vhr_threshold);
         global_state.circuit_breaker_active = false;
         global_state.circuit_breaker_timelock = 0;
         global_state.min_agent_consensus = 3;
         global_state.proposal_counter = 0;
         global_state.last_update_slot = Clock::get()?.slot;
+        global_state.stability_fee_index = STABILITY_FEE_INDEX_SCALE;
+        global_state.last_fee_accrual = Clock::get()?.unix_timestamp;
+        global_state.accrued_stability_fees = 0;
+        global_state.savings_fee_share_bps = 0;
+        global_state.settlement_active = false;
+        global_state.settlement_started_at = 0;
+        global_state.settlement_ili = 0;
         global_state.bump = ctx.bumps.global_state;
 
         let ili_oracle = &mut ctx.accounts.ili_oracle;
@@ -67,6 +83,82 @@ pub mod ars_core {
         Ok(())
     }
 
+    pub fn migrate_global_state(
+        ctx: Context<MigrateGlobalState>,
+    ) -> Result<()> {
+        let global_state_info = ctx.accounts.global_state.to_account_info();
+        require!(
+            global_state_info.data_len() == LegacyGlobalState::LEN,
+            ErrorCode::AlreadyMigrated
+        );
+        
+        let legacy = {
+            let data = global_state_info.try_borrow_data()?;
+            require!(
+                data[..8] == GlobalState::DISCRIMINATOR,
+                ErrorCode::InvalidGlobalStateAccount
+            );
+            LegacyGlobalState::deserialize(&mut &data[8..])?
+        };
+        require_keys_eq!(
+            legacy.authority,
+            ctx.accounts.authority.key(),
+            ErrorCode::Unauthorized
+        );
+        
+        // Existing fields carry over; fee and settlement state start fresh as in initialize
+        let global_state = GlobalState {
+            authority: legacy.authority,
+            pending_authority: legacy.pending_authority,
+            transfer_timelock: legacy.transfer_timelock,
+            ili_oracle: legacy.ili_oracle,
+            reserve_vault: legacy.reserve_vault,
+            aru_mint: legacy.aru_mint,
+            epoch_duration: legacy.epoch_duration,
+            mint_burn_cap_bps: legacy.mint_burn_cap_bps,
+            stability_fee_bps: legacy.stability_fee_bps,
+            vhr_threshold: u64::from(legacy.vhr_threshold),
+            circuit_breaker_active: legacy.circuit_breaker_active,
+            circuit_breaker_timelock: legacy.circuit_breaker_timelock,
+            min_agent_consensus: legacy.min_agent_consensus,
+            proposal_counter: legacy.proposal_counter,
+            last_update_slot: legacy.last_update_slot,
+            bump: legacy.bump,
+            aru_mint_state: Pubkey::find_program_address(
+                &[b"mint_state", legacy.authority.as_ref()],
+                &ars_token::ID,
+            ).0,
+            stability_fee_index: STABILITY_FEE_INDEX_SCALE,
+            last_fee_accrual: Clock::get()?.unix_timestamp,
+            accrued_stability_fees: 0,
+            savings_fee_share_bps: 0,
+            settlement_active: false,
+            settlement_started_at: 0,
+            settlement_ili: 0,
+        };
+        
+        let rent_exempt = Rent::get()?.minimum_balance(GlobalState::LEN);
+        let current_lamports = global_state_info.lamports();
+        if current_lamports < rent_exempt {
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.authority.to_account_info(),
+                        to: global_state_info.clone(),
+                    },
+                ),
+                rent_exempt - current_lamports,
+            )?;
+        }
+        
+        global_state_info.realloc(GlobalState::LEN, false)?;
+        let mut data = global_state_info.try_borrow_mut_data()?;
+        global_state.try_serialize(&mut &mut data[..])?;
+        
+        Ok(())
+    }
+
     pub fn initiate_admin_transfer(
         ctx: Context<InitiateAdminTransfer>,
         new_authority: Pubkey,
@@ -144,6 +236,8 @@ pub mod ars_core {
         agent_registry.registered_at = current_time;
         agent_registry.last_active = current_time;
         agent_registry.is_active = true;
+        agent_registry.pending_rewards = 0;
+        agent_registry.last_rewarded_at = 0;
         agent_registry.bump = ctx.bumps.agent_registry;
         
         token::transfer(
@@ -173,7 +267,7 @@ pub mod ars_core {
         ili_value: u64,
         timestamp: i64,
     ) -> Result<()> {
-        let agent_registry = &ctx.accounts.agent_registry;
+        let agent_registry = &mut ctx.accounts.agent_registry;
         let ili_oracle = &mut ctx.accounts.ili_oracle;
         let global_state = &ctx.accounts.global_state;
         let current_time = Clock::get()?.unix_timestamp;
@@ -184,6 +278,30 @@ pub mod ars_core {
             ErrorCode::CircuitBreakerActive
         );
         
+        agent_registry.total_ili_updates = agent_registry.total_ili_updates
+            .checked_add(1)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        agent_registry.last_active = current_time;
+        
+        // Credit the contributor from the emission pool when one is supplied,
+        // at most once per oracle update interval so resubmitting earns nothing
+        let reward_due = current_time
+            >= agent_registry.last_rewarded_at
+                .saturating_add(ili_oracle.update_interval);
+        if let Some(reward_pool) = ctx.accounts.reward_pool.as_mut().filter(|_| reward_due) {
+            agent_registry.last_rewarded_at = current_time;
+            let reward = reward_pool.reward_per_update.min(reward_pool.unallocated);
+            reward_pool.unallocated = reward_pool.unallocated
+                .checked_sub(reward)
+                .ok_or(ErrorCode::ArithmeticOverflow)?;
+            reward_pool.total_credited = reward_pool.total_credited
+                .checked_add(reward)
+                .ok_or(ErrorCode::ArithmeticOverflow)?;
+            agent_registry.pending_rewards = agent_registry.pending_rewards
+                .checked_add(reward)
+                .ok_or(ErrorCode::ArithmeticOverflow)?;
+        }
+        
         ili_oracle.pending_updates.push(ILIPendingUpdate {
             agent: agent_registry.agent_pubkey,
             ili_value,
@@ -223,12 +341,21 @@ pub mod ars_core {
         policy_type: PolicyType,
         policy_params: Vec<u8>,
         voting_period: i64,
+        voting_power_source: VotingPowerSource,
     ) -> Result<()> {
         require!(
             voting_period > 0 && voting_period <= 604800,
             ErrorCode::InvalidVotingPeriod
         );
         require!(policy_params.len() <= 256, ErrorCode::InvalidAmount);
+        require!(
+            !ctx.accounts.global_state.settlement_active,
+            ErrorCode::SettlementActive
+        );
+        if policy_type == PolicyType::UpdateParameters {
+            ParameterUpdate::try_from_slice(&policy_params)
+                .map_err(|_| ErrorCode::InvalidPolicyParams)?;
+        }
 
         let global_state = &mut ctx.accounts.global_state;
         let proposal = &mut ctx.accounts.proposal;
@@ -249,6 +376,9 @@ pub mod ars_core {
         proposal.status = ProposalStatus::Active;
         proposal.execution_tx = None;
         proposal.griefing_protection_deposit = 10_000_000;
+        // Balances are read as of the previous slot so same-slot stakes cannot count
+        proposal.snapshot_slot = clock.slot.saturating_sub(1);
+        proposal.voting_power_source = voting_power_source;
         proposal.bump = ctx.bumps.proposal;
 
         global_state.proposal_counter = global_state.proposal_counter
@@ -259,6 +389,7 @@ pub mod ars_core {
             proposal_id: proposal.id,
             proposer: proposal.proposer,
             policy_type,
+            voting_power_source,
             timestamp: clock.unix_timestamp,
         });
 
@@ -268,19 +399,46 @@ pub mod ars_core {
     pub fn vote_on_proposal(
         ctx: Context<VoteOnProposal>,
         vote_yes: bool,
-        stake_amount: u64,
     ) -> Result<()> {
         let proposal = &mut ctx.accounts.proposal;
         let agent_registry = &ctx.accounts.agent_registry;
         let current_time = Clock::get()?.unix_timestamp;
         
+        require!(
+            !ctx.accounts.global_state.settlement_active,
+            ErrorCode::SettlementActive
+        );
         require!(
             current_time >= proposal.start_time && current_time < proposal.end_time,
             ErrorCode::ProposalNotActive
         );
         require!(agent_registry.is_active, ErrorCode::AgentNotActive);
         
-        let voting_power = (stake_amount as f64).sqrt() as u64;
+        let (stake_amount, voting_power) = match proposal.voting_power_source {
+            VotingPowerSource::QuadraticStake => {
+                let checkpoints = ctx.accounts.voter_checkpoints
+                    .as_ref()
+                    .ok_or(ErrorCode::InvalidVotingPowerSource)?;
+                let stake_amount = checkpoints.balance_at(proposal.snapshot_slot);
+                (stake_amount, (stake_amount as f64).sqrt() as u64)
+            }
+            VotingPowerSource::VoteEscrow => {
+                let vote_escrow = ctx.accounts.vote_escrow
+                    .as_ref()
+                    .ok_or(ErrorCode::InvalidVotingPowerSource)?;
+                let lock = vote_escrow.lock_at(proposal.snapshot_slot);
+                (lock.amount, lock.voting_power_at(proposal.start_time))
+            }
+        };
+        require!(voting_power > 0, ErrorCode::InvalidStakeAmount);
+        
+        let vote_record = &mut ctx.accounts.vote_record;
+        vote_record.proposal_id = proposal.id;
+        vote_record.voter = ctx.accounts.voter.key();
+        vote_record.vote_yes = vote_yes;
+        vote_record.stake_amount = stake_amount;
+        vote_record.voting_power = voting_power;
+        vote_record.bump = ctx.bumps.vote_record;
         
         if vote_yes {
             proposal.yes_stake = proposal.yes_stake
@@ -376,6 +534,449 @@ pub mod ars_core {
         
         Ok(())
     }
+
+    /// Last-resort wind-down: freeze ARU minting and governance for good.
+    /// Slashing and stake withdrawals keep working; ARU holders claim their
+    /// share of the reserve through ars-reserve.
+    pub fn initiate_global_settlement(ctx: Context<InitiateGlobalSettlement>) -> Result<()> {
+        let global_state = &mut ctx.accounts.global_state;
+        let current_time = Clock::get()?.unix_timestamp;
+        
+        require!(
+            ctx.accounts.authority.key() == global_state.authority,
+            ErrorCode::Unauthorized
+        );
+        require!(
+            !global_state.settlement_active,
+            ErrorCode::SettlementActive
+        );
+        
+        ars_token::cpi::freeze_minting(CpiContext::new(
+            ctx.accounts.ars_token_program.to_account_info(),
+            ars_token::cpi::accounts::FreezeMinting {
+                mint_state: ctx.accounts.mint_state.to_account_info(),
+                authority: ctx.accounts.authority.to_account_info(),
+            },
+        ))?;
+        
+        global_state.settlement_active = true;
+        global_state.settlement_started_at = current_time;
+        global_state.settlement_ili = ctx.accounts.ili_oracle.current_ili;
+        
+        emit!(GlobalSettlementInitiated {
+            authority: global_state.authority,
+            aru_supply: ctx.accounts.mint_state.total_supply,
+            ili: global_state.settlement_ili,
+            timestamp: current_time,
+        });
+        
+        Ok(())
+    }
+
+    pub fn execute_proposal(ctx: Context<ExecuteProposal>) -> Result<()> {
+        let global_state = &mut ctx.accounts.global_state;
+        let proposal = &mut ctx.accounts.proposal;
+        let current_time = Clock::get()?.unix_timestamp;
+        
+        require!(
+            !global_state.settlement_active,
+            ErrorCode::SettlementActive
+        );
+        require!(
+            proposal.status == ProposalStatus::Active,
+            ErrorCode::ProposalNotActive
+        );
+        require!(
+            current_time >= proposal.end_time,
+            ErrorCode::VotingPeriodNotEnded
+        );
+        
+        if proposal.quadratic_yes <= proposal.quadratic_no {
+            proposal.status = ProposalStatus::Rejected;
+        } else if proposal.policy_type == PolicyType::UpdateParameters {
+            let update = ParameterUpdate::try_from_slice(&proposal.policy_params)
+                .map_err(|_| ErrorCode::InvalidPolicyParams)?;
+            
+            match update {
+                ParameterUpdate::StabilityFeeBps(fee_bps) => {
+                    require!(fee_bps <= 10000, ErrorCode::InvalidStabilityFee);
+                    
+                    // Settle accrual at the old rate before switching
+                    let mint_state = ctx.accounts.mint_state
+                        .as_ref()
+                        .ok_or(ErrorCode::InvalidPolicyParams)?;
+                    global_state.accrue_stability_fee(mint_state.total_supply, current_time)?;
+                    global_state.stability_fee_bps = fee_bps;
+                }
+                ParameterUpdate::SavingsFeeShareBps(share_bps) => {
+                    require!(share_bps <= 10000, ErrorCode::InvalidSavingsFeeShare);
+                    global_state.savings_fee_share_bps = share_bps;
+                }
+            }
+            
+            proposal.status = ProposalStatus::Executed;
+            
+            emit!(ParameterUpdated {
+                proposal_id: proposal.id,
+                update,
+                timestamp: current_time,
+            });
+        } else {
+            proposal.status = ProposalStatus::Passed;
+        }
+        
+        emit!(ProposalExecuted {
+            proposal_id: proposal.id,
+            status: proposal.status,
+            quadratic_yes: proposal.quadratic_yes,
+            quadratic_no: proposal.quadratic_no,
+            timestamp: current_time,
+        });
+        
+        Ok(())
+    }
+
+    /// Mint accrued stability fees to the reserve (and the sARU savings vault)
+    /// through the ars-token minter registered for the global state PDA, so
+    /// issuance is bounded by the ceiling governance set for it.
+    pub fn collect_stability_fee(ctx: Context<CollectStabilityFee>) -> Result<()> {
+        let current_time = Clock::get()?.unix_timestamp;
+        let total_supply = ctx.accounts.mint_state.total_supply;
+        let global_state = &mut ctx.accounts.global_state;
+        
+        global_state.accrue_stability_fee(total_supply, current_time)?;
+        
+        // Only mint what still fits under the minter's ceiling
+        let minter_account = &ctx.accounts.minter_account;
+        let remaining_ceiling = minter_account.mint_ceiling
+            .saturating_sub(minter_account.outstanding);
+        let amount = global_state.accrued_stability_fees.min(remaining_ceiling);
+        
+        let savings_amount = (amount as u128)
+            .checked_mul(global_state.savings_fee_share_bps as u128)
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            .checked_div(10000)
+            .ok_or(ErrorCode::ArithmeticOverflow)? as u64;
+        let reserve_amount = amount
+            .checked_sub(savings_amount)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        
+        let bump = global_state.bump;
+        let seeds = &[b"global_state".as_ref(), &[bump]];
+        let signer = &[&seeds[..]];
+        
+        if savings_amount > 0 {
+            let savings_destination = ctx.accounts.savings_destination
+                .as_ref()
+                .ok_or(ErrorCode::SavingsDestinationRequired)?;
+            
+            ars_token::cpi::minter_mint(
+                CpiContext::new_with_signer(
+                    ctx.accounts.ars_token_program.to_account_info(),
+                    ars_token::cpi::accounts::MinterMint {
+                        mint_state: ctx.accounts.mint_state.to_account_info(),
+                        minter_account: ctx.accounts.minter_account.to_account_info(),
+                        aru_mint: ctx.accounts.aru_mint.to_account_info(),
+                        destination: savings_destination.to_account_info(),
+                        minter: ctx.accounts.global_state.to_account_info(),
+                        token_program: ctx.accounts.token_program.to_account_info(),
+                    },
+                    signer,
+                ),
+                savings_amount,
+            )?;
+        }
+        
+        if reserve_amount > 0 {
+            ars_token::cpi::minter_mint(
+                CpiContext::new_with_signer(
+                    ctx.accounts.ars_token_program.to_account_info(),
+                    ars_token::cpi::accounts::MinterMint {
+                        mint_state: ctx.accounts.mint_state.to_account_info(),
+                        minter_account: ctx.accounts.minter_account.to_account_info(),
+                        aru_mint: ctx.accounts.aru_mint.to_account_info(),
+                        destination: ctx.accounts.destination.to_account_info(),
+                        minter: ctx.accounts.global_state.to_account_info(),
+                        token_program: ctx.accounts.token_program.to_account_info(),
+                    },
+                    signer,
+                ),
+                reserve_amount,
+            )?;
+        }
+        
+        let global_state = &mut ctx.accounts.global_state;
+        global_state.accrued_stability_fees = global_state.accrued_stability_fees
+            .checked_sub(amount)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        
+        emit!(StabilityFeeCollected {
+            amount,
+            savings_amount,
+            remaining_accrued: global_state.accrued_stability_fees,
+            stability_fee_index: global_state.stability_fee_index,
+            destination: ctx.accounts.destination.key(),
+            timestamp: current_time,
+        });
+        
+        Ok(())
+    }
+
+    pub fn initialize_agent_reward_pool(ctx: Context<InitializeAgentRewardPool>) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.global_state.authority,
+            ErrorCode::Unauthorized
+        );
+        
+        let reward_pool = &mut ctx.accounts.reward_pool;
+        reward_pool.reward_vault = ctx.accounts.reward_vault.key();
+        reward_pool.reward_per_update = 0;
+        reward_pool.unallocated = 0;
+        reward_pool.total_received = 0;
+        reward_pool.total_credited = 0;
+        reward_pool.bump = ctx.bumps.reward_pool;
+        
+        Ok(())
+    }
+
+    pub fn distribute_agent_rewards(ctx: Context<DistributeAgentRewards>) -> Result<()> {
+        let pool_seeds = &[b"agent_rewards".as_ref(), &[ctx.accounts.reward_pool.bump]];
+        let signer = &[&pool_seeds[..]];
+        
+        let amount = ars_token::cpi::emit_epoch_rewards(
+            CpiContext::new_with_signer(
+                ctx.accounts.ars_token_program.to_account_info(),
+                ars_token::cpi::accounts::EmitEpochRewards {
+                    mint_state: ctx.accounts.mint_state.to_account_info(),
+                    emission_schedule: ctx.accounts.emission_schedule.to_account_info(),
+                    aru_mint: ctx.accounts.aru_mint.to_account_info(),
+                    reward_pool: ctx.accounts.reward_vault.to_account_info(),
+                    distributor: ctx.accounts.reward_pool.to_account_info(),
+                    token_program: ctx.accounts.token_program.to_account_info(),
+                },
+                signer,
+            ),
+        )?.get();
+        
+        let global_state = &ctx.accounts.global_state;
+        let ili_oracle = &ctx.accounts.ili_oracle;
+        let reward_pool = &mut ctx.accounts.reward_pool;
+        
+        reward_pool.unallocated = reward_pool.unallocated
+            .checked_add(amount)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        reward_pool.total_received = reward_pool.total_received
+            .checked_add(amount)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        
+        // Spread the pool over the ILI updates expected in one epoch
+        let expected_updates = (global_state.epoch_duration / ili_oracle.update_interval.max(1))
+            .max(1) as u64;
+        let expected_updates = expected_updates
+            .checked_mul(ili_oracle.consensus_threshold.max(1) as u64)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        reward_pool.reward_per_update = reward_pool.unallocated / expected_updates;
+        
+        emit!(AgentRewardsDistributed {
+            amount,
+            reward_per_update: reward_pool.reward_per_update,
+            unallocated: reward_pool.unallocated,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        
+        Ok(())
+    }
+
+    pub fn claim_agent_rewards(ctx: Context<ClaimAgentRewards>) -> Result<()> {
+        let agent_registry = &mut ctx.accounts.agent_registry;
+        let amount = agent_registry.pending_rewards;
+        require!(amount > 0, ErrorCode::NoRewardsToClaim);
+        
+        let pool_seeds = &[b"agent_rewards".as_ref(), &[ctx.accounts.reward_pool.bump]];
+        let signer = &[&pool_seeds[..]];
+        
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.reward_vault.to_account_info(),
+                    to: ctx.accounts.agent_token_account.to_account_info(),
+                    authority: ctx.accounts.reward_pool.to_account_info(),
+                },
+                signer,
+            ),
+            amount,
+        )?;
+        
+        agent_registry.pending_rewards = 0;
+        
+        emit!(AgentRewardsClaimed {
+            agent: agent_registry.agent_pubkey,
+            amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        
+        Ok(())
+    }
+
+    /// Allocate collateral from ARS to Percolator market
+    pub fn allocate_to_percolator(
+        ctx: Context<AllocateToPercolator>,
+        user_idx: u16,
+        amount: u64,
+    ) -> Result<()> {
+        let global_state = &ctx.accounts.global_state;
+        
+        require!(
+            ctx.accounts.authority.key() == global_state.authority,
+            ErrorCode::Unauthorized
+        );
+        require!(
+            !global_state.settlement_active,
+            ErrorCode::SettlementActive
+        );
+        require!(
+            !global_state.circuit_breaker_active,
+            ErrorCode::CircuitBreakerActive
+        );
+        require!(amount > 0, ErrorCode::InvalidAmount);
+        
+        let authority_seeds = &[
+            PERCOLATOR_AUTHORITY_SEED,
+            &[ctx.bumps.percolator_deposit.authority],
+        ];
+        percolator_deposit_collateral(
+            &ctx.accounts.percolator_deposit,
+            user_idx,
+            amount,
+            &[&authority_seeds[..]],
+        )?;
+        
+        emit!(PercolatorAllocation {
+            user_idx,
+            amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        
+        Ok(())
+    }
+
+    /// Withdraw collateral from Percolator back to ARS. Left open under the
+    /// circuit breaker and during settlement so collateral can always be
+    /// pulled back into the reserve.
+    pub fn withdraw_from_percolator(
+        ctx: Context<WithdrawFromPercolator>,
+        user_idx: u16,
+        amount: u64,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.global_state.authority,
+            ErrorCode::Unauthorized
+        );
+        require!(amount > 0, ErrorCode::InvalidAmount);
+        
+        let authority_seeds = &[
+            PERCOLATOR_AUTHORITY_SEED,
+            &[ctx.bumps.percolator_withdraw.authority],
+        ];
+        percolator_withdraw_collateral(
+            &ctx.accounts.percolator_withdraw,
+            user_idx,
+            amount,
+            &[&authority_seeds[..]],
+        )?;
+        
+        emit!(PercolatorWithdrawal {
+            user_idx,
+            amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        
+        Ok(())
+    }
+
+    /// Update Percolator oracle with ILI-derived price
+    pub fn update_percolator_oracle(ctx: Context<UpdatePercolatorOracle>) -> Result<()> {
+        let global_state = &ctx.accounts.global_state;
+        
+        require!(
+            ctx.accounts.authority.key() == global_state.authority,
+            ErrorCode::Unauthorized
+        );
+        require!(
+            !global_state.circuit_breaker_active,
+            ErrorCode::CircuitBreakerActive
+        );
+        
+        let ili_value = ctx.accounts.ili_oracle.current_ili;
+        require!(ili_value > 0, ErrorCode::InvalidILIValue);
+        let price_e6 = ili_to_price_e6(ili_value)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        
+        let authority_seeds = &[
+            PERCOLATOR_AUTHORITY_SEED,
+            &[ctx.bumps.percolator_push.authority],
+        ];
+        percolator_push_oracle_price(
+            &ctx.accounts.percolator_push,
+            price_e6,
+            &[&authority_seeds[..]],
+        )?;
+        
+        emit!(PercolatorOracleUpdate {
+            ili_value,
+            price_e6,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        
+        Ok(())
+    }
+
+    /// Execute trade on Percolator market
+    pub fn execute_percolator_trade(
+        ctx: Context<ExecutePercolatorTrade>,
+        user_idx: u16,
+        lp_idx: u16,
+        size: i128,
+    ) -> Result<()> {
+        let global_state = &ctx.accounts.global_state;
+        
+        require!(
+            ctx.accounts.authority.key() == global_state.authority,
+            ErrorCode::Unauthorized
+        );
+        require!(
+            !global_state.settlement_active,
+            ErrorCode::SettlementActive
+        );
+        require!(
+            !global_state.circuit_breaker_active,
+            ErrorCode::CircuitBreakerActive
+        );
+        require!(size != 0, ErrorCode::InvalidAmount);
+        
+        let authority_seeds = &[
+            PERCOLATOR_AUTHORITY_SEED,
+            &[ctx.bumps.percolator_trade.authority],
+        ];
+        percolator_trade_nocpi(
+            &ctx.accounts.percolator_trade,
+            user_idx,
+            lp_idx,
+            size,
+            &[&authority_seeds[..]],
+        )?;
+        
+        emit!(PercolatorTradeEvent {
+            agent: ctx.accounts.authority.key(),
+            user_idx,
+            lp_idx,
+            size,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        
+        Ok(())
+    }
 }
 
 #[derive(Accounts)]
@@ -410,6 +1011,23 @@ pub struct Initialize<'info> {
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+pub struct MigrateGlobalState<'info> {
+    /// CHECK: Legacy-layout global state, decoded manually in the handler
+    #[account(
+        mut,
+        seeds = [b"global_state"],
+        bump,
+        owner = crate::ID
+    )]
+    pub global_state: UncheckedAccount<'info>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
 #[derive(Accounts)]
 pub struct InitiateAdminTransfer<'info> {
     #[account(
@@ -472,11 +1090,19 @@ pub struct SubmitILIUpdate<'info> {
     pub global_state: Account<'info, GlobalState>,
     
     #[account(
+        mut,
         seeds = [b"agent", agent.key().as_ref()],
         bump = agent_registry.bump
     )]
     pub agent_registry: Account<'info, AgentRegistry>,
     
+    #[account(
+        mut,
+        seeds = [b"agent_rewards"],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Option<Account<'info, AgentRewardPool>>,
+    
     pub agent: Signer<'info>,
 }
 
@@ -506,6 +1132,12 @@ pub struct CreateProposal<'info> {
 
 #[derive(Accounts)]
 pub struct VoteOnProposal<'info> {
+    #[account(
+        seeds = [b"global_state"],
+        bump = global_state.bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    
     #[account(
         mut,
         seeds = [b"proposal", proposal.id.to_le_bytes().as_ref()],
@@ -519,7 +1151,35 @@ pub struct VoteOnProposal<'info> {
     )]
     pub agent_registry: Account<'info, AgentRegistry>,
     
+    /// Voter's ars-token stake checkpoints (quadratic stake proposals)
+    #[account(
+        seeds = [b"checkpoints", global_state.aru_mint_state.as_ref(), voter.key().as_ref()],
+        bump = voter_checkpoints.bump,
+        seeds::program = ars_token::ID
+    )]
+    pub voter_checkpoints: Option<Account<'info, BalanceCheckpoints>>,
+    
+    /// Voter's veARU lock (vote-escrow proposals)
+    #[account(
+        seeds = [b"ve_lock", global_state.aru_mint_state.as_ref(), voter.key().as_ref()],
+        bump = vote_escrow.bump,
+        seeds::program = ars_token::ID
+    )]
+    pub vote_escrow: Option<Account<'info, VoteEscrow>>,
+    
+    #[account(
+        init,
+        payer = voter,
+        space = VoteRecord::LEN,
+        seeds = [b"vote_record", proposal.id.to_le_bytes().as_ref(), voter.key().as_ref()],
+        bump
+    )]
+    pub vote_record: Account<'info, VoteRecord>,
+    
+    #[account(mut)]
     pub voter: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
@@ -558,48 +1218,217 @@ pub struct SlashAgent<'info> {
     pub authority: Signer<'info>,
 }
 
-    /// Allocate collateral from ARS to Percolator market
-    pub fn allocate_to_percolator(
-        _ctx: Context<AllocateToPercolator>,
-        _user_idx: u16,
-        _amount: u64,
-    ) -> Result<()> {
-        // TODO: Implement Percolator integration
-        // Temporarily disabled for build
-        Ok(())
-    }
+#[derive(Accounts)]
+pub struct InitiateGlobalSettlement<'info> {
+    #[account(
+        mut,
+        seeds = [b"global_state"],
+        bump = global_state.bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
     
-    /// Withdraw collateral from Percolator back to ARS
-    pub fn withdraw_from_percolator(
-        _ctx: Context<WithdrawFromPercolator>,
-        _user_idx: u16,
-        _amount: u64,
-    ) -> Result<()> {
-        // TODO: Implement Percolator integration
-        // Temporarily disabled for build
-        Ok(())
-    }
+    #[account(
+        mut,
+        address = global_state.aru_mint_state @ ErrorCode::InvalidAsset
+    )]
+    pub mint_state: Account<'info, MintState>,
     
-    /// Update Percolator oracle with ILI-derived price
-    pub fn update_percolator_oracle(
-        _ctx: Context<UpdatePercolatorOracle>,
-    ) -> Result<()> {
-        // TODO: Implement Percolator integration
-        // Temporarily disabled for build
-        Ok(())
-    }
+    #[account(
+        seeds = [b"ili_oracle"],
+        bump = ili_oracle.bump
+    )]
+    pub ili_oracle: Account<'info, ILIOracle>,
     
-    /// Execute trade on Percolator market
-    pub fn execute_percolator_trade(
-        _ctx: Context<ExecutePercolatorTrade>,
-        _user_idx: u16,
-        _lp_idx: u16,
-        _size: i128,
-    ) -> Result<()> {
-        // TODO: Implement Percolator integration
-        // Temporarily disabled for build
-        Ok(())
-    }
+    pub authority: Signer<'info>,
+    
+    pub ars_token_program: Program<'info, ArsToken>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteProposal<'info> {
+    #[account(
+        mut,
+        seeds = [b"global_state"],
+        bump = global_state.bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    
+    #[account(
+        mut,
+        seeds = [b"proposal", proposal.id.to_le_bytes().as_ref()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, PolicyProposal>,
+    
+    /// Required when the proposal changes the stability fee
+    #[account(address = global_state.aru_mint_state @ ErrorCode::InvalidPolicyParams)]
+    pub mint_state: Option<Account<'info, MintState>>,
+}
+
+#[derive(Accounts)]
+pub struct CollectStabilityFee<'info> {
+    #[account(
+        mut,
+        seeds = [b"global_state"],
+        bump = global_state.bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    
+    #[account(
+        mut,
+        address = global_state.aru_mint_state @ ErrorCode::InvalidAsset
+    )]
+    pub mint_state: Account<'info, MintState>,
+    
+    #[account(
+        mut,
+        address = global_state.aru_mint @ ErrorCode::InvalidAsset
+    )]
+    pub aru_mint: Account<'info, Mint>,
+    
+    /// Reserve-owned ARU account receiving the fees
+    #[account(
+        mut,
+        constraint = destination.owner == global_state.reserve_vault @ ErrorCode::Unauthorized,
+        constraint = destination.mint == aru_mint.key() @ ErrorCode::InvalidAsset
+    )]
+    pub destination: Account<'info, TokenAccount>,
+    
+    /// sARU savings vault ARU account; required while a savings share is set
+    #[account(
+        mut,
+        constraint = savings_destination.owner == Pubkey::find_program_address(
+            &[b"savings_vault", mint_state.key().as_ref()],
+            &ars_token::ID,
+        ).0 @ ErrorCode::Unauthorized,
+        constraint = savings_destination.mint == aru_mint.key() @ ErrorCode::InvalidAsset
+    )]
+    pub savings_destination: Option<Account<'info, TokenAccount>>,
+    
+    /// ars-token minter registered for the global state PDA
+    #[account(
+        mut,
+        seeds = [b"minter", mint_state.key().as_ref(), global_state.key().as_ref()],
+        bump = minter_account.bump,
+        seeds::program = ars_token::ID
+    )]
+    pub minter_account: Account<'info, Minter>,
+    
+    pub ars_token_program: Program<'info, ArsToken>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeAgentRewardPool<'info> {
+    #[account(
+        seeds = [b"global_state"],
+        bump = global_state.bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = AgentRewardPool::LEN,
+        seeds = [b"agent_rewards"],
+        bump
+    )]
+    pub reward_pool: Account<'info, AgentRewardPool>,
+    
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"agent_reward_vault"],
+        bump,
+        token::mint = aru_mint,
+        token::authority = reward_pool
+    )]
+    pub reward_vault: Account<'info, TokenAccount>,
+    
+    #[account(address = global_state.aru_mint @ ErrorCode::InvalidAsset)]
+    pub aru_mint: Account<'info, Mint>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct DistributeAgentRewards<'info> {
+    #[account(
+        seeds = [b"global_state"],
+        bump = global_state.bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    
+    #[account(
+        seeds = [b"ili_oracle"],
+        bump = ili_oracle.bump
+    )]
+    pub ili_oracle: Account<'info, ILIOracle>,
+    
+    #[account(
+        mut,
+        seeds = [b"agent_rewards"],
+        bump = reward_pool.bump,
+        has_one = reward_vault
+    )]
+    pub reward_pool: Account<'info, AgentRewardPool>,
+    
+    #[account(mut)]
+    pub reward_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        address = global_state.aru_mint_state @ ErrorCode::InvalidAsset
+    )]
+    pub mint_state: Account<'info, MintState>,
+    
+    #[account(mut)]
+    pub emission_schedule: Account<'info, EmissionSchedule>,
+    
+    #[account(
+        mut,
+        address = global_state.aru_mint @ ErrorCode::InvalidAsset
+    )]
+    pub aru_mint: Account<'info, Mint>,
+    
+    pub ars_token_program: Program<'info, ArsToken>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimAgentRewards<'info> {
+    #[account(
+        seeds = [b"agent_rewards"],
+        bump = reward_pool.bump,
+        has_one = reward_vault
+    )]
+    pub reward_pool: Account<'info, AgentRewardPool>,
+    
+    #[account(mut)]
+    pub reward_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"agent", agent.key().as_ref()],
+        bump = agent_registry.bump
+    )]
+    pub agent_registry: Account<'info, AgentRegistry>,
+    
+    #[account(
+        mut,
+        constraint = agent_token_account.owner == agent.key() @ ErrorCode::Unauthorized
+    )]
+    pub agent_token_account: Account<'info, TokenAccount>,
+    
+    pub agent: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+}
 
 // Percolator Integration Account Contexts
 
@@ -607,7 +1436,7 @@ pub struct SlashAgent<'info> {
 pub struct AllocateToPercolator<'info> {
     #[account(
         seeds = [b"global_state"],
-        bump
+        bump = global_state.bump
     )]
     pub global_state: Account<'info, GlobalState>,
     
@@ -620,7 +1449,7 @@ pub struct AllocateToPercolator<'info> {
 pub struct WithdrawFromPercolator<'info> {
     #[account(
         seeds = [b"global_state"],
-        bump
+        bump = global_state.bump
     )]
     pub global_state: Account<'info, GlobalState>,
     
@@ -633,13 +1462,13 @@ pub struct WithdrawFromPercolator<'info> {
 pub struct UpdatePercolatorOracle<'info> {
     #[account(
         seeds = [b"global_state"],
-        bump
+        bump = global_state.bump
     )]
     pub global_state: Account<'info, GlobalState>,
     
     #[account(
         seeds = [b"ili_oracle"],
-        bump
+        bump = ili_oracle.bump
     )]
     pub ili_oracle: Account<'info, ILIOracle>,
     
@@ -651,10 +1480,10 @@ pub struct UpdatePercolatorOracle<'info> {
 #[derive(Accounts)]
 pub struct ExecutePercolatorTrade<'info> {
     #[account(
-        seeds = [b"agent_registry", agent_registry.agent_pubkey.as_ref()],
-        bump
+        seeds = [b"global_state"],
+        bump = global_state.bump
     )]
-    pub agent_registry: Account<'info, AgentRegistry>,
+    pub global_state: Account<'info, GlobalState>,
     
     pub authority: Signer<'info>,
     
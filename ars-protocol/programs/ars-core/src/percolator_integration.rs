@@ -1,18 +1,38 @@
+//! Percolator integration module for ARS
+//!
+//! This module provides CPI interfaces to interact with Percolator perpetual futures markets.
+//! ARS can use Percolator for:
+//! - Oracle price feeds (ILI-derived prices)
+//! - Collateral allocation from reserve vault
+//! - Agent-operated liquidity provision
+//! - Governance-controlled risk parameters
+//!
+//! Every CPI is signed by the `PERCOLATOR_AUTHORITY_SEED` PDA, which owns the
+//! collateral ARS sends to Percolator and acts as the slab's oracle authority.
+
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
-use solana_program::{pubkey, pubkey::Pubkey as SolanaPubkey, instruction::{AccountMeta, Instruction}, program::invoke};
+use anchor_spl::token::{Token, TokenAccount};
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke_signed;
+use solana_program::pubkey;
+
+use crate::errors::ErrorCode;
+use crate::state::ILI_DECIMALS;
 
 /// Percolator program ID (devnet)
-pub const PERCOLATOR_PROGRAM_ID: SolanaPubkey = pubkey!("46iB4ET4WpqfTXAqGSmyBczLBgVhd1sHre93KtU3sTg9");
+pub const PERCOLATOR_PROGRAM_ID: Pubkey = pubkey!("46iB4ET4WpqfTXAqGSmyBczLBgVhd1sHre93KtU3sTg9");
+
+/// Decimals of Percolator oracle prices
+pub const PERCOLATOR_PRICE_DECIMALS: i32 = 6;
 
-/// Percolator integration module for ARS
-/// 
-/// This module provides CPI interfaces to interact with Percolator perpetual futures markets.
-/// ARS can use Percolator for:
-/// - Oracle price feeds (ILI-derived prices)
-/// - Collateral allocation from reserve vault
-/// - Agent-operated liquidity provision
-/// - Governance-controlled risk parameters
+/// Seed of the ars-core PDA that signs Percolator CPIs
+pub const PERCOLATOR_AUTHORITY_SEED: &[u8] = b"percolator_authority";
+
+/// Percolator instruction tags
+pub const DEPOSIT_COLLATERAL_TAG: u8 = 3;
+pub const WITHDRAW_COLLATERAL_TAG: u8 = 4;
+pub const TRADE_NO_CPI_TAG: u8 = 5;
+pub const PUSH_ORACLE_PRICE_TAG: u8 = 14;
 
 #[derive(Accounts)]
 pub struct PercolatorDeposit<'info> {
@@ -22,14 +42,22 @@ pub struct PercolatorDeposit<'info> {
     pub slab: AccountInfo<'info>,
     
     /// Percolator vault token account
-    #[account(mut)]
+    #[account(
+        mut,
+        constraint = vault.mint == ars_token_account.mint @ ErrorCode::InvalidPercolatorSlab
+    )]
     pub vault: Account<'info, TokenAccount>,
     
-    /// ARS authority (signer)
-    pub authority: Signer<'info>,
+    /// ARS Percolator authority PDA, signs via invoke_signed
+    /// CHECK: Seeds checked; holds no data
+    #[account(seeds = [PERCOLATOR_AUTHORITY_SEED], bump)]
+    pub authority: AccountInfo<'info>,
     
     /// ARS token account (source)
-    #[account(mut)]
+    #[account(
+        mut,
+        constraint = ars_token_account.owner == authority.key() @ ErrorCode::Unauthorized
+    )]
     pub ars_token_account: Account<'info, TokenAccount>,
     
     /// Token program
@@ -37,6 +65,7 @@ pub struct PercolatorDeposit<'info> {
     
     /// Percolator program
     /// CHECK: Validated against PERCOLATOR_PROGRAM_ID
+    #[account(address = PERCOLATOR_PROGRAM_ID @ ErrorCode::InvalidPercolatorProgram)]
     pub percolator_program: AccountInfo<'info>,
 }
 
@@ -48,18 +77,27 @@ pub struct PercolatorWithdraw<'info> {
     pub slab: AccountInfo<'info>,
     
     /// Percolator vault token account
-    #[account(mut)]
+    #[account(
+        mut,
+        constraint = vault.mint == ars_token_account.mint @ ErrorCode::InvalidPercolatorSlab
+    )]
     pub vault: Account<'info, TokenAccount>,
     
     /// Vault authority PDA
-    /// CHECK: Derived by Percolator program
+    /// CHECK: Derived from the slab under the Percolator program
+    #[account(address = derive_vault_authority_pda(&slab.key()).0 @ ErrorCode::InvalidPercolatorSlab)]
     pub vault_authority: AccountInfo<'info>,
     
-    /// ARS authority (signer)
-    pub authority: Signer<'info>,
+    /// ARS Percolator authority PDA, signs via invoke_signed
+    /// CHECK: Seeds checked; holds no data
+    #[account(seeds = [PERCOLATOR_AUTHORITY_SEED], bump)]
+    pub authority: AccountInfo<'info>,
     
     /// ARS token account (destination)
-    #[account(mut)]
+    #[account(
+        mut,
+        constraint = ars_token_account.owner == authority.key() @ ErrorCode::Unauthorized
+    )]
     pub ars_token_account: Account<'info, TokenAccount>,
     
     /// Oracle account
@@ -71,6 +109,7 @@ pub struct PercolatorWithdraw<'info> {
     
     /// Percolator program
     /// CHECK: Validated against PERCOLATOR_PROGRAM_ID
+    #[account(address = PERCOLATOR_PROGRAM_ID @ ErrorCode::InvalidPercolatorProgram)]
     pub percolator_program: AccountInfo<'info>,
 }
 
@@ -85,11 +124,14 @@ pub struct PercolatorTrade<'info> {
     /// CHECK: Validated by Percolator program
     pub oracle: AccountInfo<'info>,
     
-    /// ARS authority (signer)
-    pub authority: Signer<'info>,
+    /// ARS Percolator authority PDA, signs via invoke_signed
+    /// CHECK: Seeds checked; holds no data
+    #[account(seeds = [PERCOLATOR_AUTHORITY_SEED], bump)]
+    pub authority: AccountInfo<'info>,
     
     /// Percolator program
     /// CHECK: Validated against PERCOLATOR_PROGRAM_ID
+    #[account(address = PERCOLATOR_PROGRAM_ID @ ErrorCode::InvalidPercolatorProgram)]
     pub percolator_program: AccountInfo<'info>,
 }
 
@@ -100,225 +142,174 @@ pub struct PercolatorPushPrice<'info> {
     #[account(mut)]
     pub slab: AccountInfo<'info>,
     
-    /// Oracle authority (must match slab's oracle_authority)
-    pub authority: Signer<'info>,
+    /// ARS Percolator authority PDA, must match the slab's oracle_authority
+    /// CHECK: Seeds checked; holds no data
+    #[account(seeds = [PERCOLATOR_AUTHORITY_SEED], bump)]
+    pub authority: AccountInfo<'info>,
     
     /// Percolator program
     /// CHECK: Validated against PERCOLATOR_PROGRAM_ID
+    #[account(address = PERCOLATOR_PROGRAM_ID @ ErrorCode::InvalidPercolatorProgram)]
     pub percolator_program: AccountInfo<'info>,
 }
 
-/// CPI helper functions for Percolator integration
+// CPI helper functions for Percolator integration, signed by the authority PDA
 
-/// Deposit collateral to Percolator vault
+/// Deposit collateral to Percolator vault; Percolator moves the tokens itself
 pub fn percolator_deposit_collateral(
-    ctx: Context<PercolatorDeposit>,
+    accounts: &PercolatorDeposit,
     user_idx: u16,
     amount: u64,
+    signer_seeds: &[&[&[u8]]],
 ) -> Result<()> {
-    let perc_id: Pubkey = PERCOLATOR_PROGRAM_ID.into();
-    require!(
-        ctx.accounts.percolator_program.key() == perc_id,
-        crate::errors::ErrorCode::InvalidPercolatorProgram
-    );
-    
-    // Transfer tokens from ARS to Percolator vault
-    let cpi_accounts = Transfer {
-        from: ctx.accounts.ars_token_account.to_account_info(),
-        to: ctx.accounts.vault.to_account_info(),
-        authority: ctx.accounts.authority.to_account_info(),
-    };
-    let cpi_program = ctx.accounts.token_program.to_account_info();
-    let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-    token::transfer(cpi_ctx, amount)?;
-    
-    // Build Percolator deposit instruction data
     // Instruction format: [tag: u8, user_idx: u16, amount: u64]
     let mut data = Vec::with_capacity(11);
-    data.push(3); // DepositCollateral instruction tag
+    data.push(DEPOSIT_COLLATERAL_TAG);
     data.extend_from_slice(&user_idx.to_le_bytes());
     data.extend_from_slice(&amount.to_le_bytes());
     
-    // CPI to Percolator
-    let accounts = vec![
-        ctx.accounts.slab.to_account_info(),
-        ctx.accounts.vault.to_account_info(),
-        ctx.accounts.ars_token_account.to_account_info(),
-        ctx.accounts.authority.to_account_info(),
-        ctx.accounts.token_program.to_account_info(),
-    ];
-    
-    invoke(
-        &Instruction {
-            program_id: *ctx.accounts.percolator_program.key,
-            accounts: accounts.iter().map(|a| AccountMeta {
-                pubkey: *a.key,
-                is_signer: a.is_signer,
-                is_writable: a.is_writable,
-            }).collect(),
-            data,
-        },
-        &accounts,
-    )?;
-    
-    Ok(())
+    invoke_percolator(
+        &accounts.percolator_program,
+        accounts.authority.key,
+        &[
+            accounts.slab.to_account_info(),
+            accounts.vault.to_account_info(),
+            accounts.ars_token_account.to_account_info(),
+            accounts.authority.to_account_info(),
+            accounts.token_program.to_account_info(),
+        ],
+        data,
+        signer_seeds,
+    )
 }
 
 /// Withdraw collateral from Percolator vault
 pub fn percolator_withdraw_collateral(
-    ctx: Context<PercolatorWithdraw>,
+    accounts: &PercolatorWithdraw,
     user_idx: u16,
     amount: u64,
+    signer_seeds: &[&[&[u8]]],
 ) -> Result<()> {
-    let perc_id: Pubkey = PERCOLATOR_PROGRAM_ID.into();
-    require!(
-        ctx.accounts.percolator_program.key() == perc_id,
-        crate::errors::ErrorCode::InvalidPercolatorProgram
-    );
-    
-    // Build Percolator withdraw instruction data
     // Instruction format: [tag: u8, user_idx: u16, amount: u64]
     let mut data = Vec::with_capacity(11);
-    data.push(4); // WithdrawCollateral instruction tag
+    data.push(WITHDRAW_COLLATERAL_TAG);
     data.extend_from_slice(&user_idx.to_le_bytes());
     data.extend_from_slice(&amount.to_le_bytes());
     
-    // CPI to Percolator
-    let accounts = vec![
-        ctx.accounts.slab.to_account_info(),
-        ctx.accounts.vault.to_account_info(),
-        ctx.accounts.vault_authority.to_account_info(),
-        ctx.accounts.ars_token_account.to_account_info(),
-        ctx.accounts.oracle.to_account_info(),
-        ctx.accounts.authority.to_account_info(),
-        ctx.accounts.token_program.to_account_info(),
-    ];
-    
-    invoke(
-        &Instruction {
-            program_id: *ctx.accounts.percolator_program.key,
-            accounts: accounts.iter().map(|a| AccountMeta {
-                pubkey: *a.key,
-                is_signer: a.is_signer,
-                is_writable: a.is_writable,
-            }).collect(),
-            data,
-        },
-        &accounts,
-    )?;
-    
-    Ok(())
+    invoke_percolator(
+        &accounts.percolator_program,
+        accounts.authority.key,
+        &[
+            accounts.slab.to_account_info(),
+            accounts.vault.to_account_info(),
+            accounts.vault_authority.to_account_info(),
+            accounts.ars_token_account.to_account_info(),
+            accounts.oracle.to_account_info(),
+            accounts.authority.to_account_info(),
+            accounts.token_program.to_account_info(),
+        ],
+        data,
+        signer_seeds,
+    )
 }
 
 /// Execute trade on Percolator (no CPI to matcher)
 pub fn percolator_trade_nocpi(
-    ctx: Context<PercolatorTrade>,
+    accounts: &PercolatorTrade,
     user_idx: u16,
     lp_idx: u16,
     size: i128,
+    signer_seeds: &[&[&[u8]]],
 ) -> Result<()> {
-    let perc_id: Pubkey = PERCOLATOR_PROGRAM_ID.into();
-    require!(
-        ctx.accounts.percolator_program.key() == perc_id,
-        crate::errors::ErrorCode::InvalidPercolatorProgram
-    );
-    
-    // Build Percolator trade instruction data
     // Instruction format: [tag: u8, user_idx: u16, lp_idx: u16, size: i128]
     let mut data = Vec::with_capacity(21);
-    data.push(5); // TradeNoCpi instruction tag
+    data.push(TRADE_NO_CPI_TAG);
     data.extend_from_slice(&user_idx.to_le_bytes());
     data.extend_from_slice(&lp_idx.to_le_bytes());
     data.extend_from_slice(&size.to_le_bytes());
     
-    // CPI to Percolator
-    let accounts = vec![
-        ctx.accounts.slab.to_account_info(),
-        ctx.accounts.oracle.to_account_info(),
-        ctx.accounts.authority.to_account_info(),
-    ];
-    
-    invoke(
-        &Instruction {
-            program_id: *ctx.accounts.percolator_program.key,
-            accounts: accounts.iter().map(|a| AccountMeta {
-                pubkey: *a.key,
-                is_signer: a.is_signer,
-                is_writable: a.is_writable,
-            }).collect(),
-            data,
-        },
-        &accounts,
-    )?;
-    
-    Ok(())
+    invoke_percolator(
+        &accounts.percolator_program,
+        accounts.authority.key,
+        &[
+            accounts.slab.to_account_info(),
+            accounts.oracle.to_account_info(),
+            accounts.authority.to_account_info(),
+        ],
+        data,
+        signer_seeds,
+    )
 }
 
 /// Push oracle price to Percolator (oracle authority only)
 pub fn percolator_push_oracle_price(
-    ctx: Context<PercolatorPushPrice>,
-    price_usd: u64,
+    accounts: &PercolatorPushPrice,
+    price_e6: u64,
+    signer_seeds: &[&[&[u8]]],
 ) -> Result<()> {
-    let perc_id: Pubkey = PERCOLATOR_PROGRAM_ID.into();
-    require!(
-        ctx.accounts.percolator_program.key() == perc_id,
-        crate::errors::ErrorCode::InvalidPercolatorProgram
-    );
-    
-    // Build Percolator push price instruction data
     // Instruction format: [tag: u8, price_e6: u64]
     let mut data = Vec::with_capacity(9);
-    data.push(14); // PushOraclePrice instruction tag
-    
-    // Convert USD price to e6 format (price * 1_000_000)
-    let price_e6 = price_usd.checked_mul(1_000_000)
-        .ok_or(crate::errors::ErrorCode::Overflow)?;
+    data.push(PUSH_ORACLE_PRICE_TAG);
     data.extend_from_slice(&price_e6.to_le_bytes());
     
-    // CPI to Percolator
-    let accounts = vec![
-        ctx.accounts.slab.to_account_info(),
-        ctx.accounts.authority.to_account_info(),
-    ];
-    
-    invoke(
-        &Instruction {
-            program_id: *ctx.accounts.percolator_program.key,
-            accounts: accounts.iter().map(|a| AccountMeta {
-                pubkey: *a.key,
-                is_signer: a.is_signer,
-                is_writable: a.is_writable,
-            }).collect(),
-            data,
-        },
-        &accounts,
-    )?;
-    
-    Ok(())
+    invoke_percolator(
+        &accounts.percolator_program,
+        accounts.authority.key,
+        &[
+            accounts.slab.to_account_info(),
+            accounts.authority.to_account_info(),
+        ],
+        data,
+        signer_seeds,
+    )
 }
 
 /// Helper: Convert ILI value to Percolator price format (e6)
-pub fn ili_to_price_e6(ili_value: u64) -> u64 {
-    // ILI is typically in basis points (10000 = 100%)
-    // Convert to price per unit (e.g., if ILI = 10500, price = 1.05)
-    // Then scale to e6 format
-    ili_value.saturating_mul(100) // 10500 * 100 = 1_050_000 (1.05 in e6)
+///
+/// The ILI is published with `ILI_DECIMALS` (4) decimals while Percolator
+/// quotes 6-decimal prices, so an ILI of 10_500 (1.05) becomes 1_050_000.
+/// Returns `None` on overflow.
+pub fn ili_to_price_e6(ili_value: u64) -> Option<u64> {
+    ili_value.checked_mul(10u64.pow((PERCOLATOR_PRICE_DECIMALS - ILI_DECIMALS) as u32))
 }
 
 /// Helper: Derive Percolator vault authority PDA
 pub fn derive_vault_authority_pda(slab: &Pubkey) -> (Pubkey, u8) {
-    let perc_id: Pubkey = PERCOLATOR_PROGRAM_ID.into();
     Pubkey::find_program_address(
         &[b"vault", slab.as_ref()],
-        &perc_id,
+        &PERCOLATOR_PROGRAM_ID,
     )
 }
 
 /// Helper: Derive Percolator LP PDA
 pub fn derive_lp_pda(slab: &Pubkey, lp_idx: u16) -> (Pubkey, u8) {
-    let perc_id: Pubkey = PERCOLATOR_PROGRAM_ID.into();
     Pubkey::find_program_address(
         &[b"lp", slab.as_ref(), &lp_idx.to_le_bytes()],
-        &perc_id,
+        &PERCOLATOR_PROGRAM_ID,
     )
 }
+
+/// CPI to Percolator, passing writability through and marking the authority PDA as signer
+fn invoke_percolator<'info>(
+    percolator_program: &AccountInfo<'info>,
+    authority: &Pubkey,
+    accounts: &[AccountInfo<'info>],
+    data: Vec<u8>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let instruction = Instruction {
+        program_id: *percolator_program.key,
+        accounts: accounts.iter().map(|a| AccountMeta {
+            pubkey: *a.key,
+            is_signer: a.is_signer || a.key == authority,
+            is_writable: a.is_writable,
+        }).collect(),
+        data,
+    };
+    
+    let mut account_infos = accounts.to_vec();
+    account_infos.push(percolator_program.clone());
+    
+    invoke_signed(&instruction, &account_infos, signer_seeds)
+        .map_err(|_| error!(ErrorCode::PercolatorCPIFailed))
+}
@@ -1,4 +1,14 @@
 use anchor_lang::prelude::*;
+use crate::errors::ErrorCode;
+
+/// Fixed-point scale for the cumulative stability fee index (1.0 = 1e12)
+pub const STABILITY_FEE_INDEX_SCALE: u128 = 1_000_000_000_000;
+
+/// Seconds per year used to annualize basis-point rates
+pub const SECONDS_PER_YEAR: i64 = 31_536_000;
+
+/// The ILI is published as 4-decimal fixed point (10500 = 1.05)
+pub const ILI_DECIMALS: i32 = 4;
 
 /// Global state for the ARS protocol with admin transfer and circuit breaker
 #[account]
@@ -21,8 +31,9 @@ pub struct GlobalState {
     pub mint_burn_cap_bps: u16,
     /// Stability fee in basis points
     pub stability_fee_bps: u16,
-    /// VHR threshold in basis points
-    pub vhr_threshold: u16,
+    /// Minimum healthy VHR in basis points (at least 10000 = 100%).
+    /// Legacy accounts stored this as u16; `migrate_global_state` widens it.
+    pub vhr_threshold: u64,
     /// Circuit breaker active flag
     pub circuit_breaker_active: bool,
     /// Circuit breaker timelock (24 hours)
@@ -35,10 +46,126 @@ pub struct GlobalState {
     pub last_update_slot: u64,
     /// PDA bump
     pub bump: u8,
+    /// Canonical ars-token MintState PDA for `aru_mint`
+    pub aru_mint_state: Pubkey,
+    /// Cumulative stability fee index (scaled by STABILITY_FEE_INDEX_SCALE)
+    pub stability_fee_index: u128,
+    /// Timestamp of the last stability fee accrual
+    pub last_fee_accrual: i64,
+    /// Stability fees accrued but not yet minted
+    pub accrued_stability_fees: u64,
+    /// Share of collected stability fees routed to the sARU savings vault (bps)
+    pub savings_fee_share_bps: u16,
+    /// Global settlement started; minting and governance are frozen for good
+    pub settlement_active: bool,
+    /// Timestamp global settlement was initiated
+    pub settlement_started_at: i64,
+    /// ILI frozen at global settlement
+    pub settlement_ili: u64,
 }
 
 impl GlobalState {
+    /// Compare a full-width VHR against the threshold
+    pub fn vhr_below_threshold(&self, vhr: u64) -> bool {
+        vhr < self.vhr_threshold
+    }
+
     /// Calculate space needed for GlobalState account
+    pub const LEN: usize = 8 + // discriminator
+        32 + // authority
+        (1 + 32) + // pending_authority (Option<Pubkey>)
+        8 + // transfer_timelock
+        32 + // ili_oracle
+        32 + // reserve_vault
+        32 + // aru_mint
+        8 + // epoch_duration
+        2 + // mint_burn_cap_bps
+        2 + // stability_fee_bps
+        8 + // vhr_threshold
+        1 + // circuit_breaker_active
+        8 + // circuit_breaker_timelock
+        1 + // min_agent_consensus
+        8 + // proposal_counter
+        8 + // last_update_slot
+        1 + // bump
+        32 + // aru_mint_state
+        16 + // stability_fee_index
+        8 + // last_fee_accrual
+        8 + // accrued_stability_fees
+        2 + // savings_fee_share_bps
+        1 + // settlement_active
+        8 + // settlement_started_at
+        8; // settlement_ili
+
+    /// Accrue the stability fee on `total_supply` up to `now`.
+    /// Advances the cumulative index and returns the newly accrued amount.
+    pub fn accrue_stability_fee(&mut self, total_supply: u64, now: i64) -> Result<u64> {
+        let elapsed = now.saturating_sub(self.last_fee_accrual);
+        if elapsed <= 0 {
+            return Ok(0);
+        }
+        self.last_fee_accrual = now;
+
+        if self.stability_fee_bps == 0 {
+            return Ok(0);
+        }
+
+        let denominator = 10_000u128
+            .checked_mul(SECONDS_PER_YEAR as u128)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+        let index_delta = self.stability_fee_index
+            .checked_mul(self.stability_fee_bps as u128)
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            .checked_mul(elapsed as u128)
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            .checked_div(denominator)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        self.stability_fee_index = self.stability_fee_index
+            .checked_add(index_delta)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+        let accrued = (total_supply as u128)
+            .checked_mul(self.stability_fee_bps as u128)
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            .checked_mul(elapsed as u128)
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            .checked_div(denominator)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        let accrued = u64::try_from(accrued).map_err(|_| ErrorCode::ArithmeticOverflow)?;
+
+        self.accrued_stability_fees = self.accrued_stability_fees
+            .checked_add(accrued)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+        Ok(accrued)
+    }
+}
+
+/// `GlobalState` layout before the mint-state, stability fee and settlement
+/// fields were appended and `vhr_threshold` was widened to u64, read by
+/// `migrate_global_state`
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct LegacyGlobalState {
+    pub authority: Pubkey,
+    pub pending_authority: Option<Pubkey>,
+    pub transfer_timelock: i64,
+    pub ili_oracle: Pubkey,
+    pub reserve_vault: Pubkey,
+    pub aru_mint: Pubkey,
+    pub epoch_duration: i64,
+    pub mint_burn_cap_bps: u16,
+    pub stability_fee_bps: u16,
+    pub vhr_threshold: u16,
+    pub circuit_breaker_active: bool,
+    pub circuit_breaker_timelock: i64,
+    pub min_agent_consensus: u8,
+    pub proposal_counter: u64,
+    pub last_update_slot: u64,
+    pub bump: u8,
+}
+
+impl LegacyGlobalState {
     pub const LEN: usize = 8 + // discriminator
         32 + // authority
         (1 + 32) + // pending_authority (Option<Pubkey>)
@@ -113,6 +240,10 @@ pub struct AgentRegistry {
     pub last_active: i64,
     /// Active status flag
     pub is_active: bool,
+    /// ILI contributor rewards credited but not yet claimed
+    pub pending_rewards: u64,
+    /// Timestamp of the last rewarded ILI update
+    pub last_rewarded_at: i64,
     /// PDA bump
     pub bump: u8,
 }
@@ -130,6 +261,36 @@ impl AgentRegistry {
         8 + // registered_at
         8 + // last_active
         1 + // is_active
+        8 + // pending_rewards
+        8 + // last_rewarded_at
+        1; // bump
+}
+
+/// Pool of ars-token agent emissions paid out to ILI contributors
+#[account]
+pub struct AgentRewardPool {
+    /// ARU token account holding emitted rewards (owned by this PDA)
+    pub reward_vault: Pubkey,
+    /// Reward credited per accepted ILI update
+    pub reward_per_update: u64,
+    /// Emitted rewards not yet credited to any agent
+    pub unallocated: u64,
+    /// Total rewards received from emissions
+    pub total_received: u64,
+    /// Total rewards credited to agents
+    pub total_credited: u64,
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl AgentRewardPool {
+    /// Calculate space needed for AgentRewardPool account
+    pub const LEN: usize = 8 + // discriminator
+        32 + // reward_vault
+        8 + // reward_per_update
+        8 + // unallocated
+        8 + // total_received
+        8 + // total_credited
         1; // bump
 }
 
@@ -158,7 +319,7 @@ impl ILIPendingUpdate {
 pub struct ILIOracle {
     /// Authority (global state)
     pub authority: Pubkey,
-    /// Current ILI value
+    /// Current ILI value (ILI_DECIMALS fixed point)
     pub current_ili: u64,
     /// Last update timestamp
     pub last_update: i64,
@@ -198,6 +359,24 @@ pub enum PolicyType {
     RebalanceVault,
 }
 
+/// Parameter change carried (Borsh-encoded) in an `UpdateParameters` proposal
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ParameterUpdate {
+    /// Set the annual stability fee in basis points
+    StabilityFeeBps(u16),
+    /// Set the share of stability fees that funds the savings vault
+    SavingsFeeShareBps(u16),
+}
+
+/// Source of voting power for a proposal
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum VotingPowerSource {
+    /// Square root of the voter's checkpointed ars-token stake
+    QuadraticStake,
+    /// Voter's veARU weight at proposal start
+    VoteEscrow,
+}
+
 /// Proposal status
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
 pub enum ProposalStatus {
@@ -230,9 +409,9 @@ pub struct PolicyProposal {
     pub yes_stake: u64,
     /// Total stake voting no
     pub no_stake: u64,
-    /// Quadratic voting power for yes (sqrt of yes_stake)
+    /// Voting power for yes (sqrt of stake, or veARU weight)
     pub quadratic_yes: u64,
-    /// Quadratic voting power for no (sqrt of no_stake)
+    /// Voting power for no (sqrt of stake, or veARU weight)
     pub quadratic_no: u64,
     /// Proposal status
     pub status: ProposalStatus,
@@ -240,6 +419,10 @@ pub struct PolicyProposal {
     pub execution_tx: Option<[u8; 64]>,
     /// Griefing protection deposit (minimum 10 ARU)
     pub griefing_protection_deposit: u64,
+    /// Slot whose checkpointed balances determine voting power
+    pub snapshot_slot: u64,
+    /// Where voting power is read from
+    pub voting_power_source: VotingPowerSource,
     /// PDA bump
     pub bump: u8,
 }
@@ -261,5 +444,35 @@ impl PolicyProposal {
         1 + // status (enum)
         (1 + 64) + // execution_tx (Option<[u8; 64]>)
         8 + // griefing_protection_deposit
+        8 + // snapshot_slot
+        1 + // voting_power_source (enum)
+        1; // bump
+}
+
+/// Record of a single voter's ballot on a proposal (prevents double voting)
+#[account]
+pub struct VoteRecord {
+    /// Proposal voted on
+    pub proposal_id: u64,
+    /// Voter's public key
+    pub voter: Pubkey,
+    /// Vote direction
+    pub vote_yes: bool,
+    /// Checkpointed stake counted for this vote
+    pub stake_amount: u64,
+    /// Quadratic voting power applied
+    pub voting_power: u64,
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl VoteRecord {
+    /// Calculate space needed for VoteRecord account
+    pub const LEN: usize = 8 + // discriminator
+        8 + // proposal_id
+        32 + // voter
+        1 + // vote_yes
+        8 + // stake_amount
+        8 + // voting_power
         1; // bump
 }
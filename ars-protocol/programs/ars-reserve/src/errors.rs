@@ -22,4 +22,193 @@ pub enum ErrorCode {
     
     #[msg("Rebalance not needed")]
     RebalanceNotNeeded,
+    
+    #[msg("Unauthorized")]
+    Unauthorized,
+    
+    #[msg("Invalid asset weights")]
+    InvalidWeights,
+    
+    #[msg("Total target weight exceeds 100%")]
+    TargetWeightExceeded,
+    
+    #[msg("Asset registry is full")]
+    TooManyAssets,
+    
+    #[msg("Asset not found in registry")]
+    AssetNotFound,
+    
+    #[msg("Asset still holds a balance")]
+    AssetNotEmpty,
+    
+    #[msg("Token account does not match asset")]
+    InvalidAssetVault,
+    
+    #[msg("Invalid asset haircut")]
+    InvalidHaircut,
+    
+    #[msg("Invalid or unsupported oracle account")]
+    InvalidOracle,
+    
+    #[msg("Oracle price is stale")]
+    StalePrice,
+    
+    #[msg("Oracle price is invalid")]
+    InvalidPrice,
+    
+    #[msg("Oracle confidence interval too wide")]
+    PriceConfidenceTooWide,
+    
+    #[msg("Remaining accounts do not match the asset registry")]
+    AssetListMismatch,
+    
+    #[msg("Liability source does not match the vault")]
+    InvalidLiabilitySource,
+    
+    #[msg("ILI price unavailable while ARU is outstanding")]
+    InvalidILIPrice,
+    
+    #[msg("Vault already uses the current layout")]
+    AlreadyMigrated,
+    
+    #[msg("Account is not a reserve vault")]
+    InvalidVaultAccount,
+    
+    #[msg("Invalid slippage limit")]
+    InvalidSlippage,
+    
+    #[msg("Rebalance legs must be distinct assets")]
+    InvalidRebalanceLeg,
+    
+    #[msg("Swap program does not match the rebalance config")]
+    InvalidSwapAdapter,
+    
+    #[msg("Swap output below minimum amount")]
+    SlippageExceeded,
+    
+    #[msg("Swap spent more than the requested input")]
+    ExcessiveSwapInput,
+    
+    #[msg("Asset weight outside its min/max bounds")]
+    WeightOutOfBounds,
+    
+    #[msg("Invalid fee")]
+    InvalidFee,
+    
+    #[msg("Epoch redemption cap exceeded")]
+    RedemptionCapExceeded,
+    
+    #[msg("PSM debt ceiling exceeded")]
+    DebtCeilingExceeded,
+    
+    #[msg("Amount exceeds ARU minted through the PSM")]
+    PsmDebtExceeded,
+    
+    #[msg("Position still holds a balance")]
+    PositionNotEmpty,
+    
+    #[msg("Withdrawals must be paid to the position owner")]
+    InvalidRecipient,
+    
+    #[msg("Epoch outflow cap exceeded")]
+    OutflowCapExceeded,
+    
+    #[msg("Queued withdrawals must be processed first")]
+    WithdrawalQueueNotEmpty,
+    
+    #[msg("Withdrawal queue is full")]
+    WithdrawalQueueFull,
+    
+    #[msg("Owner has too many queued withdrawals")]
+    TooManyQueuedWithdrawals,
+    
+    #[msg("Queued withdrawal is below the minimum size")]
+    WithdrawalTooSmall,
+    
+    #[msg("Withdrawal fits within the epoch outflow cap")]
+    WithinOutflowCap,
+    
+    #[msg("Request is not at the head of the queue")]
+    NotNextInQueue,
+    
+    #[msg("Queued withdrawals are processed from the next epoch")]
+    WithdrawalNotReady,
+    
+    #[msg("Stake pool account does not match the asset")]
+    InvalidStakePool,
+    
+    #[msg("No yield accrued")]
+    NoYieldAccrued,
+    
+    #[msg("VHR is not below the protocol threshold")]
+    NoShortfall,
+    
+    #[msg("No new stability fees to route")]
+    NoNewFees,
+    
+    #[msg("Global settlement has not been initiated")]
+    SettlementNotActive,
+    
+    #[msg("Reserve is in global settlement")]
+    ReserveSettled,
+    
+    #[msg("Recapitalization auction is already running")]
+    RecapAuctionActive,
+    
+    #[msg("No recapitalization auction is running")]
+    RecapAuctionInactive,
+    
+    #[msg("Auction has time left and VHR is still below threshold")]
+    RecapAuctionLive,
+    
+    #[msg("Bid exceeds the face value left in this round")]
+    RecapRoundCapExceeded,
+    
+    #[msg("Recapitalization bond has not matured")]
+    BondNotMature,
+    
+    #[msg("Bond redemption would leave VHR below the protocol threshold")]
+    BondRedemptionBlocked,
+    
+    #[msg("A flash loan is already outstanding")]
+    FlashLoanActive,
+    
+    #[msg("No flash loan is outstanding")]
+    NoFlashLoan,
+    
+    #[msg("Flash loan exceeds the asset exposure limit")]
+    FlashLoanLimitExceeded,
+    
+    #[msg("Flash loans must be top-level instructions")]
+    FlashLoanCpiForbidden,
+    
+    #[msg("Flash loan is not repaid later in the transaction")]
+    FlashLoanNotRepaid,
+    
+    #[msg("Reserve attestations must be top-level instructions")]
+    AttestationCpiForbidden,
+    
+    #[msg("Reserves cannot be attested in a transaction that takes a flash loan")]
+    AttestationDuringFlashLoan,
+    
+    #[msg("Strategy deployment cap exceeded")]
+    StrategyCapExceeded,
+    
+    #[msg("Strategy was unwound")]
+    StrategyUnwound,
+    
+    #[msg("Adapter accounts do not match the strategy")]
+    StrategyAccountsMismatch,
+    
+    #[msg("Strategy adapter did not report a value")]
+    InvalidStrategyReport,
+    
+    #[msg("Strategy funds must be withdrawn before settlement")]
+    StrategiesDeployed,
+    
+    #[msg("Not enough of the asset is held outside strategies")]
+    InsufficientIdleBalance,
+    
+    #[msg("Fee recipient does not match the flow fee schedule")]
+    InvalidFeeRecipient,
 }
@@ -0,0 +1,174 @@
+use anchor_lang::prelude::*;
+use crate::state::{FeeDestination, InsuranceSource};
+
+#[event]
+pub struct InsuranceFunded {
+    pub reserve_vault: Pubkey,
+    pub source: InsuranceSource,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub value_usd: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ShortfallCovered {
+    pub reserve_vault: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub value_usd: u64,
+    pub vhr_before: u64,
+    pub vhr_after: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct InsuranceFundReported {
+    pub reserve_vault: Pubkey,
+    pub value_usd: u64,
+    pub slash_inflows_usd: u64,
+    pub redemption_fee_inflows_usd: u64,
+    pub stability_fee_inflows_usd: u64,
+    pub total_covered_usd: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ReserveAttested {
+    pub reserve_vault: Pubkey,
+    pub epoch: u64,
+    pub hash: [u8; 32],
+    pub total_value_usd: u64,
+    pub liabilities_usd: u64,
+    pub vhr: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SettlementInitiated {
+    pub reserve_vault: Pubkey,
+    pub aru_supply: u64,
+    pub total_value_usd: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SettlementClaimed {
+    pub reserve_vault: Pubkey,
+    pub claimant: Pubkey,
+    pub aru_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SettledPositionExited {
+    pub reserve_vault: Pubkey,
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RecapAuctionStarted {
+    pub reserve_vault: Pubkey,
+    pub round: u64,
+    pub vhr: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RecapBondPurchased {
+    pub reserve_vault: Pubkey,
+    pub round: u64,
+    pub bond: Pubkey,
+    pub owner: Pubkey,
+    pub face_aru: u64,
+    pub discount_bps: u16,
+    pub amount_in: u64,
+    pub value_usd: u64,
+    pub vhr_after: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RecapAuctionEnded {
+    pub reserve_vault: Pubkey,
+    pub round: u64,
+    pub face_sold: u64,
+    pub vhr: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RecapBondRedeemed {
+    pub reserve_vault: Pubkey,
+    pub bond: Pubkey,
+    pub owner: Pubkey,
+    pub face_aru: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FlashLoanRepaid {
+    pub reserve_vault: Pubkey,
+    pub mint: Pubkey,
+    pub borrower: Pubkey,
+    pub amount: u64,
+    pub fee: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct StrategyRebalanced {
+    pub strategy: Pubkey,
+    pub mint: Pubkey,
+    pub deposited: u64,
+    pub withdrawn: u64,
+    pub deployed: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct StrategyReported {
+    pub strategy: Pubkey,
+    pub mint: Pubkey,
+    pub value: u64,
+    pub gain: u64,
+    pub loss: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct StrategyUnwound {
+    pub strategy: Pubkey,
+    pub mint: Pubkey,
+    pub recovered: u64,
+    pub loss: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct NavUpdated {
+    pub reserve_vault: Pubkey,
+    pub total_value_usd: u64,
+    pub liabilities_usd: u64,
+    pub aru_supply: u64,
+    pub nav_per_aru: u64,
+    pub vhr: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FlowFeeCharged {
+    pub reserve_vault: Pubkey,
+    pub mint: Pubkey,
+    pub user: Pubkey,
+    pub deposit: bool,
+    pub amount: u64,
+    pub fee: u64,
+    pub fee_bps: u16,
+    pub weight_bps: u16,
+    pub destination: FeeDestination,
+    pub timestamp: i64,
+}
@@ -1,13 +1,27 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use anchor_lang::solana_program::sysvar::instructions::{
+    load_current_index_checked, load_instruction_at_checked,
+};
+use anchor_lang::system_program;
+use anchor_lang::Discriminator;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use ars_core::{GlobalState, ILIOracle};
+use ars_token::program::ArsToken;
+use ars_token::{Minter, MintState};
 
 declare_id!("ARS7PfJZeYAhsYGvR68ccZEpoXWHLYvJ3YbKoG5GHb5o");
 
 pub mod state;
 pub mod errors;
+pub mod events;
+pub mod oracle;
+pub mod swap_adapter;
+pub mod stake_pool;
+pub mod strategy_adapter;
 
 pub use state::*;
 pub use errors::ErrorCode;
+pub use events::*;
 
 #[program]
 pub mod ars_reserve {
@@ -24,18 +38,213 @@ pub mod ars_reserve {
         require!(rebalance_threshold_bps <= 10000, ErrorCode::InvalidThreshold);
 
         vault.authority = ctx.accounts.authority.key();
-        vault.usdc_vault = ctx.accounts.usdc_vault.key();
-        vault.sol_vault = ctx.accounts.sol_vault.key();
-        vault.msol_vault = ctx.accounts.msol_vault.key();
-        vault.jitosol_vault = ctx.accounts.jitosol_vault.key();
+        vault.asset_registry = ctx.accounts.asset_registry.key();
+        vault.mint_state = ctx.accounts.mint_state.key();
         vault.total_value_usd = 0;
         vault.liabilities_usd = 0;
-        vault.vhr = u16::MAX;
+        vault.aru_price_usd = 0;
+        vault.last_liability_update = 0;
+        vault.vhr = u64::MAX;
         vault.last_rebalance = 0;
         vault.rebalance_threshold_bps = rebalance_threshold_bps;
-        vault.min_vhr = min_vhr;
+        vault.min_vhr = u64::from(min_vhr);
+        vault.settled = false;
         vault.bump = ctx.bumps.vault;
+        
+        let asset_registry = &mut ctx.accounts.asset_registry;
+        asset_registry.reserve_vault = vault.key();
+        asset_registry.assets = Vec::new();
+        asset_registry.total_target_weight_bps = 0;
+        asset_registry.bump = ctx.bumps.asset_registry;
+
+        Ok(())
+    }
+
+    pub fn migrate_vault(
+        ctx: Context<MigrateVault>,
+    ) -> Result<()> {
+        let vault_info = ctx.accounts.vault.to_account_info();
+        require!(
+            vault_info.data_len() == LegacyReserveVault::LEN,
+            ErrorCode::AlreadyMigrated
+        );
+        
+        let legacy = {
+            let data = vault_info.try_borrow_data()?;
+            require!(
+                data[..8] == ReserveVault::DISCRIMINATOR,
+                ErrorCode::InvalidVaultAccount
+            );
+            LegacyReserveVault::deserialize(&mut &data[8..])?
+        };
+        require_keys_eq!(
+            legacy.authority,
+            ctx.accounts.authority.key(),
+            ErrorCode::Unauthorized
+        );
+        
+        // Asset balances and value are rebuilt through add_asset and refresh_valuation
+        let vault = ReserveVault {
+            authority: legacy.authority,
+            asset_registry: ctx.accounts.asset_registry.key(),
+            mint_state: ctx.accounts.mint_state.key(),
+            total_value_usd: 0,
+            liabilities_usd: 0,
+            aru_price_usd: 0,
+            last_liability_update: 0,
+            vhr: u64::MAX,
+            last_rebalance: legacy.last_rebalance,
+            rebalance_threshold_bps: legacy.rebalance_threshold_bps,
+            min_vhr: u64::from(legacy.min_vhr),
+            settled: false,
+            bump: legacy.bump,
+        };
+        
+        let rent_exempt = Rent::get()?.minimum_balance(ReserveVault::LEN);
+        let current_lamports = vault_info.lamports();
+        if current_lamports < rent_exempt {
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.authority.to_account_info(),
+                        to: vault_info.clone(),
+                    },
+                ),
+                rent_exempt - current_lamports,
+            )?;
+        } else if current_lamports > rent_exempt {
+            let excess = current_lamports - rent_exempt;
+            **vault_info.try_borrow_mut_lamports()? -= excess;
+            **ctx.accounts.authority.to_account_info().try_borrow_mut_lamports()? += excess;
+        }
+        
+        vault_info.realloc(ReserveVault::LEN, false)?;
+        let mut data = vault_info.try_borrow_mut_data()?;
+        vault.try_serialize(&mut &mut data[..])?;
+        drop(data);
+        
+        let asset_registry = &mut ctx.accounts.asset_registry;
+        asset_registry.reserve_vault = vault_info.key();
+        asset_registry.assets = Vec::new();
+        asset_registry.total_target_weight_bps = 0;
+        asset_registry.bump = ctx.bumps.asset_registry;
+        
+        Ok(())
+    }
+
+    pub fn add_asset(
+        ctx: Context<AddAsset>,
+        params: AssetParams,
+    ) -> Result<()> {
+        params.validate()?;
+        
+        let asset_registry = &mut ctx.accounts.asset_registry;
+        require!(
+            asset_registry.assets.len() < MAX_ASSETS,
+            ErrorCode::TooManyAssets
+        );
+        
+        let total_target_weight_bps = asset_registry.total_target_weight_bps
+            .checked_add(params.target_weight_bps)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        require!(
+            total_target_weight_bps <= 10000,
+            ErrorCode::TargetWeightExceeded
+        );
+        
+        let asset_config = &mut ctx.accounts.asset_config;
+        asset_config.reserve_vault = ctx.accounts.vault.key();
+        asset_config.mint = ctx.accounts.mint.key();
+        asset_config.vault = ctx.accounts.asset_vault.key();
+        asset_config.apply(&params);
+        asset_config.current_weight_bps = 0;
+        asset_config.decimals = ctx.accounts.mint.decimals;
+        // Tokens already held (e.g. by a migrated vault) count once the asset is valued
+        asset_config.balance = ctx.accounts.asset_vault.amount;
+        asset_config.position_balance = 0;
+        asset_config.deployed = 0;
+        asset_config.yield_basis = 0;
+        asset_config.price_usd = 0;
+        asset_config.value_usd = 0;
+        asset_config.last_price_update = 0;
+        asset_config.bump = ctx.bumps.asset_config;
+        
+        asset_registry.assets.push(asset_config.key());
+        asset_registry.total_target_weight_bps = total_target_weight_bps;
+        
+        Ok(())
+    }
+
+    pub fn update_asset(
+        ctx: Context<UpdateAsset>,
+        params: AssetParams,
+    ) -> Result<()> {
+        params.validate()?;
+        
+        let asset_registry = &mut ctx.accounts.asset_registry;
+        let asset_config = &mut ctx.accounts.asset_config;
+        
+        let total_target_weight_bps = asset_registry.total_target_weight_bps
+            .checked_sub(asset_config.target_weight_bps)
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            .checked_add(params.target_weight_bps)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        require!(
+            total_target_weight_bps <= 10000,
+            ErrorCode::TargetWeightExceeded
+        );
+        
+        asset_config.apply(&params);
+        
+        asset_registry.total_target_weight_bps = total_target_weight_bps;
+        
+        Ok(())
+    }
+
+    pub fn remove_asset(
+        ctx: Context<RemoveAsset>,
+    ) -> Result<()> {
+        let asset_registry = &mut ctx.accounts.asset_registry;
+        let asset_config = &ctx.accounts.asset_config;
+        
+        require!(
+            asset_config.balance == 0 && ctx.accounts.asset_vault.amount == 0,
+            ErrorCode::AssetNotEmpty
+        );
+        
+        let index = asset_registry.assets
+            .iter()
+            .position(|asset| *asset == asset_config.key())
+            .ok_or(ErrorCode::AssetNotFound)?;
+        asset_registry.assets.remove(index);
+        asset_registry.total_target_weight_bps = asset_registry.total_target_weight_bps
+            .checked_sub(asset_config.target_weight_bps)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        
+        Ok(())
+    }
+
+    pub fn open_position(
+        ctx: Context<OpenPosition>,
+    ) -> Result<()> {
+        let position = &mut ctx.accounts.position;
+        position.owner = ctx.accounts.owner.key();
+        position.asset_config = ctx.accounts.asset_config.key();
+        position.amount = 0;
+        position.total_deposited = 0;
+        position.total_withdrawn = 0;
+        position.last_update = Clock::get()?.unix_timestamp;
+        position.bump = ctx.bumps.position;
+        
+        Ok(())
+    }
 
+    pub fn close_position(
+        ctx: Context<ClosePosition>,
+    ) -> Result<()> {
+        require!(ctx.accounts.position.amount == 0, ErrorCode::PositionNotEmpty);
+        
         Ok(())
     }
 
@@ -45,7 +254,40 @@ pub mod ars_reserve {
     ) -> Result<()> {
         require!(amount > 0, ErrorCode::InvalidAmount);
         
+        let schedule = load_flow_fee_schedule(
+            &ctx.accounts.flow_fee_schedule,
+            ctx.accounts.fee_recipient.as_ref(),
+        )?;
+        let current_time = Clock::get()?.unix_timestamp;
         let vault = &mut ctx.accounts.vault;
+        let asset_config = &mut ctx.accounts.asset_config;
+        
+        // The fee band is read with the whole deposit counted in the asset's weight
+        let gross_balance = asset_config.balance
+            .checked_add(amount)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        let gross_value = revalue_asset(
+            asset_config,
+            gross_balance,
+            &ctx.accounts.price_feed,
+            current_time,
+        )?;
+        let (fee, fee_bps, weight_bps) = flow_fee(
+            schedule.as_ref(),
+            asset_config,
+            amount,
+            gross_value,
+            vault.total_value_usd
+                .checked_sub(asset_config.value_usd)
+                .ok_or(ErrorCode::ArithmeticOverflow)?
+                .checked_add(gross_value)
+                .ok_or(ErrorCode::ArithmeticOverflow)?,
+            true,
+        )?;
+        let net_amount = amount
+            .checked_sub(fee)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        require!(net_amount > 0, ErrorCode::InvalidAmount);
         
         token::transfer(
             CpiContext::new(
@@ -56,16 +298,77 @@ pub mod ars_reserve {
                     authority: ctx.accounts.user.to_account_info(),
                 },
             ),
-            amount,
+            net_amount,
         )?;
         
-        // Simplified: assume 1:1 USD for now
-        let value_usd = amount;
+        if let Some(schedule) = &schedule {
+            if fee > 0 {
+                token::transfer(
+                    CpiContext::new(
+                        ctx.accounts.token_program.to_account_info(),
+                        Transfer {
+                            from: ctx.accounts.user_token_account.to_account_info(),
+                            to: fee_recipient_info(&ctx.accounts.fee_recipient)?,
+                            authority: ctx.accounts.user.to_account_info(),
+                        },
+                    ),
+                    fee,
+                )?;
+            }
+            
+            emit!(FlowFeeCharged {
+                reserve_vault: vault.key(),
+                mint: asset_config.mint,
+                user: ctx.accounts.user.key(),
+                deposit: true,
+                amount,
+                fee,
+                fee_bps,
+                weight_bps,
+                destination: schedule.destination,
+                timestamp: current_time,
+            });
+        }
+        
+        let new_balance = asset_config.balance
+            .checked_add(net_amount)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        let new_value = oracle::asset_value_usd(
+            new_balance,
+            asset_config.decimals,
+            asset_config.price_usd,
+            asset_config.haircut_bps,
+        )?;
         
         vault.total_value_usd = vault.total_value_usd
-            .checked_add(value_usd)
+            .checked_sub(asset_config.value_usd)
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            .checked_add(new_value)
             .ok_or(ErrorCode::ArithmeticOverflow)?;
         
+        asset_config.balance = new_balance;
+        asset_config.position_balance = asset_config.position_balance
+            .checked_add(net_amount)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        asset_config.value_usd = new_value;
+        asset_config.current_weight_bps = calculate_weight(new_value, vault.total_value_usd)?;
+        
+        let position = &mut ctx.accounts.position;
+        position.amount = position.amount
+            .checked_add(net_amount)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        position.total_deposited = position.total_deposited
+            .checked_add(net_amount)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        position.last_update = current_time;
+        
+        sync_liabilities(
+            vault,
+            &ctx.accounts.mint_state,
+            &ctx.accounts.ili_oracle,
+            ctx.accounts.aru_mint.decimals,
+            current_time,
+        )?;
         vault.vhr = calculate_vhr(vault.total_value_usd, vault.liabilities_usd)?;
         
         Ok(())
@@ -75,22 +378,574 @@ pub mod ars_reserve {
         ctx: Context<Withdraw>,
         amount: u64,
     ) -> Result<()> {
-        let vault = &mut ctx.accounts.vault;
+        let schedule = load_flow_fee_schedule(
+            &ctx.accounts.flow_fee_schedule,
+            ctx.accounts.fee_recipient.as_ref(),
+        )?;
         
         require!(
-            amount <= ctx.accounts.vault_token_account.amount,
+            amount <= ctx.accounts.position.amount,
             ErrorCode::InsufficientBalance
         );
+        // Direct withdrawals may not jump queued requests for the same asset and
+        // share the epoch outflow cap with the queue
+        require!(
+            !ctx.accounts.withdrawal_queue.has_pending(&ctx.accounts.asset_config.key()),
+            ErrorCode::WithdrawalQueueNotEmpty
+        );
         
-        let value_usd = amount;
+        let current_time = Clock::get()?.unix_timestamp;
+        pay_out_withdrawal(
+            &mut ctx.accounts.vault,
+            &mut ctx.accounts.asset_config,
+            &mut ctx.accounts.withdrawal_queue,
+            &ctx.accounts.price_feed,
+            &ctx.accounts.mint_state,
+            &ctx.accounts.ili_oracle,
+            ctx.accounts.aru_mint.decimals,
+            &ctx.accounts.vault_token_account,
+            &ctx.accounts.user_token_account,
+            schedule.as_ref(),
+            &ctx.accounts.fee_recipient,
+            &ctx.accounts.token_program,
+            ctx.accounts.user.key(),
+            amount,
+            current_time,
+        )?;
         
-        let new_total_value = vault.total_value_usd
-            .checked_sub(value_usd)
+        let position = &mut ctx.accounts.position;
+        position.amount = position.amount
+            .checked_sub(amount)
             .ok_or(ErrorCode::ArithmeticOverflow)?;
+        position.total_withdrawn = position.total_withdrawn
+            .checked_add(amount)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        position.last_update = current_time;
         
-        let new_vhr = calculate_vhr(new_total_value, vault.liabilities_usd)?;
+        Ok(())
+    }
+
+    pub fn add_flow_fee_schedule(
+        ctx: Context<AddFlowFeeSchedule>,
+        destination: FeeDestination,
+        params: FlowFeeParams,
+    ) -> Result<()> {
+        params.validate()?;
         
-        require!(new_vhr >= vault.min_vhr, ErrorCode::VHRTooLow);
+        let flow_fee_schedule = &mut ctx.accounts.flow_fee_schedule;
+        flow_fee_schedule.reserve_vault = ctx.accounts.vault.key();
+        flow_fee_schedule.asset_config = ctx.accounts.asset_config.key();
+        flow_fee_schedule.destination = destination;
+        flow_fee_schedule.fee_recipient = ctx.accounts.fee_recipient.key();
+        flow_fee_schedule.apply(&params);
+        flow_fee_schedule.bump = ctx.bumps.flow_fee_schedule;
+        
+        Ok(())
+    }
+
+    pub fn update_flow_fee_schedule(
+        ctx: Context<UpdateFlowFeeSchedule>,
+        destination: FeeDestination,
+        params: FlowFeeParams,
+    ) -> Result<()> {
+        params.validate()?;
+        
+        let flow_fee_schedule = &mut ctx.accounts.flow_fee_schedule;
+        flow_fee_schedule.destination = destination;
+        flow_fee_schedule.fee_recipient = ctx.accounts.fee_recipient.key();
+        flow_fee_schedule.apply(&params);
+        
+        Ok(())
+    }
+
+    pub fn initialize_withdrawal_queue(
+        ctx: Context<InitializeWithdrawalQueue>,
+        outflow_cap_bps: u16,
+        min_request_usd: u64,
+    ) -> Result<()> {
+        require!(outflow_cap_bps <= 10000, ErrorCode::InvalidThreshold);
+        
+        let withdrawal_queue = &mut ctx.accounts.withdrawal_queue;
+        withdrawal_queue.reserve_vault = ctx.accounts.vault.key();
+        withdrawal_queue.outflow_cap_bps = outflow_cap_bps;
+        withdrawal_queue.min_request_usd = min_request_usd;
+        withdrawal_queue.epoch = 0;
+        withdrawal_queue.epoch_cap_usd = 0;
+        withdrawal_queue.epoch_outflow_usd = 0;
+        withdrawal_queue.next_request_id = 0;
+        withdrawal_queue.pending = Vec::new();
+        withdrawal_queue.bump = ctx.bumps.withdrawal_queue;
+        
+        Ok(())
+    }
+
+    pub fn set_outflow_cap(
+        ctx: Context<SetOutflowCap>,
+        outflow_cap_bps: u16,
+        min_request_usd: u64,
+    ) -> Result<()> {
+        require!(outflow_cap_bps <= 10000, ErrorCode::InvalidThreshold);
+        
+        let withdrawal_queue = &mut ctx.accounts.withdrawal_queue;
+        withdrawal_queue.outflow_cap_bps = outflow_cap_bps;
+        withdrawal_queue.min_request_usd = min_request_usd;
+        // Resize the current epoch's cap; what already left this epoch still counts
+        withdrawal_queue.epoch_cap_usd = withdrawal_queue.cap_for(ctx.accounts.vault.total_value_usd)?;
+        
+        Ok(())
+    }
+
+    /// Withdraw the part of a request that fits under the remaining epoch
+    /// outflow cap and queue the rest until a later epoch has capacity
+    pub fn queue_withdrawal(
+        ctx: Context<QueueWithdrawal>,
+        amount: u64,
+    ) -> Result<()> {
+        let schedule = load_flow_fee_schedule(
+            &ctx.accounts.flow_fee_schedule,
+            ctx.accounts.fee_recipient.as_ref(),
+        )?;
+        
+        require!(amount > 0, ErrorCode::InvalidAmount);
+        require!(amount <= ctx.accounts.position.amount, ErrorCode::InsufficientBalance);
+        
+        let owner = ctx.accounts.owner.key();
+        let asset_key = ctx.accounts.asset_config.key();
+        let current_time = Clock::get()?.unix_timestamp;
+        let withdrawal_queue = &mut ctx.accounts.withdrawal_queue;
+        require!(
+            withdrawal_queue.pending.len() < MAX_QUEUED_WITHDRAWALS,
+            ErrorCode::WithdrawalQueueFull
+        );
+        require!(
+            withdrawal_queue.pending_for_owner(&owner) < MAX_QUEUED_WITHDRAWALS_PER_OWNER,
+            ErrorCode::TooManyQueuedWithdrawals
+        );
+        
+        let asset_config = &ctx.accounts.asset_config;
+        let price = oracle::load_price(
+            &ctx.accounts.price_feed,
+            asset_config.max_price_age,
+            asset_config.max_confidence_bps,
+            current_time,
+        )?;
+        withdrawal_queue.roll_epoch(ctx.accounts.mint_state.current_epoch, ctx.accounts.vault.total_value_usd)?;
+        let value_usd = oracle::asset_value_usd(
+            amount,
+            asset_config.decimals,
+            price.price_usd,
+            asset_config.haircut_bps,
+        )?;
+        let remaining_usd = withdrawal_queue.remaining_usd(&asset_key);
+        require!(value_usd > remaining_usd, ErrorCode::WithinOutflowCap);
+        
+        let within_cap = oracle::amount_within_value_usd(
+            remaining_usd,
+            asset_config.decimals,
+            price.price_usd,
+            asset_config.haircut_bps,
+        )?.min(amount);
+        let queued = amount
+            .checked_sub(within_cap)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        require!(
+            oracle::asset_value_usd(
+                queued,
+                asset_config.decimals,
+                price.price_usd,
+                asset_config.haircut_bps,
+            )? >= withdrawal_queue.min_request_usd,
+            ErrorCode::WithdrawalTooSmall
+        );
+        
+        if within_cap > 0 {
+            pay_out_withdrawal(
+                &mut ctx.accounts.vault,
+                &mut ctx.accounts.asset_config,
+                &mut ctx.accounts.withdrawal_queue,
+                &ctx.accounts.price_feed,
+                &ctx.accounts.mint_state,
+                &ctx.accounts.ili_oracle,
+                ctx.accounts.aru_mint.decimals,
+                &ctx.accounts.vault_token_account,
+                &ctx.accounts.owner_token_account,
+                schedule.as_ref(),
+                &ctx.accounts.fee_recipient,
+                &ctx.accounts.token_program,
+                owner,
+                within_cap,
+                current_time,
+            )?;
+        }
+        
+        let position = &mut ctx.accounts.position;
+        position.amount = position.amount
+            .checked_sub(amount)
+            .ok_or(ErrorCode::InsufficientBalance)?;
+        position.total_withdrawn = position.total_withdrawn
+            .checked_add(within_cap)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        position.last_update = current_time;
+        
+        let withdrawal_queue = &mut ctx.accounts.withdrawal_queue;
+        let withdrawal_request = &mut ctx.accounts.withdrawal_request;
+        withdrawal_request.reserve_vault = ctx.accounts.vault.key();
+        withdrawal_request.owner = owner;
+        withdrawal_request.asset_config = asset_key;
+        withdrawal_request.position = position.key();
+        withdrawal_request.id = withdrawal_queue.next_request_id;
+        withdrawal_request.amount = queued;
+        withdrawal_request.requested_epoch = ctx.accounts.mint_state.current_epoch;
+        withdrawal_request.requested_at = current_time;
+        withdrawal_request.bump = ctx.bumps.withdrawal_request;
+        
+        withdrawal_queue.pending.push(PendingWithdrawal {
+            request: withdrawal_request.key(),
+            owner,
+            asset_config: asset_key,
+        });
+        withdrawal_queue.next_request_id = withdrawal_queue.next_request_id
+            .checked_add(1)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        
+        Ok(())
+    }
+
+    pub fn cancel_withdrawal(
+        ctx: Context<CancelWithdrawal>,
+    ) -> Result<()> {
+        let withdrawal_queue = &mut ctx.accounts.withdrawal_queue;
+        let withdrawal_request = &ctx.accounts.withdrawal_request;
+        
+        let index = withdrawal_queue.pending
+            .iter()
+            .position(|pending| pending.request == withdrawal_request.key())
+            .ok_or(ErrorCode::NotNextInQueue)?;
+        withdrawal_queue.pending.remove(index);
+        
+        let position = &mut ctx.accounts.position;
+        position.amount = position.amount
+            .checked_add(withdrawal_request.amount)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        
+        Ok(())
+    }
+
+    /// Permissionless crank paying out the request at the head of the queue.
+    /// A request worth more than the epoch can still release is paid in part,
+    /// and the rest stays at the head for the next epoch.
+    pub fn process_withdrawal(
+        ctx: Context<ProcessWithdrawal>,
+    ) -> Result<()> {
+        let schedule = load_flow_fee_schedule(
+            &ctx.accounts.flow_fee_schedule,
+            ctx.accounts.fee_recipient.as_ref(),
+        )?;
+        let request_amount = ctx.accounts.withdrawal_request.amount;
+        
+        require!(
+            ctx.accounts.withdrawal_queue.pending.first().map(|pending| pending.request)
+                == Some(ctx.accounts.withdrawal_request.key()),
+            ErrorCode::NotNextInQueue
+        );
+        require!(
+            ctx.accounts.withdrawal_request.requested_epoch < ctx.accounts.mint_state.current_epoch,
+            ErrorCode::WithdrawalNotReady
+        );
+        
+        let current_time = Clock::get()?.unix_timestamp;
+        let asset_config = &ctx.accounts.asset_config;
+        let price = oracle::load_price(
+            &ctx.accounts.price_feed,
+            asset_config.max_price_age,
+            asset_config.max_confidence_bps,
+            current_time,
+        )?;
+        let withdrawal_queue = &mut ctx.accounts.withdrawal_queue;
+        withdrawal_queue.roll_epoch(ctx.accounts.mint_state.current_epoch, ctx.accounts.vault.total_value_usd)?;
+        let capacity_usd = withdrawal_queue.epoch_cap_usd
+            .saturating_sub(withdrawal_queue.epoch_outflow_usd);
+        let value_usd = oracle::asset_value_usd(
+            request_amount,
+            asset_config.decimals,
+            price.price_usd,
+            asset_config.haircut_bps,
+        )?;
+        let amount = if value_usd <= capacity_usd {
+            request_amount
+        } else {
+            oracle::amount_within_value_usd(
+                capacity_usd,
+                asset_config.decimals,
+                price.price_usd,
+                asset_config.haircut_bps,
+            )?.min(request_amount)
+        };
+        require!(amount > 0, ErrorCode::OutflowCapExceeded);
+        
+        pay_out_withdrawal(
+            &mut ctx.accounts.vault,
+            &mut ctx.accounts.asset_config,
+            &mut ctx.accounts.withdrawal_queue,
+            &ctx.accounts.price_feed,
+            &ctx.accounts.mint_state,
+            &ctx.accounts.ili_oracle,
+            ctx.accounts.aru_mint.decimals,
+            &ctx.accounts.vault_token_account,
+            &ctx.accounts.owner_token_account,
+            schedule.as_ref(),
+            &ctx.accounts.fee_recipient,
+            &ctx.accounts.token_program,
+            ctx.accounts.owner.key(),
+            amount,
+            current_time,
+        )?;
+        
+        let position = &mut ctx.accounts.position;
+        position.total_withdrawn = position.total_withdrawn
+            .checked_add(amount)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        position.last_update = current_time;
+        
+        let withdrawal_request = &mut ctx.accounts.withdrawal_request;
+        withdrawal_request.amount = request_amount
+            .checked_sub(amount)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        if withdrawal_request.amount == 0 {
+            ctx.accounts.withdrawal_queue.pending.remove(0);
+            ctx.accounts.withdrawal_request.close(ctx.accounts.owner.to_account_info())?;
+        }
+        
+        Ok(())
+    }
+
+    pub fn refresh_valuation<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RefreshValuation<'info>>,
+    ) -> Result<()> {
+        let asset_registry = &ctx.accounts.asset_registry;
+        let remaining_accounts = ctx.remaining_accounts;
+        
+        // Expect an [asset_config, price_feed] pair for every registered asset, in order
+        require!(
+            remaining_accounts.len() == asset_registry.assets.len() * 2,
+            ErrorCode::AssetListMismatch
+        );
+        
+        let current_time = Clock::get()?.unix_timestamp;
+        let mut asset_configs = Vec::with_capacity(asset_registry.assets.len());
+        let mut total_value_usd: u64 = 0;
+        
+        for (expected, pair) in asset_registry.assets.iter().zip(remaining_accounts.chunks(2)) {
+            let price_feed = &pair[1];
+            require_keys_eq!(pair[0].key(), *expected, ErrorCode::AssetListMismatch);
+            
+            let mut asset_config = Account::<AssetConfig>::try_from(&pair[0])?;
+            require_keys_eq!(
+                price_feed.key(),
+                asset_config.oracle_source,
+                ErrorCode::InvalidOracle
+            );
+            
+            let balance = asset_config.balance;
+            asset_config.value_usd = revalue_asset(
+                &mut asset_config,
+                balance,
+                price_feed,
+                current_time,
+            )?;
+            total_value_usd = total_value_usd
+                .checked_add(asset_config.value_usd)
+                .ok_or(ErrorCode::ArithmeticOverflow)?;
+            
+            asset_configs.push(asset_config);
+        }
+        
+        for mut asset_config in asset_configs {
+            asset_config.current_weight_bps = calculate_weight(asset_config.value_usd, total_value_usd)?;
+            asset_config.exit(&crate::ID)?;
+        }
+        
+        let vault = &mut ctx.accounts.vault;
+        vault.total_value_usd = total_value_usd;
+        sync_liabilities(
+            vault,
+            &ctx.accounts.mint_state,
+            &ctx.accounts.ili_oracle,
+            ctx.accounts.aru_mint.decimals,
+            current_time,
+        )?;
+        vault.vhr = calculate_vhr(vault.total_value_usd, vault.liabilities_usd)?;
+        
+        Ok(())
+    }
+
+    pub fn refresh_vhr(
+        ctx: Context<RefreshVhr>,
+    ) -> Result<()> {
+        let vault = &mut ctx.accounts.vault;
+        
+        sync_liabilities(
+            vault,
+            &ctx.accounts.mint_state,
+            &ctx.accounts.ili_oracle,
+            ctx.accounts.aru_mint.decimals,
+            Clock::get()?.unix_timestamp,
+        )?;
+        vault.vhr = calculate_vhr(vault.total_value_usd, vault.liabilities_usd)?;
+        
+        Ok(())
+    }
+
+    pub fn initialize_redemption_config(
+        ctx: Context<InitializeRedemptionConfig>,
+        fee_bps: u16,
+        epoch_cap_bps: u16,
+    ) -> Result<()> {
+        let redemption_config = &mut ctx.accounts.redemption_config;
+        
+        redemption_config.reserve_vault = ctx.accounts.vault.key();
+        redemption_config.set(fee_bps, epoch_cap_bps)?;
+        redemption_config.epoch = 0;
+        redemption_config.epoch_supply = 0;
+        redemption_config.epoch_redeemed = 0;
+        redemption_config.total_redeemed = 0;
+        redemption_config.total_fees_usd = 0;
+        redemption_config.bump = ctx.bumps.redemption_config;
+        
+        Ok(())
+    }
+
+    pub fn update_redemption_config(
+        ctx: Context<UpdateRedemptionConfig>,
+        fee_bps: u16,
+        epoch_cap_bps: u16,
+    ) -> Result<()> {
+        ctx.accounts.redemption_config.set(fee_bps, epoch_cap_bps)
+    }
+
+    /// Burn ARU and pay out a pro-rata slice of every reserve asset.
+    ///
+    /// Remaining accounts are `[asset_config, price_feed, vault_token_account,
+    /// user_token_account]` for every registered asset, in registry order.
+    pub fn redeem<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Redeem<'info>>,
+        aru_amount: u64,
+    ) -> Result<()> {
+        require!(aru_amount > 0, ErrorCode::InvalidAmount);
+        
+        let asset_registry = &ctx.accounts.asset_registry;
+        let remaining_accounts = ctx.remaining_accounts;
+        require!(
+            remaining_accounts.len() == asset_registry.assets.len() * 4,
+            ErrorCode::AssetListMismatch
+        );
+        
+        // The cap is sized off the ARU supply at the first redemption of each ars-token epoch
+        let redemption_config = &mut ctx.accounts.redemption_config;
+        let current_epoch = ctx.accounts.mint_state.current_epoch;
+        if redemption_config.epoch != current_epoch || redemption_config.epoch_redeemed == 0 {
+            redemption_config.epoch = current_epoch;
+            redemption_config.epoch_supply = ctx.accounts.mint_state.total_supply;
+            redemption_config.epoch_redeemed = 0;
+        }
+        
+        let epoch_cap = (redemption_config.epoch_supply as u128)
+            .checked_mul(redemption_config.epoch_cap_bps as u128)
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            .checked_div(10000)
+            .ok_or(ErrorCode::ArithmeticOverflow)? as u64;
+        let epoch_redeemed = redemption_config.epoch_redeemed
+            .checked_add(aru_amount)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        require!(epoch_redeemed <= epoch_cap, ErrorCode::RedemptionCapExceeded);
+        
+        let current_time = Clock::get()?.unix_timestamp;
+        let mut asset_configs = Vec::with_capacity(asset_registry.assets.len());
+        let mut market_value_usd: u64 = 0;
+        
+        for (expected, accounts) in asset_registry.assets.iter().zip(remaining_accounts.chunks(4)) {
+            require_keys_eq!(accounts[0].key(), *expected, ErrorCode::AssetListMismatch);
+            
+            let mut asset_config = Account::<AssetConfig>::try_from(&accounts[0])?;
+            require_keys_eq!(
+                accounts[1].key(),
+                asset_config.oracle_source,
+                ErrorCode::InvalidOracle
+            );
+            require_keys_eq!(
+                accounts[2].key(),
+                asset_config.vault,
+                ErrorCode::InvalidAssetVault
+            );
+            let user_token_account = Account::<TokenAccount>::try_from(&accounts[3])?;
+            require_keys_eq!(
+                user_token_account.mint,
+                asset_config.mint,
+                ErrorCode::InvalidAssetVault
+            );
+            
+            let balance = asset_config.balance;
+            asset_config.value_usd = revalue_asset(
+                &mut asset_config,
+                balance,
+                &accounts[1],
+                current_time,
+            )?;
+            
+            // Payouts are sized at oracle value, before haircuts, and only from
+            // protocol-owned tokens: positions and strategy funds are not redeemable
+            market_value_usd = market_value_usd
+                .checked_add(oracle::asset_value_usd(
+                    asset_config.protocol_idle_balance(),
+                    asset_config.decimals,
+                    asset_config.price_usd,
+                    0,
+                )?)
+                .ok_or(ErrorCode::ArithmeticOverflow)?;
+            
+            asset_configs.push(asset_config);
+        }
+        
+        let vault = &mut ctx.accounts.vault;
+        sync_liabilities(
+            vault,
+            &ctx.accounts.mint_state,
+            &ctx.accounts.ili_oracle,
+            ctx.accounts.aru_mint.decimals,
+            current_time,
+        )?;
+        require!(vault.aru_price_usd > 0, ErrorCode::InvalidILIPrice);
+        
+        let gross_usd = oracle::asset_value_usd(
+            aru_amount,
+            ctx.accounts.aru_mint.decimals,
+            vault.aru_price_usd,
+            0,
+        )?;
+        let fee_usd = (gross_usd as u128)
+            .checked_mul(redemption_config.fee_bps as u128)
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            .checked_div(10000)
+            .ok_or(ErrorCode::ArithmeticOverflow)? as u64;
+        let net_usd = gross_usd
+            .checked_sub(fee_usd)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        require!(
+            net_usd > 0 && net_usd <= market_value_usd,
+            ErrorCode::InsufficientBalance
+        );
+        
+        // The insurance fund's share of the fee is kept as ARU rather than burned
+        let insurance_cut = (aru_amount as u128)
+            .checked_mul(redemption_config.fee_bps as u128)
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            .checked_mul(ctx.accounts.insurance_fund.redemption_fee_share_bps as u128)
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            .checked_div(100_000_000)
+            .ok_or(ErrorCode::ArithmeticOverflow)? as u64;
+        let burn_amount = aru_amount
+            .checked_sub(insurance_cut)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
         
         let vault_seeds = &[
             b"vault",
@@ -98,90 +953,4671 @@ pub mod ars_reserve {
             &[vault.bump],
         ];
         let signer = &[&vault_seeds[..]];
+        let mut new_total_value: u64 = 0;
         
-        token::transfer(
-            CpiContext::new_with_signer(
-                ctx.accounts.token_program.to_account_info(),
-                Transfer {
-                    from: ctx.accounts.vault_token_account.to_account_info(),
-                    to: ctx.accounts.user_token_account.to_account_info(),
-                    authority: vault.to_account_info(),
+        for (asset_config, accounts) in asset_configs.iter_mut().zip(remaining_accounts.chunks(4)) {
+            let payout = (asset_config.protocol_idle_balance() as u128)
+                .checked_mul(net_usd as u128)
+                .ok_or(ErrorCode::ArithmeticOverflow)?
+                .checked_div(market_value_usd as u128)
+                .ok_or(ErrorCode::ArithmeticOverflow)? as u64;
+            
+            require!(
+                payout <= asset_config.protocol_idle_balance(),
+                ErrorCode::InsufficientIdleBalance
+            );
+            
+            if payout > 0 {
+                token::transfer(
+                    CpiContext::new_with_signer(
+                        ctx.accounts.token_program.to_account_info(),
+                        Transfer {
+                            from: accounts[2].clone(),
+                            to: accounts[3].clone(),
+                            authority: vault.to_account_info(),
+                        },
+                        signer,
+                    ),
+                    payout,
+                )?;
+                
+                asset_config.balance = asset_config.balance
+                    .checked_sub(payout)
+                    .ok_or(ErrorCode::ArithmeticOverflow)?;
+                asset_config.clamp_yield_basis();
+            }
+            
+            asset_config.value_usd = oracle::asset_value_usd(
+                asset_config.balance,
+                asset_config.decimals,
+                asset_config.price_usd,
+                asset_config.haircut_bps,
+            )?;
+            new_total_value = new_total_value
+                .checked_add(asset_config.value_usd)
+                .ok_or(ErrorCode::ArithmeticOverflow)?;
+        }
+        
+        if insurance_cut > 0 {
+            token::transfer(
+                CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.user_aru_account.to_account_info(),
+                        to: ctx.accounts.insurance_aru_account.to_account_info(),
+                        authority: ctx.accounts.user.to_account_info(),
+                    },
+                ),
+                insurance_cut,
+            )?;
+        }
+        
+        ars_token::cpi::burn_aru(
+            CpiContext::new(
+                ctx.accounts.ars_token_program.to_account_info(),
+                ars_token::cpi::accounts::BurnARU {
+                    mint_state: ctx.accounts.mint_state.to_account_info(),
+                    aru_mint: ctx.accounts.aru_mint.to_account_info(),
+                    source: ctx.accounts.user_aru_account.to_account_info(),
+                    authority: ctx.accounts.user.to_account_info(),
+                    token_program: ctx.accounts.token_program.to_account_info(),
                 },
-                signer,
             ),
-            amount,
+            burn_amount,
+        )?;
+        ctx.accounts.mint_state.reload()?;
+        
+        let vault = &mut ctx.accounts.vault;
+        sync_liabilities(
+            vault,
+            &ctx.accounts.mint_state,
+            &ctx.accounts.ili_oracle,
+            ctx.accounts.aru_mint.decimals,
+            current_time,
         )?;
         
+        let new_vhr = calculate_vhr(new_total_value, vault.liabilities_usd)?;
+        require!(new_vhr >= vault.min_vhr, ErrorCode::VHRTooLow);
+        
+        if insurance_cut > 0 {
+            let insurance_value = oracle::asset_value_usd(
+                insurance_cut,
+                ctx.accounts.aru_mint.decimals,
+                vault.aru_price_usd,
+                0,
+            )?;
+            ctx.accounts.insurance_fund.record_inflow(InsuranceSource::RedemptionFee, insurance_value)?;
+            
+            emit!(InsuranceFunded {
+                reserve_vault: vault.key(),
+                source: InsuranceSource::RedemptionFee,
+                mint: ctx.accounts.aru_mint.key(),
+                amount: insurance_cut,
+                value_usd: insurance_value,
+                timestamp: current_time,
+            });
+        }
+        
+        for mut asset_config in asset_configs {
+            asset_config.current_weight_bps = calculate_weight(asset_config.value_usd, new_total_value)?;
+            asset_config.exit(&crate::ID)?;
+        }
+        
         vault.total_value_usd = new_total_value;
         vault.vhr = new_vhr;
         
+        let redemption_config = &mut ctx.accounts.redemption_config;
+        redemption_config.epoch_redeemed = epoch_redeemed;
+        redemption_config.total_redeemed = redemption_config.total_redeemed
+            .checked_add(aru_amount)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        redemption_config.total_fees_usd = redemption_config.total_fees_usd
+            .checked_add(fee_usd)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        
         Ok(())
     }
 
-    pub fn rebalance(
-        ctx: Context<Rebalance>,
-        _amount: u64,
+    pub fn add_psm_asset(
+        ctx: Context<AddPsmAsset>,
+        params: PsmParams,
     ) -> Result<()> {
-        let vault = &mut ctx.accounts.vault;
-        
-        require!(
-            vault.vhr < vault.rebalance_threshold_bps,
-            ErrorCode::RebalanceNotNeeded
-        );
+        params.validate()?;
         
-        // Simplified rebalancing logic
-        vault.last_rebalance = Clock::get()?.unix_timestamp;
-        vault.vhr = calculate_vhr(vault.total_value_usd, vault.liabilities_usd)?;
+        let psm_config = &mut ctx.accounts.psm_config;
+        psm_config.reserve_vault = ctx.accounts.vault.key();
+        psm_config.asset_config = ctx.accounts.asset_config.key();
+        psm_config.apply(&params);
+        psm_config.debt = 0;
+        psm_config.bump = ctx.bumps.psm_config;
         
         Ok(())
     }
-}
 
-fn calculate_vhr(total_value_usd: u64, liabilities_usd: u64) -> Result<u16> {
-    if liabilities_usd == 0 {
-        return Ok(u16::MAX);
+    pub fn update_psm_asset(
+        ctx: Context<UpdatePsmAsset>,
+        params: PsmParams,
+    ) -> Result<()> {
+        params.validate()?;
+        
+        ctx.accounts.psm_config.apply(&params);
+        
+        Ok(())
+    }
+
+    /// Deposit an approved stable asset and mint ARU at the ILI price less the mint fee.
+    ///
+    /// PSM positions are backed by their own deposits, so they are bounded by the
+    /// debt ceiling rather than the vault's `min_vhr`.
+    pub fn psm_mint(
+        ctx: Context<PsmSwap>,
+        amount_in: u64,
+        min_aru_out: u64,
+    ) -> Result<()> {
+        require!(amount_in > 0, ErrorCode::InvalidAmount);
+        
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.user_token_account.to_account_info(),
+                    to: ctx.accounts.vault_token_account.to_account_info(),
+                    authority: ctx.accounts.user.to_account_info(),
+                },
+            ),
+            amount_in,
+        )?;
+        
+        let current_time = Clock::get()?.unix_timestamp;
+        let vault = &mut ctx.accounts.vault;
+        let asset_config = &mut ctx.accounts.asset_config;
+        
+        let new_balance = asset_config.balance
+            .checked_add(amount_in)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        let new_value = revalue_asset(
+            asset_config,
+            new_balance,
+            &ctx.accounts.price_feed,
+            current_time,
+        )?;
+        
+        sync_liabilities(
+            vault,
+            &ctx.accounts.mint_state,
+            &ctx.accounts.ili_oracle,
+            ctx.accounts.aru_mint.decimals,
+            current_time,
+        )?;
+        require!(vault.aru_price_usd > 0, ErrorCode::InvalidILIPrice);
+        
+        let deposit_value_usd = oracle::asset_value_usd(
+            amount_in,
+            asset_config.decimals,
+            asset_config.price_usd,
+            0,
+        )?;
+        let aru_out = oracle::amount_for_value_usd(
+            apply_fee(deposit_value_usd, ctx.accounts.psm_config.mint_fee_bps)?,
+            ctx.accounts.aru_mint.decimals,
+            vault.aru_price_usd,
+        )?;
+        require!(aru_out > 0, ErrorCode::InvalidAmount);
+        require!(aru_out >= min_aru_out, ErrorCode::SlippageExceeded);
+        
+        let psm_config = &mut ctx.accounts.psm_config;
+        let debt = psm_config.debt
+            .checked_add(aru_out)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        require!(debt <= psm_config.debt_ceiling, ErrorCode::DebtCeilingExceeded);
+        
+        let vault_seeds = &[
+            b"vault",
+            vault.authority.as_ref(),
+            &[vault.bump],
+        ];
+        let signer = &[&vault_seeds[..]];
+        
+        ars_token::cpi::minter_mint(
+            CpiContext::new_with_signer(
+                ctx.accounts.ars_token_program.to_account_info(),
+                ars_token::cpi::accounts::MinterMint {
+                    mint_state: ctx.accounts.mint_state.to_account_info(),
+                    minter_account: ctx.accounts.minter_account.to_account_info(),
+                    aru_mint: ctx.accounts.aru_mint.to_account_info(),
+                    destination: ctx.accounts.user_aru_account.to_account_info(),
+                    minter: vault.to_account_info(),
+                    token_program: ctx.accounts.token_program.to_account_info(),
+                },
+                signer,
+            ),
+            aru_out,
+        )?;
+        ctx.accounts.mint_state.reload()?;
+        
+        psm_config.debt = debt;
+        
+        vault.total_value_usd = vault.total_value_usd
+            .checked_sub(asset_config.value_usd)
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            .checked_add(new_value)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        asset_config.balance = new_balance;
+        asset_config.value_usd = new_value;
+        asset_config.current_weight_bps = calculate_weight(new_value, vault.total_value_usd)?;
+        
+        sync_liabilities(
+            vault,
+            &ctx.accounts.mint_state,
+            &ctx.accounts.ili_oracle,
+            ctx.accounts.aru_mint.decimals,
+            current_time,
+        )?;
+        vault.vhr = calculate_vhr(vault.total_value_usd, vault.liabilities_usd)?;
+        
+        Ok(())
+    }
+
+    /// Burn PSM-minted ARU for the stable asset at the ILI price less the redeem fee
+    pub fn psm_redeem(
+        ctx: Context<PsmSwap>,
+        aru_amount: u64,
+        min_amount_out: u64,
+    ) -> Result<()> {
+        require!(aru_amount > 0, ErrorCode::InvalidAmount);
+        
+        let psm_config = &mut ctx.accounts.psm_config;
+        let debt = psm_config.debt
+            .checked_sub(aru_amount)
+            .ok_or(ErrorCode::PsmDebtExceeded)?;
+        
+        let current_time = Clock::get()?.unix_timestamp;
+        let vault = &mut ctx.accounts.vault;
+        let asset_config = &mut ctx.accounts.asset_config;
+        
+        let balance = asset_config.balance;
+        revalue_asset(
+            asset_config,
+            balance,
+            &ctx.accounts.price_feed,
+            current_time,
+        )?;
+        
+        sync_liabilities(
+            vault,
+            &ctx.accounts.mint_state,
+            &ctx.accounts.ili_oracle,
+            ctx.accounts.aru_mint.decimals,
+            current_time,
+        )?;
+        require!(vault.aru_price_usd > 0, ErrorCode::InvalidILIPrice);
+        
+        let redeem_value_usd = oracle::asset_value_usd(
+            aru_amount,
+            ctx.accounts.aru_mint.decimals,
+            vault.aru_price_usd,
+            0,
+        )?;
+        let amount_out = oracle::amount_for_value_usd(
+            apply_fee(redeem_value_usd, psm_config.redeem_fee_bps)?,
+            asset_config.decimals,
+            asset_config.price_usd,
+        )?;
+        require!(amount_out > 0, ErrorCode::InvalidAmount);
+        require!(amount_out >= min_amount_out, ErrorCode::SlippageExceeded);
+        require!(
+            amount_out <= balance && amount_out <= ctx.accounts.vault_token_account.amount,
+            ErrorCode::InsufficientBalance
+        );
+        require!(
+            amount_out <= asset_config.protocol_idle_balance(),
+            ErrorCode::InsufficientIdleBalance
+        );
+        
+        let vault_seeds = &[
+            b"vault",
+            vault.authority.as_ref(),
+            &[vault.bump],
+        ];
+        let signer = &[&vault_seeds[..]];
+        
+        ars_token::cpi::minter_burn(
+            CpiContext::new_with_signer(
+                ctx.accounts.ars_token_program.to_account_info(),
+                ars_token::cpi::accounts::MinterBurn {
+                    mint_state: ctx.accounts.mint_state.to_account_info(),
+                    minter_account: ctx.accounts.minter_account.to_account_info(),
+                    aru_mint: ctx.accounts.aru_mint.to_account_info(),
+                    source: ctx.accounts.user_aru_account.to_account_info(),
+                    owner: ctx.accounts.user.to_account_info(),
+                    minter: vault.to_account_info(),
+                    token_program: ctx.accounts.token_program.to_account_info(),
+                },
+                signer,
+            ),
+            aru_amount,
+        )?;
+        ctx.accounts.mint_state.reload()?;
+        
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.vault_token_account.to_account_info(),
+                    to: ctx.accounts.user_token_account.to_account_info(),
+                    authority: vault.to_account_info(),
+                },
+                signer,
+            ),
+            amount_out,
+        )?;
+        
+        psm_config.debt = debt;
+        
+        let new_balance = balance
+            .checked_sub(amount_out)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        let new_value = oracle::asset_value_usd(
+            new_balance,
+            asset_config.decimals,
+            asset_config.price_usd,
+            asset_config.haircut_bps,
+        )?;
+        vault.total_value_usd = vault.total_value_usd
+            .checked_sub(asset_config.value_usd)
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            .checked_add(new_value)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        asset_config.balance = new_balance;
+        asset_config.clamp_yield_basis();
+        asset_config.value_usd = new_value;
+        asset_config.current_weight_bps = calculate_weight(new_value, vault.total_value_usd)?;
+        
+        sync_liabilities(
+            vault,
+            &ctx.accounts.mint_state,
+            &ctx.accounts.ili_oracle,
+            ctx.accounts.aru_mint.decimals,
+            current_time,
+        )?;
+        vault.vhr = calculate_vhr(vault.total_value_usd, vault.liabilities_usd)?;
+        
+        Ok(())
+    }
+
+    pub fn initialize_rebalance_config(
+        ctx: Context<InitializeRebalanceConfig>,
+        swap_adapter: Pubkey,
+        max_slippage_bps: u16,
+        max_trade_bps: u16,
+    ) -> Result<()> {
+        let rebalance_config = &mut ctx.accounts.rebalance_config;
+        
+        rebalance_config.reserve_vault = ctx.accounts.vault.key();
+        rebalance_config.set(swap_adapter, max_slippage_bps, max_trade_bps)?;
+        rebalance_config.bump = ctx.bumps.rebalance_config;
+        
+        Ok(())
+    }
+
+    pub fn update_rebalance_config(
+        ctx: Context<UpdateRebalanceConfig>,
+        swap_adapter: Pubkey,
+        max_slippage_bps: u16,
+        max_trade_bps: u16,
+    ) -> Result<()> {
+        ctx.accounts.rebalance_config.set(swap_adapter, max_slippage_bps, max_trade_bps)
+    }
+
+    /// Execute one trade leg from an overweight asset into an underweight one.
+    ///
+    /// Leg planning is off-chain: the keeper reads every `AssetConfig`, picks
+    /// which pair to trade and submits one call per leg. On-chain, the leg is
+    /// only checked to be overweight to underweight past the rebalance
+    /// threshold, and it is sized so neither side crosses its target.
+    ///
+    /// `amount` caps the input in `from_asset` base units. Accounts required by
+    /// the swap adapter are passed as remaining accounts.
+    pub fn rebalance<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Rebalance<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidAmount);
+        require_keys_neq!(
+            ctx.accounts.from_asset.key(),
+            ctx.accounts.to_asset.key(),
+            ErrorCode::InvalidRebalanceLeg
+        );
+        
+        let current_time = Clock::get()?.unix_timestamp;
+        let rebalance_config = &ctx.accounts.rebalance_config;
+        let vault = &mut ctx.accounts.vault;
+        let from_asset = &mut ctx.accounts.from_asset;
+        let to_asset = &mut ctx.accounts.to_asset;
+        
+        // Reprice both legs so drift is measured at current prices
+        let from_balance = from_asset.balance;
+        let from_value = revalue_asset(
+            from_asset,
+            from_balance,
+            &ctx.accounts.from_price_feed,
+            current_time,
+        )?;
+        let to_balance = to_asset.balance;
+        let to_value = revalue_asset(
+            to_asset,
+            to_balance,
+            &ctx.accounts.to_price_feed,
+            current_time,
+        )?;
+        
+        let total_value_usd = vault.total_value_usd
+            .checked_sub(from_asset.value_usd)
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            .checked_sub(to_asset.value_usd)
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            .checked_add(from_value)
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            .checked_add(to_value)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        
+        let excess_bps = calculate_weight(from_value, total_value_usd)?
+            .saturating_sub(from_asset.target_weight_bps);
+        let deficit_bps = to_asset.target_weight_bps
+            .saturating_sub(calculate_weight(to_value, total_value_usd)?);
+        require!(
+            excess_bps > 0
+                && deficit_bps > 0
+                && excess_bps.max(deficit_bps) > vault.rebalance_threshold_bps,
+            ErrorCode::RebalanceNotNeeded
+        );
+        
+        // Never trade past either target or beyond the per-leg cap
+        let leg_bps = excess_bps
+            .min(deficit_bps)
+            .min(rebalance_config.max_trade_bps);
+        let leg_value_usd = (total_value_usd as u128)
+            .checked_mul(leg_bps as u128)
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            .checked_div(10000)
+            .ok_or(ErrorCode::ArithmeticOverflow)? as u64;
+        
+        // Weights are haircut values, so sizing at the raw price undershoots slightly
+        let amount_in = oracle::amount_for_value_usd(
+            leg_value_usd,
+            from_asset.decimals,
+            from_asset.price_usd,
+        )?
+        .min(amount)
+        .min(from_asset.protocol_idle_balance());
+        require!(amount_in > 0, ErrorCode::InvalidAmount);
+        
+        let expected_out = oracle::amount_for_value_usd(
+            oracle::asset_value_usd(amount_in, from_asset.decimals, from_asset.price_usd, 0)?,
+            to_asset.decimals,
+            to_asset.price_usd,
+        )?;
+        let min_amount_out = (expected_out as u128)
+            .checked_mul(10000u128 - rebalance_config.max_slippage_bps as u128)
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            .checked_div(10000)
+            .ok_or(ErrorCode::ArithmeticOverflow)? as u64;
+        
+        let from_vault_before = ctx.accounts.from_vault.amount;
+        let to_vault_before = ctx.accounts.to_vault.amount;
+        
+        let vault_seeds = &[
+            b"vault",
+            vault.authority.as_ref(),
+            &[vault.bump],
+        ];
+        let signer = &[&vault_seeds[..]];
+        
+        swap_adapter::swap(
+            &ctx.accounts.swap_adapter.to_account_info(),
+            &vault.to_account_info(),
+            &ctx.accounts.from_vault.to_account_info(),
+            &ctx.accounts.to_vault.to_account_info(),
+            &ctx.accounts.token_program.to_account_info(),
+            ctx.remaining_accounts,
+            amount_in,
+            min_amount_out,
+            signer,
+        )?;
+        
+        // Settle on what actually moved, not on what the adapter was asked to do
+        ctx.accounts.from_vault.reload()?;
+        ctx.accounts.to_vault.reload()?;
+        let spent = from_vault_before
+            .checked_sub(ctx.accounts.from_vault.amount)
+            .ok_or(ErrorCode::ExcessiveSwapInput)?;
+        let received = ctx.accounts.to_vault.amount
+            .checked_sub(to_vault_before)
+            .ok_or(ErrorCode::SlippageExceeded)?;
+        require!(spent <= amount_in, ErrorCode::ExcessiveSwapInput);
+        require!(received >= min_amount_out, ErrorCode::SlippageExceeded);
+        
+        let new_from_balance = from_balance
+            .checked_sub(spent)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        let new_to_balance = to_balance
+            .checked_add(received)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        let new_from_value = oracle::asset_value_usd(
+            new_from_balance,
+            from_asset.decimals,
+            from_asset.price_usd,
+            from_asset.haircut_bps,
+        )?;
+        let new_to_value = oracle::asset_value_usd(
+            new_to_balance,
+            to_asset.decimals,
+            to_asset.price_usd,
+            to_asset.haircut_bps,
+        )?;
+        
+        let new_total_value = total_value_usd
+            .checked_sub(from_value)
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            .checked_sub(to_value)
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            .checked_add(new_from_value)
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            .checked_add(new_to_value)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        
+        from_asset.balance = new_from_balance;
+        from_asset.clamp_yield_basis();
+        from_asset.value_usd = new_from_value;
+        from_asset.current_weight_bps = calculate_weight(new_from_value, new_total_value)?;
+        to_asset.balance = new_to_balance;
+        to_asset.value_usd = new_to_value;
+        to_asset.current_weight_bps = calculate_weight(new_to_value, new_total_value)?;
+        
+        require!(
+            from_asset.current_weight_bps >= from_asset.min_weight_bps
+                && to_asset.current_weight_bps <= to_asset.max_weight_bps,
+            ErrorCode::WeightOutOfBounds
+        );
+        
+        vault.total_value_usd = new_total_value;
+        vault.last_rebalance = current_time;
+        vault.vhr = calculate_vhr(vault.total_value_usd, vault.liabilities_usd)?;
+        
+        Ok(())
+    }
+
+    /// Start tracking staking yield on an LST asset from the pool's current rate
+    pub fn initialize_lst_yield(
+        ctx: Context<InitializeLstYield>,
+        yield_bucket: YieldBucket,
+    ) -> Result<()> {
+        let pool_rate = stake_pool::load_exchange_rate(&ctx.accounts.stake_pool)?;
+        require_keys_eq!(
+            pool_rate.pool_mint,
+            ctx.accounts.asset_config.mint,
+            ErrorCode::InvalidStakePool
+        );
+        
+        // Everything the protocol holds now is valued from the current rate
+        let asset_config = &mut ctx.accounts.asset_config;
+        asset_config.yield_basis = asset_config.protocol_balance();
+        
+        let lst_yield = &mut ctx.accounts.lst_yield;
+        lst_yield.reserve_vault = ctx.accounts.vault.key();
+        lst_yield.asset_config = ctx.accounts.asset_config.key();
+        lst_yield.stake_pool = ctx.accounts.stake_pool.key();
+        lst_yield.yield_bucket = yield_bucket;
+        lst_yield.recipient = ctx.accounts.recipient.key();
+        lst_yield.last_rate = pool_rate.rate;
+        lst_yield.epoch = ctx.accounts.mint_state.current_epoch;
+        lst_yield.epoch_yield = 0;
+        lst_yield.accrued_yield = 0;
+        lst_yield.total_yield = 0;
+        lst_yield.last_harvest = Clock::get()?.unix_timestamp;
+        lst_yield.bump = ctx.bumps.lst_yield;
+        
+        Ok(())
+    }
+
+    pub fn set_yield_bucket(
+        ctx: Context<SetYieldBucket>,
+        yield_bucket: YieldBucket,
+    ) -> Result<()> {
+        let lst_yield = &mut ctx.accounts.lst_yield;
+        lst_yield.yield_bucket = yield_bucket;
+        lst_yield.recipient = ctx.accounts.recipient.key();
+        
+        Ok(())
+    }
+
+    /// Permissionless crank moving exchange-rate appreciation out of the reserve balance.
+    ///
+    /// Yield is measured against the highest rate seen, so a pool recovering from
+    /// a slash does not count the recovery as new yield. LST the protocol takes in
+    /// between harvests is left out until the next one, since it was bought above
+    /// `last_rate`.
+    pub fn harvest_lst_yield(
+        ctx: Context<HarvestLstYield>,
+    ) -> Result<()> {
+        let pool_rate = stake_pool::load_exchange_rate(&ctx.accounts.stake_pool)?;
+        
+        let current_time = Clock::get()?.unix_timestamp;
+        let vault = &mut ctx.accounts.vault;
+        let asset_config = &mut ctx.accounts.asset_config;
+        let lst_yield = &mut ctx.accounts.lst_yield;
+        
+        // Depositors hold their LST in kind and keep its appreciation; only the
+        // protocol-owned part held since the last harvest has appreciated from `last_rate`
+        let basis = asset_config.yield_basis.min(asset_config.protocol_balance());
+        let amount = stake_pool::accrued_yield(basis, lst_yield.last_rate, pool_rate.rate)?;
+        
+        let new_balance = asset_config.balance
+            .checked_sub(amount)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        let new_value = revalue_asset(
+            asset_config,
+            new_balance,
+            &ctx.accounts.price_feed,
+            current_time,
+        )?;
+        
+        let new_total_value = vault.total_value_usd
+            .checked_sub(asset_config.value_usd)
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            .checked_add(new_value)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        
+        asset_config.balance = new_balance;
+        asset_config.yield_basis = asset_config.protocol_balance();
+        asset_config.value_usd = new_value;
+        asset_config.current_weight_bps = calculate_weight(new_value, new_total_value)?;
+        
+        vault.total_value_usd = new_total_value;
+        vault.vhr = calculate_vhr(vault.total_value_usd, vault.liabilities_usd)?;
+        
+        lst_yield.record_yield(ctx.accounts.mint_state.current_epoch, amount)?;
+        lst_yield.last_rate = lst_yield.last_rate.max(pool_rate.rate);
+        lst_yield.last_harvest = current_time;
+        
+        Ok(())
+    }
+
+    /// Pay harvested yield to the tracker's treasury or savings recipient
+    pub fn distribute_lst_yield(
+        ctx: Context<DistributeLstYield>,
+    ) -> Result<()> {
+        let amount = ctx.accounts.lst_yield.accrued_yield;
+        require!(amount > 0, ErrorCode::NoYieldAccrued);
+        
+        let vault = &ctx.accounts.vault;
+        let vault_seeds = &[
+            b"vault",
+            vault.authority.as_ref(),
+            &[vault.bump],
+        ];
+        let signer = &[&vault_seeds[..]];
+        
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.vault_token_account.to_account_info(),
+                    to: ctx.accounts.recipient.to_account_info(),
+                    authority: vault.to_account_info(),
+                },
+                signer,
+            ),
+            amount,
+        )?;
+        
+        ctx.accounts.lst_yield.accrued_yield = 0;
+        
+        Ok(())
+    }
+
+    pub fn initialize_insurance_fund(
+        ctx: Context<InitializeInsuranceFund>,
+        redemption_fee_share_bps: u16,
+        stability_fee_share_bps: u16,
+    ) -> Result<()> {
+        let insurance_fund = &mut ctx.accounts.insurance_fund;
+        
+        insurance_fund.reserve_vault = ctx.accounts.vault.key();
+        insurance_fund.aru_account = ctx.accounts.aru_account.key();
+        insurance_fund.stability_fee_account = ctx.accounts.stability_fee_account.key();
+        insurance_fund.set(redemption_fee_share_bps, stability_fee_share_bps)?;
+        // Fees collected before the fund existed stay with the reserve
+        insurance_fund.stability_fee_checkpoint = ctx.accounts.stability_fee_account.amount;
+        insurance_fund.slash_inflows_usd = 0;
+        insurance_fund.redemption_fee_inflows_usd = 0;
+        insurance_fund.stability_fee_inflows_usd = 0;
+        insurance_fund.total_covered_usd = 0;
+        insurance_fund.value_usd = 0;
+        insurance_fund.last_report = 0;
+        insurance_fund.bump = ctx.bumps.insurance_fund;
+        
+        Ok(())
+    }
+
+    pub fn set_insurance_fee_shares(
+        ctx: Context<SetInsuranceFeeShares>,
+        redemption_fee_share_bps: u16,
+        stability_fee_share_bps: u16,
+    ) -> Result<()> {
+        ctx.accounts.insurance_fund.set(redemption_fee_share_bps, stability_fee_share_bps)
+    }
+
+    /// Deposit slashed stake proceeds, in a reserve asset, into the insurance fund
+    pub fn fund_insurance_from_slash(
+        ctx: Context<FundInsuranceFromSlash>,
+        amount: u64,
+    ) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidAmount);
+        
+        let current_time = Clock::get()?.unix_timestamp;
+        let asset_config = &ctx.accounts.asset_config;
+        let price = oracle::load_price(
+            &ctx.accounts.price_feed,
+            asset_config.max_price_age,
+            asset_config.max_confidence_bps,
+            current_time,
+        )?;
+        let value_usd = oracle::asset_value_usd(
+            amount,
+            asset_config.decimals,
+            price.price_usd,
+            asset_config.haircut_bps,
+        )?;
+        
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.authority_token_account.to_account_info(),
+                    to: ctx.accounts.fund_token_account.to_account_info(),
+                    authority: ctx.accounts.authority.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+        
+        ctx.accounts.insurance_fund.record_inflow(InsuranceSource::Slash, value_usd)?;
+        
+        emit!(InsuranceFunded {
+            reserve_vault: ctx.accounts.vault.key(),
+            source: InsuranceSource::Slash,
+            mint: asset_config.mint,
+            amount,
+            value_usd,
+            timestamp: current_time,
+        });
+        
+        Ok(())
     }
+
+    /// Permissionless crank moving the fund's share of newly collected stability fees.
+    ///
+    /// ars-core mints the reserve's share of stability fees as ARU into
+    /// `stability_fee_account`; anything above the last checkpoint is new.
+    pub fn route_stability_fees(
+        ctx: Context<RouteStabilityFees>,
+    ) -> Result<()> {
+        let balance = ctx.accounts.stability_fee_account.amount;
+        let insurance_fund = &mut ctx.accounts.insurance_fund;
+        
+        let new_fees = balance.saturating_sub(insurance_fund.stability_fee_checkpoint);
+        let amount = (new_fees as u128)
+            .checked_mul(insurance_fund.stability_fee_share_bps as u128)
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            .checked_div(10000)
+            .ok_or(ErrorCode::ArithmeticOverflow)? as u64;
+        require!(amount > 0, ErrorCode::NoNewFees);
+        
+        let vault = &ctx.accounts.vault;
+        let vault_seeds = &[
+            b"vault",
+            vault.authority.as_ref(),
+            &[vault.bump],
+        ];
+        let signer = &[&vault_seeds[..]];
+        
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.stability_fee_account.to_account_info(),
+                    to: ctx.accounts.aru_account.to_account_info(),
+                    authority: vault.to_account_info(),
+                },
+                signer,
+            ),
+            amount,
+        )?;
+        
+        let value_usd = oracle::asset_value_usd(
+            amount,
+            ctx.accounts.aru_mint.decimals,
+            vault.aru_price_usd,
+            0,
+        )?;
+        insurance_fund.stability_fee_checkpoint = balance
+            .checked_sub(amount)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        insurance_fund.record_inflow(InsuranceSource::StabilityFee, value_usd)?;
+        
+        emit!(InsuranceFunded {
+            reserve_vault: vault.key(),
+            source: InsuranceSource::StabilityFee,
+            mint: ctx.accounts.aru_mint.key(),
+            amount,
+            value_usd,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        
+        Ok(())
+    }
+
+    /// Move a reserve asset from the insurance fund into the reserve while VHR is below threshold
+    pub fn cover_shortfall(
+        ctx: Context<CoverShortfall>,
+        amount: u64,
+    ) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidAmount);
+        
+        let current_time = Clock::get()?.unix_timestamp;
+        let vault = &mut ctx.accounts.vault;
+        
+        sync_liabilities(
+            vault,
+            &ctx.accounts.mint_state,
+            &ctx.accounts.ili_oracle,
+            ctx.accounts.aru_mint.decimals,
+            current_time,
+        )?;
+        let vhr_before = calculate_vhr(vault.total_value_usd, vault.liabilities_usd)?;
+        require!(
+            ctx.accounts.global_state.vhr_below_threshold(vhr_before),
+            ErrorCode::NoShortfall
+        );
+        
+        let vault_key = vault.key();
+        let fund_seeds = &[
+            b"insurance_fund",
+            vault_key.as_ref(),
+            &[ctx.accounts.insurance_fund.bump],
+        ];
+        let signer = &[&fund_seeds[..]];
+        
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.fund_token_account.to_account_info(),
+                    to: ctx.accounts.vault_token_account.to_account_info(),
+                    authority: ctx.accounts.insurance_fund.to_account_info(),
+                },
+                signer,
+            ),
+            amount,
+        )?;
+        
+        let asset_config = &mut ctx.accounts.asset_config;
+        let old_value = asset_config.value_usd;
+        let new_balance = asset_config.balance
+            .checked_add(amount)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        let new_value = revalue_asset(
+            asset_config,
+            new_balance,
+            &ctx.accounts.price_feed,
+            current_time,
+        )?;
+        let value_usd = oracle::asset_value_usd(
+            amount,
+            asset_config.decimals,
+            asset_config.price_usd,
+            asset_config.haircut_bps,
+        )?;
+        
+        let new_total_value = vault.total_value_usd
+            .checked_sub(old_value)
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            .checked_add(new_value)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        
+        asset_config.balance = new_balance;
+        asset_config.value_usd = new_value;
+        asset_config.current_weight_bps = calculate_weight(new_value, new_total_value)?;
+        
+        vault.total_value_usd = new_total_value;
+        vault.vhr = calculate_vhr(new_total_value, vault.liabilities_usd)?;
+        
+        let insurance_fund = &mut ctx.accounts.insurance_fund;
+        insurance_fund.total_covered_usd = insurance_fund.total_covered_usd
+            .checked_add(value_usd)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        
+        emit!(ShortfallCovered {
+            reserve_vault: vault_key,
+            mint: asset_config.mint,
+            amount,
+            value_usd,
+            vhr_before,
+            vhr_after: vault.vhr,
+            timestamp: current_time,
+        });
+        
+        Ok(())
+    }
+
+    /// Burn ARU held by the insurance fund to cut liabilities while VHR is below threshold
+    pub fn cover_shortfall_with_aru(
+        ctx: Context<CoverShortfallWithAru>,
+        amount: u64,
+    ) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidAmount);
+        
+        let current_time = Clock::get()?.unix_timestamp;
+        let vault = &mut ctx.accounts.vault;
+        
+        sync_liabilities(
+            vault,
+            &ctx.accounts.mint_state,
+            &ctx.accounts.ili_oracle,
+            ctx.accounts.aru_mint.decimals,
+            current_time,
+        )?;
+        let vhr_before = calculate_vhr(vault.total_value_usd, vault.liabilities_usd)?;
+        require!(
+            ctx.accounts.global_state.vhr_below_threshold(vhr_before),
+            ErrorCode::NoShortfall
+        );
+        
+        let vault_key = vault.key();
+        let fund_seeds = &[
+            b"insurance_fund",
+            vault_key.as_ref(),
+            &[ctx.accounts.insurance_fund.bump],
+        ];
+        let signer = &[&fund_seeds[..]];
+        
+        ars_token::cpi::burn_aru(
+            CpiContext::new_with_signer(
+                ctx.accounts.ars_token_program.to_account_info(),
+                ars_token::cpi::accounts::BurnARU {
+                    mint_state: ctx.accounts.mint_state.to_account_info(),
+                    aru_mint: ctx.accounts.aru_mint.to_account_info(),
+                    source: ctx.accounts.aru_account.to_account_info(),
+                    authority: ctx.accounts.insurance_fund.to_account_info(),
+                    token_program: ctx.accounts.token_program.to_account_info(),
+                },
+                signer,
+            ),
+            amount,
+        )?;
+        ctx.accounts.mint_state.reload()?;
+        
+        let vault = &mut ctx.accounts.vault;
+        let value_usd = oracle::asset_value_usd(
+            amount,
+            ctx.accounts.aru_mint.decimals,
+            vault.aru_price_usd,
+            0,
+        )?;
+        sync_liabilities(
+            vault,
+            &ctx.accounts.mint_state,
+            &ctx.accounts.ili_oracle,
+            ctx.accounts.aru_mint.decimals,
+            current_time,
+        )?;
+        vault.vhr = calculate_vhr(vault.total_value_usd, vault.liabilities_usd)?;
+        
+        let insurance_fund = &mut ctx.accounts.insurance_fund;
+        insurance_fund.total_covered_usd = insurance_fund.total_covered_usd
+            .checked_add(value_usd)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        
+        emit!(ShortfallCovered {
+            reserve_vault: vault_key,
+            mint: ctx.accounts.aru_mint.key(),
+            amount,
+            value_usd,
+            vhr_before,
+            vhr_after: vault.vhr,
+            timestamp: current_time,
+        });
+        
+        Ok(())
+    }
+
+    /// Value the insurance fund's holdings and emit a report.
+    ///
+    /// Remaining accounts are `[asset_config, price_feed, fund_token_account]`
+    /// for every registered asset, in registry order.
+    pub fn report_insurance_fund<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ReportInsuranceFund<'info>>,
+    ) -> Result<()> {
+        let asset_registry = &ctx.accounts.asset_registry;
+        let remaining_accounts = ctx.remaining_accounts;
+        require!(
+            remaining_accounts.len() == asset_registry.assets.len() * 3,
+            ErrorCode::AssetListMismatch
+        );
+        
+        let current_time = Clock::get()?.unix_timestamp;
+        let insurance_fund = &mut ctx.accounts.insurance_fund;
+        
+        let mut value_usd = oracle::asset_value_usd(
+            ctx.accounts.aru_account.amount,
+            ctx.accounts.aru_mint.decimals,
+            ctx.accounts.vault.aru_price_usd,
+            0,
+        )?;
+        
+        for (expected, accounts) in asset_registry.assets.iter().zip(remaining_accounts.chunks(3)) {
+            require_keys_eq!(accounts[0].key(), *expected, ErrorCode::AssetListMismatch);
+            
+            let asset_config = Account::<AssetConfig>::try_from(&accounts[0])?;
+            require_keys_eq!(
+                accounts[1].key(),
+                asset_config.oracle_source,
+                ErrorCode::InvalidOracle
+            );
+            let fund_token_account = Account::<TokenAccount>::try_from(&accounts[2])?;
+            require!(
+                fund_token_account.mint == asset_config.mint
+                    && fund_token_account.owner == insurance_fund.key(),
+                ErrorCode::InvalidAssetVault
+            );
+            
+            let price = oracle::load_price(
+                &accounts[1],
+                asset_config.max_price_age,
+                asset_config.max_confidence_bps,
+                current_time,
+            )?;
+            value_usd = value_usd
+                .checked_add(oracle::asset_value_usd(
+                    fund_token_account.amount,
+                    asset_config.decimals,
+                    price.price_usd,
+                    asset_config.haircut_bps,
+                )?)
+                .ok_or(ErrorCode::ArithmeticOverflow)?;
+        }
+        
+        insurance_fund.value_usd = value_usd;
+        insurance_fund.last_report = current_time;
+        
+        emit!(InsuranceFundReported {
+            reserve_vault: insurance_fund.reserve_vault,
+            value_usd,
+            slash_inflows_usd: insurance_fund.slash_inflows_usd,
+            redemption_fee_inflows_usd: insurance_fund.redemption_fee_inflows_usd,
+            stability_fee_inflows_usd: insurance_fund.stability_fee_inflows_usd,
+            total_covered_usd: insurance_fund.total_covered_usd,
+            timestamp: current_time,
+        });
+        
+        Ok(())
+    }
+
+    pub fn initialize_attestation_log(
+        ctx: Context<InitializeAttestationLog>,
+    ) -> Result<()> {
+        let attestation_log = &mut ctx.accounts.attestation_log;
+        
+        attestation_log.reserve_vault = ctx.accounts.vault.key();
+        attestation_log.latest_epoch = 0;
+        attestation_log.latest_hash = [0; 32];
+        attestation_log.count = 0;
+        attestation_log.bump = ctx.bumps.attestation_log;
+        
+        Ok(())
+    }
+
+    /// Permissionless crank writing this epoch's proof-of-reserves attestation.
+    ///
+    /// Balances are read from the asset token accounts themselves, not from the
+    /// reserve's books. Remaining accounts are `[asset_config, price_feed,
+    /// vault_token_account]` for every registered asset, in registry order.
+    pub fn attest_reserves<'info>(
+        ctx: Context<'_, '_, 'info, 'info, AttestReserves<'info>>,
+    ) -> Result<()> {
+        verify_no_flash_loan(&ctx.accounts.instructions)?;
+        
+        let asset_registry = &ctx.accounts.asset_registry;
+        let remaining_accounts = ctx.remaining_accounts;
+        require!(
+            remaining_accounts.len() == asset_registry.assets.len() * 3,
+            ErrorCode::AssetListMismatch
+        );
+        
+        let clock = Clock::get()?;
+        let mut assets = Vec::with_capacity(asset_registry.assets.len());
+        let mut total_value_usd: u64 = 0;
+        
+        for (expected, accounts) in asset_registry.assets.iter().zip(remaining_accounts.chunks(3)) {
+            require_keys_eq!(accounts[0].key(), *expected, ErrorCode::AssetListMismatch);
+            
+            let asset_config = Account::<AssetConfig>::try_from(&accounts[0])?;
+            require_keys_eq!(
+                accounts[1].key(),
+                asset_config.oracle_source,
+                ErrorCode::InvalidOracle
+            );
+            require_keys_eq!(
+                accounts[2].key(),
+                asset_config.vault,
+                ErrorCode::InvalidAssetVault
+            );
+            let token_account = Account::<TokenAccount>::try_from(&accounts[2])?;
+            
+            let price = oracle::load_price(
+                &accounts[1],
+                asset_config.max_price_age,
+                asset_config.max_confidence_bps,
+                clock.unix_timestamp,
+            )?;
+            let value_usd = oracle::asset_value_usd(
+                token_account.amount,
+                asset_config.decimals,
+                price.price_usd,
+                asset_config.haircut_bps,
+            )?;
+            total_value_usd = total_value_usd
+                .checked_add(value_usd)
+                .ok_or(ErrorCode::ArithmeticOverflow)?;
+            
+            assets.push(AttestedAsset {
+                mint: asset_config.mint,
+                token_account: token_account.key(),
+                balance: token_account.amount,
+                price_usd: price.price_usd,
+                value_usd,
+            });
+        }
+        
+        let (_, liabilities_usd) = value_liabilities(
+            &ctx.accounts.mint_state,
+            &ctx.accounts.ili_oracle,
+            ctx.accounts.aru_mint.decimals,
+        )?;
+        
+        let attestation_log = &mut ctx.accounts.attestation_log;
+        let attestation = &mut ctx.accounts.attestation;
+        attestation.reserve_vault = ctx.accounts.vault.key();
+        attestation.epoch = ctx.accounts.mint_state.current_epoch;
+        attestation.prev_hash = attestation_log.latest_hash;
+        attestation.assets = assets;
+        attestation.total_value_usd = total_value_usd;
+        attestation.liabilities_usd = liabilities_usd;
+        attestation.aru_supply = ctx.accounts.mint_state.total_supply;
+        attestation.vhr = calculate_vhr(total_value_usd, liabilities_usd)?;
+        attestation.slot = clock.slot;
+        attestation.timestamp = clock.unix_timestamp;
+        attestation.bump = ctx.bumps.attestation;
+        attestation.hash = attestation.compute_hash();
+        
+        attestation_log.latest_epoch = attestation.epoch;
+        attestation_log.latest_hash = attestation.hash;
+        attestation_log.count = attestation_log.count
+            .checked_add(1)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        
+        emit!(ReserveAttested {
+            reserve_vault: attestation.reserve_vault,
+            epoch: attestation.epoch,
+            hash: attestation.hash,
+            total_value_usd,
+            liabilities_usd,
+            vhr: attestation.vhr,
+            timestamp: attestation.timestamp,
+        });
+        
+        Ok(())
+    }
+
+    /// Freeze the reserve once ars-core has entered global settlement.
+    ///
+    /// Permissionless, so ARU holders never depend on the vault authority to
+    /// reach their claims. Remaining accounts are `[asset_config, price_feed]`
+    /// for every registered asset, in registry order.
+    pub fn initiate_settlement<'info>(
+        ctx: Context<'_, '_, 'info, 'info, InitiateSettlement<'info>>,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.global_state.settlement_active,
+            ErrorCode::SettlementNotActive
+        );
+        
+        let asset_registry = &ctx.accounts.asset_registry;
+        let remaining_accounts = ctx.remaining_accounts;
+        require!(
+            remaining_accounts.len() == asset_registry.assets.len() * 2,
+            ErrorCode::AssetListMismatch
+        );
+        
+        let current_time = Clock::get()?.unix_timestamp;
+        let mut assets = Vec::with_capacity(asset_registry.assets.len());
+        let mut total_value_usd: u64 = 0;
+        
+        for (expected, accounts) in asset_registry.assets.iter().zip(remaining_accounts.chunks(2)) {
+            require_keys_eq!(accounts[0].key(), *expected, ErrorCode::AssetListMismatch);
+            
+            let mut asset_config = Account::<AssetConfig>::try_from(&accounts[0])?;
+            require_keys_eq!(
+                accounts[1].key(),
+                asset_config.oracle_source,
+                ErrorCode::InvalidOracle
+            );
+            require!(asset_config.deployed == 0, ErrorCode::StrategiesDeployed);
+            
+            // Claims are paid in kind, so a dead feed falls back to the last stored price
+            // rather than blocking the wind-down
+            if let Ok(price) = oracle::load_price(
+                &accounts[1],
+                asset_config.max_price_age,
+                asset_config.max_confidence_bps,
+                current_time,
+            ) {
+                asset_config.price_usd = price.price_usd;
+                asset_config.last_price_update = current_time;
+            }
+            // Depositor positions and their queued withdrawals are owed in kind and
+            // leave through exit_settled_position, not ARU claims
+            let settled_balance = asset_config.protocol_idle_balance();
+            asset_config.value_usd = oracle::asset_value_usd(
+                settled_balance,
+                asset_config.decimals,
+                asset_config.price_usd,
+                asset_config.haircut_bps,
+            )?;
+            total_value_usd = total_value_usd
+                .checked_add(asset_config.value_usd)
+                .ok_or(ErrorCode::ArithmeticOverflow)?;
+            
+            assets.push(SettledAsset {
+                asset_config: asset_config.key(),
+                mint: asset_config.mint,
+                price_usd: asset_config.price_usd,
+                balance: settled_balance,
+                claimed: 0,
+            });
+            asset_config.exit(&crate::ID)?;
+        }
+        
+        let vault = &mut ctx.accounts.vault;
+        vault.total_value_usd = total_value_usd;
+        vault.settled = true;
+        
+        let settlement = &mut ctx.accounts.settlement;
+        settlement.reserve_vault = vault.key();
+        settlement.aru_supply = ctx.accounts.mint_state.total_supply;
+        settlement.claimed_aru = 0;
+        settlement.total_value_usd = total_value_usd;
+        settlement.started_at = current_time;
+        settlement.assets = assets;
+        settlement.bump = ctx.bumps.settlement;
+        
+        emit!(SettlementInitiated {
+            reserve_vault: vault.key(),
+            aru_supply: settlement.aru_supply,
+            total_value_usd,
+            timestamp: current_time,
+        });
+        
+        Ok(())
+    }
+
+    /// Burn ARU for its fixed pro-rata share of every settled reserve asset.
+    ///
+    /// Remaining accounts are `[asset_config, vault_token_account,
+    /// user_token_account]` for every settled asset, in snapshot order.
+    pub fn claim_settlement<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ClaimSettlement<'info>>,
+        aru_amount: u64,
+    ) -> Result<()> {
+        require!(aru_amount > 0, ErrorCode::InvalidAmount);
+        
+        let settlement = &mut ctx.accounts.settlement;
+        let remaining_accounts = ctx.remaining_accounts;
+        require!(
+            remaining_accounts.len() == settlement.assets.len() * 3,
+            ErrorCode::AssetListMismatch
+        );
+        
+        let claimed_aru = settlement.claimed_aru
+            .checked_add(aru_amount)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        require!(claimed_aru <= settlement.aru_supply, ErrorCode::InsufficientBalance);
+        
+        let vault = &ctx.accounts.vault;
+        let vault_seeds = &[
+            b"vault",
+            vault.authority.as_ref(),
+            &[vault.bump],
+        ];
+        let signer = &[&vault_seeds[..]];
+        
+        for index in 0..settlement.assets.len() {
+            let accounts = &remaining_accounts[index * 3..index * 3 + 3];
+            let payout = settlement
+                .payout(&settlement.assets[index], aru_amount)
+                .ok_or(ErrorCode::ArithmeticOverflow)?;
+            let asset = &mut settlement.assets[index];
+            require_keys_eq!(accounts[0].key(), asset.asset_config, ErrorCode::AssetListMismatch);
+            
+            let mut asset_config = Account::<AssetConfig>::try_from(&accounts[0])?;
+            require_keys_eq!(
+                accounts[1].key(),
+                asset_config.vault,
+                ErrorCode::InvalidAssetVault
+            );
+            let user_token_account = Account::<TokenAccount>::try_from(&accounts[2])?;
+            require_keys_eq!(
+                user_token_account.mint,
+                asset.mint,
+                ErrorCode::InvalidAssetVault
+            );
+            
+            if payout == 0 {
+                continue;
+            }
+            
+            token::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: accounts[1].clone(),
+                        to: accounts[2].clone(),
+                        authority: vault.to_account_info(),
+                    },
+                    signer,
+                ),
+                payout,
+            )?;
+            
+            asset.claimed = asset.claimed
+                .checked_add(payout)
+                .ok_or(ErrorCode::ArithmeticOverflow)?;
+            asset_config.balance = asset_config.balance
+                .checked_sub(payout)
+                .ok_or(ErrorCode::ArithmeticOverflow)?;
+            asset_config.clamp_yield_basis();
+            asset_config.value_usd = oracle::asset_value_usd(
+                asset_config.balance,
+                asset_config.decimals,
+                asset.price_usd,
+                asset_config.haircut_bps,
+            )?;
+            asset_config.exit(&crate::ID)?;
+        }
+        
+        settlement.claimed_aru = claimed_aru;
+        
+        ars_token::cpi::burn_aru(
+            CpiContext::new(
+                ctx.accounts.ars_token_program.to_account_info(),
+                ars_token::cpi::accounts::BurnARU {
+                    mint_state: ctx.accounts.mint_state.to_account_info(),
+                    aru_mint: ctx.accounts.aru_mint.to_account_info(),
+                    source: ctx.accounts.user_aru_account.to_account_info(),
+                    authority: ctx.accounts.user.to_account_info(),
+                    token_program: ctx.accounts.token_program.to_account_info(),
+                },
+            ),
+            aru_amount,
+        )?;
+        
+        emit!(SettlementClaimed {
+            reserve_vault: ctx.accounts.vault.key(),
+            claimant: ctx.accounts.user.key(),
+            aru_amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        
+        Ok(())
+    }
+
+    /// Return a depositor's position in kind once the reserve is settled.
+    ///
+    /// Queued withdrawals are cancelled back into the position first.
+    pub fn exit_settled_position(
+        ctx: Context<ExitSettledPosition>,
+    ) -> Result<()> {
+        let amount = ctx.accounts.position.amount;
+        require!(amount > 0, ErrorCode::InvalidAmount);
+        require!(
+            amount <= ctx.accounts.vault_token_account.amount,
+            ErrorCode::InsufficientBalance
+        );
+        
+        let vault = &ctx.accounts.vault;
+        let vault_seeds = &[
+            b"vault",
+            vault.authority.as_ref(),
+            &[vault.bump],
+        ];
+        let signer = &[&vault_seeds[..]];
+        
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.vault_token_account.to_account_info(),
+                    to: ctx.accounts.owner_token_account.to_account_info(),
+                    authority: vault.to_account_info(),
+                },
+                signer,
+            ),
+            amount,
+        )?;
+        
+        let current_time = Clock::get()?.unix_timestamp;
+        let asset_config = &mut ctx.accounts.asset_config;
+        asset_config.balance = asset_config.balance.saturating_sub(amount);
+        asset_config.position_balance = asset_config.position_balance.saturating_sub(amount);
+        
+        let position = &mut ctx.accounts.position;
+        position.amount = 0;
+        position.total_withdrawn = position.total_withdrawn
+            .checked_add(amount)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        position.last_update = current_time;
+        
+        emit!(SettledPositionExited {
+            reserve_vault: vault.key(),
+            owner: position.owner,
+            mint: asset_config.mint,
+            amount,
+            timestamp: current_time,
+        });
+        
+        Ok(())
+    }
+
+    pub fn initialize_recap_auction(
+        ctx: Context<InitializeRecapAuction>,
+        params: RecapAuctionParams,
+    ) -> Result<()> {
+        params.validate()?;
+        
+        let recap_auction = &mut ctx.accounts.recap_auction;
+        recap_auction.reserve_vault = ctx.accounts.vault.key();
+        recap_auction.asset_config = ctx.accounts.asset_config.key();
+        recap_auction.apply(&params);
+        recap_auction.active = false;
+        recap_auction.round = 0;
+        recap_auction.started_at = 0;
+        recap_auction.round_face_sold = 0;
+        recap_auction.total_face_sold = 0;
+        recap_auction.total_raised_usd = 0;
+        recap_auction.outstanding_face = 0;
+        recap_auction.bonds_issued = 0;
+        recap_auction.bump = ctx.bumps.recap_auction;
+        
+        Ok(())
+    }
+
+    pub fn update_recap_auction(
+        ctx: Context<UpdateRecapAuction>,
+        params: RecapAuctionParams,
+    ) -> Result<()> {
+        params.validate()?;
+        
+        let recap_auction = &mut ctx.accounts.recap_auction;
+        require!(!recap_auction.active, ErrorCode::RecapAuctionActive);
+        recap_auction.apply(&params);
+        
+        Ok(())
+    }
+
+    /// Open a recapitalization round; permissionless while VHR is below the
+    /// ars-core threshold
+    pub fn start_recap_auction(ctx: Context<CrankRecapAuction>) -> Result<()> {
+        let current_time = Clock::get()?.unix_timestamp;
+        let vault = &mut ctx.accounts.vault;
+        
+        sync_liabilities(
+            vault,
+            &ctx.accounts.mint_state,
+            &ctx.accounts.ili_oracle,
+            ctx.accounts.aru_mint.decimals,
+            current_time,
+        )?;
+        vault.vhr = calculate_vhr(vault.total_value_usd, vault.liabilities_usd)?;
+        require!(
+            ctx.accounts.global_state.vhr_below_threshold(vault.vhr),
+            ErrorCode::NoShortfall
+        );
+        
+        let recap_auction = &mut ctx.accounts.recap_auction;
+        require!(!recap_auction.active, ErrorCode::RecapAuctionActive);
+        
+        recap_auction.active = true;
+        recap_auction.round = recap_auction.round
+            .checked_add(1)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        recap_auction.started_at = current_time;
+        recap_auction.round_face_sold = 0;
+        
+        emit!(RecapAuctionStarted {
+            reserve_vault: vault.key(),
+            round: recap_auction.round,
+            vhr: vault.vhr,
+            timestamp: current_time,
+        });
+        
+        Ok(())
+    }
+
+    /// Close the running round once it has expired or VHR is restored
+    pub fn end_recap_auction(ctx: Context<CrankRecapAuction>) -> Result<()> {
+        let current_time = Clock::get()?.unix_timestamp;
+        let vault = &mut ctx.accounts.vault;
+        let recap_auction = &mut ctx.accounts.recap_auction;
+        require!(recap_auction.active, ErrorCode::RecapAuctionInactive);
+        
+        sync_liabilities(
+            vault,
+            &ctx.accounts.mint_state,
+            &ctx.accounts.ili_oracle,
+            ctx.accounts.aru_mint.decimals,
+            current_time,
+        )?;
+        vault.vhr = calculate_vhr(vault.total_value_usd, vault.liabilities_usd)?;
+        require!(
+            recap_auction.expired(current_time)
+                || !ctx.accounts.global_state.vhr_below_threshold(vault.vhr),
+            ErrorCode::RecapAuctionLive
+        );
+        
+        recap_auction.active = false;
+        
+        emit!(RecapAuctionEnded {
+            reserve_vault: vault.key(),
+            round: recap_auction.round,
+            face_sold: recap_auction.round_face_sold,
+            vhr: vault.vhr,
+            timestamp: current_time,
+        });
+        
+        Ok(())
+    }
+
+    /// Buy a recapitalization bond for `face_aru` at the current Dutch auction discount.
+    ///
+    /// The bid is paid in the auction's stable asset and flows into the reserve.
+    /// The round closes itself on the bid that restores VHR to the threshold.
+    pub fn bid_recap_auction(
+        ctx: Context<BidRecapAuction>,
+        face_aru: u64,
+        max_amount_in: u64,
+    ) -> Result<()> {
+        require!(face_aru > 0, ErrorCode::InvalidAmount);
+        
+        let current_time = Clock::get()?.unix_timestamp;
+        let recap_auction = &mut ctx.accounts.recap_auction;
+        require!(
+            recap_auction.active && !recap_auction.expired(current_time),
+            ErrorCode::RecapAuctionInactive
+        );
+        let round_face_sold = recap_auction.round_face_sold
+            .checked_add(face_aru)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        require!(
+            round_face_sold <= recap_auction.max_face_per_round,
+            ErrorCode::RecapRoundCapExceeded
+        );
+        
+        let vault = &mut ctx.accounts.vault;
+        sync_liabilities(
+            vault,
+            &ctx.accounts.mint_state,
+            &ctx.accounts.ili_oracle,
+            ctx.accounts.aru_mint.decimals,
+            current_time,
+        )?;
+        require!(vault.aru_price_usd > 0, ErrorCode::InvalidILIPrice);
+        require!(
+            ctx.accounts.global_state.vhr_below_threshold(
+                calculate_vhr(vault.total_value_usd, vault.liabilities_usd)?
+            ),
+            ErrorCode::NoShortfall
+        );
+        
+        let asset_config = &mut ctx.accounts.asset_config;
+        let old_value = asset_config.value_usd;
+        let balance = asset_config.balance;
+        revalue_asset(asset_config, balance, &ctx.accounts.price_feed, current_time)?;
+        
+        // Bidders pay face value at the ILI price less the current discount
+        let discount_bps = recap_auction.discount_bps(current_time);
+        let face_usd = oracle::asset_value_usd(
+            face_aru,
+            ctx.accounts.aru_mint.decimals,
+            vault.aru_price_usd,
+            0,
+        )?;
+        let value_usd = apply_fee(face_usd, discount_bps)?;
+        let amount_in = oracle::amount_for_value_usd(
+            value_usd,
+            asset_config.decimals,
+            asset_config.price_usd,
+        )?;
+        require!(amount_in > 0, ErrorCode::InvalidAmount);
+        require!(amount_in <= max_amount_in, ErrorCode::SlippageExceeded);
+        
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.bidder_token_account.to_account_info(),
+                    to: ctx.accounts.vault_token_account.to_account_info(),
+                    authority: ctx.accounts.bidder.to_account_info(),
+                },
+            ),
+            amount_in,
+        )?;
+        
+        let new_balance = balance
+            .checked_add(amount_in)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        let new_value = oracle::asset_value_usd(
+            new_balance,
+            asset_config.decimals,
+            asset_config.price_usd,
+            asset_config.haircut_bps,
+        )?;
+        let new_total_value = vault.total_value_usd
+            .checked_sub(old_value)
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            .checked_add(new_value)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        
+        asset_config.balance = new_balance;
+        asset_config.value_usd = new_value;
+        asset_config.current_weight_bps = calculate_weight(new_value, new_total_value)?;
+        
+        vault.total_value_usd = new_total_value;
+        vault.vhr = calculate_vhr(new_total_value, vault.liabilities_usd)?;
+        
+        let bond = &mut ctx.accounts.bond;
+        bond.auction = recap_auction.key();
+        bond.owner = ctx.accounts.bidder.key();
+        bond.id = recap_auction.bonds_issued;
+        bond.round = recap_auction.round;
+        bond.face_aru = face_aru;
+        bond.paid_usd = value_usd;
+        bond.matures_at = current_time
+            .checked_add(recap_auction.bond_term)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        bond.bump = ctx.bumps.bond;
+        
+        recap_auction.round_face_sold = round_face_sold;
+        recap_auction.total_face_sold = recap_auction.total_face_sold
+            .checked_add(face_aru)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        recap_auction.total_raised_usd = recap_auction.total_raised_usd
+            .checked_add(value_usd)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        recap_auction.outstanding_face = recap_auction.outstanding_face
+            .checked_add(face_aru)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        recap_auction.bonds_issued = recap_auction.bonds_issued
+            .checked_add(1)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        
+        emit!(RecapBondPurchased {
+            reserve_vault: vault.key(),
+            round: recap_auction.round,
+            bond: bond.key(),
+            owner: bond.owner,
+            face_aru,
+            discount_bps,
+            amount_in,
+            value_usd,
+            vhr_after: vault.vhr,
+            timestamp: current_time,
+        });
+        
+        if !ctx.accounts.global_state.vhr_below_threshold(vault.vhr) {
+            recap_auction.active = false;
+            
+            emit!(RecapAuctionEnded {
+                reserve_vault: vault.key(),
+                round: recap_auction.round,
+                face_sold: recap_auction.round_face_sold,
+                vhr: vault.vhr,
+                timestamp: current_time,
+            });
+        }
+        
+        Ok(())
+    }
+
+    /// Mint a matured bond's face value in ARU to its owner and close the bond.
+    ///
+    /// Bonds are junior to ARU holders: they only pay out while the new supply
+    /// keeps VHR at or above the ars-core threshold.
+    pub fn redeem_recap_bond(ctx: Context<RedeemRecapBond>) -> Result<()> {
+        let current_time = Clock::get()?.unix_timestamp;
+        let bond = &ctx.accounts.bond;
+        require!(current_time >= bond.matures_at, ErrorCode::BondNotMature);
+        let face_aru = bond.face_aru;
+        
+        let vault = &ctx.accounts.vault;
+        let vault_seeds = &[
+            b"vault",
+            vault.authority.as_ref(),
+            &[vault.bump],
+        ];
+        let signer = &[&vault_seeds[..]];
+        
+        ars_token::cpi::minter_mint(
+            CpiContext::new_with_signer(
+                ctx.accounts.ars_token_program.to_account_info(),
+                ars_token::cpi::accounts::MinterMint {
+                    mint_state: ctx.accounts.mint_state.to_account_info(),
+                    minter_account: ctx.accounts.minter_account.to_account_info(),
+                    aru_mint: ctx.accounts.aru_mint.to_account_info(),
+                    destination: ctx.accounts.owner_aru_account.to_account_info(),
+                    minter: vault.to_account_info(),
+                    token_program: ctx.accounts.token_program.to_account_info(),
+                },
+                signer,
+            ),
+            face_aru,
+        )?;
+        ctx.accounts.mint_state.reload()?;
+        
+        let vault = &mut ctx.accounts.vault;
+        sync_liabilities(
+            vault,
+            &ctx.accounts.mint_state,
+            &ctx.accounts.ili_oracle,
+            ctx.accounts.aru_mint.decimals,
+            current_time,
+        )?;
+        vault.vhr = calculate_vhr(vault.total_value_usd, vault.liabilities_usd)?;
+        require!(
+            !ctx.accounts.global_state.vhr_below_threshold(vault.vhr),
+            ErrorCode::BondRedemptionBlocked
+        );
+        
+        let recap_auction = &mut ctx.accounts.recap_auction;
+        recap_auction.outstanding_face = recap_auction.outstanding_face
+            .checked_sub(face_aru)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        
+        emit!(RecapBondRedeemed {
+            reserve_vault: vault.key(),
+            bond: ctx.accounts.bond.key(),
+            owner: ctx.accounts.owner.key(),
+            face_aru,
+            timestamp: current_time,
+        });
+        
+        Ok(())
+    }
+
+    pub fn add_flash_loan_asset(
+        ctx: Context<AddFlashLoanAsset>,
+        params: FlashLoanParams,
+    ) -> Result<()> {
+        params.validate()?;
+        
+        let flash_loan_config = &mut ctx.accounts.flash_loan_config;
+        flash_loan_config.reserve_vault = ctx.accounts.vault.key();
+        flash_loan_config.asset_config = ctx.accounts.asset_config.key();
+        flash_loan_config.apply(&params);
+        flash_loan_config.outstanding = 0;
+        flash_loan_config.total_borrowed = 0;
+        flash_loan_config.total_fees = 0;
+        flash_loan_config.bump = ctx.bumps.flash_loan_config;
+        
+        Ok(())
+    }
+
+    pub fn update_flash_loan_asset(
+        ctx: Context<UpdateFlashLoanAsset>,
+        params: FlashLoanParams,
+    ) -> Result<()> {
+        params.validate()?;
+        
+        ctx.accounts.flash_loan_config.apply(&params);
+        
+        Ok(())
+    }
+
+    /// Lend a reserve asset for the rest of the transaction.
+    ///
+    /// Must be a top-level instruction, and the next ars-reserve instruction in the
+    /// transaction must be `repay_flash_loan` for the same asset and amount.
+    pub fn flash_loan(
+        ctx: Context<FlashLoan>,
+        amount: u64,
+    ) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidAmount);
+        
+        let flash_loan_config = &mut ctx.accounts.flash_loan_config;
+        require!(flash_loan_config.outstanding == 0, ErrorCode::FlashLoanActive);
+        
+        // Only tokens sitting in the reserve's account can be lent
+        let limit = (ctx.accounts.asset_config.idle_balance() as u128)
+            .checked_mul(flash_loan_config.max_loan_bps as u128)
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            .checked_div(10000)
+            .ok_or(ErrorCode::ArithmeticOverflow)? as u64;
+        require!(amount <= limit, ErrorCode::FlashLoanLimitExceeded);
+        
+        verify_flash_repayment(
+            &ctx.accounts.instructions,
+            flash_loan_config.key(),
+            amount,
+        )?;
+        
+        let vault = &ctx.accounts.vault;
+        let vault_seeds = &[
+            b"vault",
+            vault.authority.as_ref(),
+            &[vault.bump],
+        ];
+        let signer = &[&vault_seeds[..]];
+        
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.vault_token_account.to_account_info(),
+                    to: ctx.accounts.borrower_token_account.to_account_info(),
+                    authority: vault.to_account_info(),
+                },
+                signer,
+            ),
+            amount,
+        )?;
+        
+        flash_loan_config.outstanding = amount;
+        
+        Ok(())
+    }
+
+    /// Return an outstanding flash loan plus its fee; the fee stays in the reserve
+    pub fn repay_flash_loan(
+        ctx: Context<RepayFlashLoan>,
+        amount: u64,
+    ) -> Result<()> {
+        let flash_loan_config = &mut ctx.accounts.flash_loan_config;
+        require!(flash_loan_config.outstanding > 0, ErrorCode::NoFlashLoan);
+        require!(amount == flash_loan_config.outstanding, ErrorCode::InvalidAmount);
+        
+        let fee = flash_loan_config.fee(amount).ok_or(ErrorCode::ArithmeticOverflow)?;
+        
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.borrower_token_account.to_account_info(),
+                    to: ctx.accounts.vault_token_account.to_account_info(),
+                    authority: ctx.accounts.borrower.to_account_info(),
+                },
+            ),
+            amount.checked_add(fee).ok_or(ErrorCode::ArithmeticOverflow)?,
+        )?;
+        
+        flash_loan_config.outstanding = 0;
+        flash_loan_config.total_borrowed = flash_loan_config.total_borrowed
+            .checked_add(amount)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        flash_loan_config.total_fees = flash_loan_config.total_fees
+            .checked_add(fee)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        
+        // Fees are booked at the last oracle price; the next refresh reprices them
+        let vault = &mut ctx.accounts.vault;
+        let asset_config = &mut ctx.accounts.asset_config;
+        let old_value = asset_config.value_usd;
+        asset_config.balance = asset_config.balance
+            .checked_add(fee)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        asset_config.value_usd = oracle::asset_value_usd(
+            asset_config.balance,
+            asset_config.decimals,
+            asset_config.price_usd,
+            asset_config.haircut_bps,
+        )?;
+        vault.total_value_usd = vault.total_value_usd
+            .checked_sub(old_value)
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            .checked_add(asset_config.value_usd)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        vault.vhr = calculate_vhr(vault.total_value_usd, vault.liabilities_usd)?;
+        
+        emit!(FlashLoanRepaid {
+            reserve_vault: vault.key(),
+            mint: asset_config.mint,
+            borrower: ctx.accounts.borrower.key(),
+            amount,
+            fee,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        
+        Ok(())
+    }
+
+    pub fn add_strategy(
+        ctx: Context<AddStrategy>,
+        max_allocation_bps: u16,
+        adapter_accounts: Vec<Pubkey>,
+    ) -> Result<()> {
+        require!(max_allocation_bps <= 10000, ErrorCode::InvalidThreshold);
+        require!(
+            adapter_accounts.len() <= MAX_STRATEGY_ACCOUNTS,
+            ErrorCode::StrategyAccountsMismatch
+        );
+        
+        let strategy = &mut ctx.accounts.strategy;
+        strategy.reserve_vault = ctx.accounts.vault.key();
+        strategy.asset_config = ctx.accounts.asset_config.key();
+        strategy.adapter_program = ctx.accounts.adapter_program.key();
+        strategy.adapter_accounts = adapter_accounts;
+        strategy.max_allocation_bps = max_allocation_bps;
+        strategy.deployed = 0;
+        strategy.total_gains = 0;
+        strategy.total_losses = 0;
+        strategy.last_report = 0;
+        strategy.unwound = false;
+        strategy.bump = ctx.bumps.strategy;
+        
+        Ok(())
+    }
+
+    /// Change a strategy's cap; also re-enables a strategy after an emergency unwind
+    pub fn update_strategy(
+        ctx: Context<UpdateStrategy>,
+        max_allocation_bps: u16,
+    ) -> Result<()> {
+        require!(max_allocation_bps <= 10000, ErrorCode::InvalidThreshold);
+        
+        let strategy = &mut ctx.accounts.strategy;
+        strategy.max_allocation_bps = max_allocation_bps;
+        strategy.unwound = false;
+        
+        Ok(())
+    }
+
+    /// Deploy idle reserve balance into a strategy, up to its share of the asset.
+    ///
+    /// Deployed tokens stay on the reserve's books; remaining accounts are the
+    /// strategy's registered adapter accounts.
+    pub fn deploy_to_strategy<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ManageStrategy<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidAmount);
+        require!(!ctx.accounts.vault.settled, ErrorCode::ReserveSettled);
+        
+        let strategy = &ctx.accounts.strategy;
+        require!(!strategy.unwound, ErrorCode::StrategyUnwound);
+        check_strategy_accounts(strategy, ctx.remaining_accounts)?;
+        
+        let deployed = strategy.deployed
+            .checked_add(amount)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        let cap = (ctx.accounts.asset_config.balance as u128)
+            .checked_mul(strategy.max_allocation_bps as u128)
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            .checked_div(10000)
+            .ok_or(ErrorCode::ArithmeticOverflow)? as u64;
+        require!(deployed <= cap, ErrorCode::StrategyCapExceeded);
+        require!(
+            amount <= ctx.accounts.asset_config.idle_balance(),
+            ErrorCode::InsufficientIdleBalance
+        );
+        
+        let vault = &ctx.accounts.vault;
+        let vault_seeds = &[
+            b"vault",
+            vault.authority.as_ref(),
+            &[vault.bump],
+        ];
+        let signer = &[&vault_seeds[..]];
+        
+        strategy_adapter::deposit(
+            &ctx.accounts.adapter_program.to_account_info(),
+            &vault.to_account_info(),
+            &ctx.accounts.vault_token_account.to_account_info(),
+            &ctx.accounts.token_program.to_account_info(),
+            ctx.remaining_accounts,
+            amount,
+            signer,
+        )?;
+        
+        let strategy = &mut ctx.accounts.strategy;
+        sync_deployed(&mut ctx.accounts.asset_config, strategy.deployed, deployed)?;
+        strategy.deployed = deployed;
+        
+        emit!(StrategyRebalanced {
+            strategy: strategy.key(),
+            mint: ctx.accounts.asset_config.mint,
+            deposited: amount,
+            withdrawn: 0,
+            deployed,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        
+        Ok(())
+    }
+
+    /// Pull up to `amount` back from a strategy into the reserve's token account.
+    ///
+    /// Anything returned beyond the deployed balance is booked as a gain.
+    pub fn withdraw_from_strategy<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ManageStrategy<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidAmount);
+        check_strategy_accounts(&ctx.accounts.strategy, ctx.remaining_accounts)?;
+        
+        let received = withdraw_strategy_funds(ctx.accounts, ctx.remaining_accounts, amount)?;
+        
+        let strategy = &mut ctx.accounts.strategy;
+        let gain = received.saturating_sub(strategy.deployed);
+        let deployed = strategy.deployed.saturating_sub(received);
+        sync_deployed(&mut ctx.accounts.asset_config, strategy.deployed, deployed)?;
+        strategy.deployed = deployed;
+        strategy.total_gains = strategy.total_gains
+            .checked_add(gain)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        book_strategy_result(
+            &mut ctx.accounts.vault,
+            &mut ctx.accounts.asset_config,
+            gain,
+            0,
+        )?;
+        
+        emit!(StrategyRebalanced {
+            strategy: strategy.key(),
+            mint: ctx.accounts.asset_config.mint,
+            deposited: 0,
+            withdrawn: received,
+            deployed: strategy.deployed,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        
+        Ok(())
+    }
+
+    /// Permissionless crank marking a strategy to the value its adapter reports.
+    ///
+    /// Gains and losses are booked against the asset's reserve balance.
+    pub fn report_strategy<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ReportStrategy<'info>>,
+    ) -> Result<()> {
+        check_strategy_accounts(&ctx.accounts.strategy, ctx.remaining_accounts)?;
+        
+        let vault = &ctx.accounts.vault;
+        let vault_seeds = &[
+            b"vault",
+            vault.authority.as_ref(),
+            &[vault.bump],
+        ];
+        let signer = &[&vault_seeds[..]];
+        
+        let value = strategy_adapter::report_value(
+            &ctx.accounts.adapter_program.to_account_info(),
+            &vault.to_account_info(),
+            ctx.remaining_accounts,
+            signer,
+        )?;
+        
+        let current_time = Clock::get()?.unix_timestamp;
+        let strategy = &mut ctx.accounts.strategy;
+        let previous = strategy.deployed;
+        let (gain, loss) = strategy.mark_to(value)?;
+        sync_deployed(&mut ctx.accounts.asset_config, previous, strategy.deployed)?;
+        strategy.last_report = current_time;
+        book_strategy_result(
+            &mut ctx.accounts.vault,
+            &mut ctx.accounts.asset_config,
+            gain,
+            loss,
+        )?;
+        
+        emit!(StrategyReported {
+            strategy: strategy.key(),
+            mint: ctx.accounts.asset_config.mint,
+            value,
+            gain,
+            loss,
+            timestamp: current_time,
+        });
+        
+        Ok(())
+    }
+
+    /// Withdraw everything from a strategy and block further deployments.
+    ///
+    /// Whatever the venue fails to return is written off as a loss.
+    pub fn emergency_unwind_strategy<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ManageStrategy<'info>>,
+    ) -> Result<()> {
+        check_strategy_accounts(&ctx.accounts.strategy, ctx.remaining_accounts)?;
+        
+        let received = withdraw_strategy_funds(
+            ctx.accounts,
+            ctx.remaining_accounts,
+            strategy_adapter::WITHDRAW_ALL,
+        )?;
+        
+        let strategy = &mut ctx.accounts.strategy;
+        let previous = strategy.deployed;
+        let (gain, loss) = strategy.mark_to(received)?;
+        sync_deployed(&mut ctx.accounts.asset_config, previous, 0)?;
+        strategy.deployed = 0;
+        strategy.unwound = true;
+        book_strategy_result(
+            &mut ctx.accounts.vault,
+            &mut ctx.accounts.asset_config,
+            gain,
+            loss,
+        )?;
+        
+        emit!(StrategyUnwound {
+            strategy: strategy.key(),
+            mint: ctx.accounts.asset_config.mint,
+            recovered: received,
+            loss,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        
+        Ok(())
+    }
+
+    pub fn initialize_nav_snapshot(ctx: Context<InitializeNavSnapshot>) -> Result<()> {
+        let nav_snapshot = &mut ctx.accounts.nav_snapshot;
+        nav_snapshot.reserve_vault = ctx.accounts.vault.key();
+        nav_snapshot.price_feed = ctx.accounts.nav_price_feed.key();
+        nav_snapshot.total_value_usd = 0;
+        nav_snapshot.liabilities_usd = 0;
+        nav_snapshot.aru_supply = 0;
+        nav_snapshot.nav_per_aru = 0;
+        nav_snapshot.vhr = 0;
+        nav_snapshot.slot = 0;
+        nav_snapshot.timestamp = 0;
+        nav_snapshot.bump = ctx.bumps.nav_snapshot;
+        nav_snapshot.price_feed_bump = ctx.bumps.nav_price_feed;
+        
+        Ok(())
+    }
+
+    /// Permissionless crank publishing NAV per ARU from the reserve's books.
+    ///
+    /// Uses the vault's booked `total_value_usd`, so run it after
+    /// `refresh_valuation`. The price account is only marked trading while ARU
+    /// is outstanding.
+    pub fn update_nav(ctx: Context<UpdateNav>) -> Result<()> {
+        let clock = Clock::get()?;
+        let (_, liabilities_usd) = value_liabilities(
+            &ctx.accounts.mint_state,
+            &ctx.accounts.ili_oracle,
+            ctx.accounts.aru_mint.decimals,
+        )?;
+        
+        let total_value_usd = ctx.accounts.vault.total_value_usd;
+        let aru_supply = ctx.accounts.mint_state.total_supply;
+        let nav_per_aru = calculate_nav(total_value_usd, aru_supply, ctx.accounts.aru_mint.decimals)?;
+        
+        let nav_snapshot = &mut ctx.accounts.nav_snapshot;
+        nav_snapshot.total_value_usd = total_value_usd;
+        nav_snapshot.liabilities_usd = liabilities_usd;
+        nav_snapshot.aru_supply = aru_supply;
+        nav_snapshot.nav_per_aru = nav_per_aru;
+        nav_snapshot.vhr = calculate_vhr(total_value_usd, liabilities_usd)?;
+        nav_snapshot.slot = clock.slot;
+        nav_snapshot.timestamp = clock.unix_timestamp;
+        
+        oracle::write_pyth_price(
+            &mut ctx.accounts.price_feed.try_borrow_mut_data()?,
+            nav_per_aru,
+            aru_supply > 0,
+            clock.slot,
+            clock.unix_timestamp,
+        )?;
+        
+        emit!(NavUpdated {
+            reserve_vault: nav_snapshot.reserve_vault,
+            total_value_usd,
+            liabilities_usd,
+            aru_supply,
+            nav_per_aru,
+            vhr: nav_snapshot.vhr,
+            timestamp: nav_snapshot.timestamp,
+        });
+        
+        Ok(())
+    }
+
+    /// Latest NAV snapshot as return data, for programs pricing ARU over CPI
+    pub fn get_nav(ctx: Context<GetNav>) -> Result<NavQuote> {
+        Ok(ctx.accounts.nav_snapshot.quote())
+    }
+}
+
+/// Reprice an asset from its oracle and return the USD value of `balance`
+fn revalue_asset(
+    asset_config: &mut AssetConfig,
+    balance: u64,
+    price_feed: &AccountInfo,
+    now: i64,
+) -> Result<u64> {
+    let price = oracle::load_price(
+        price_feed,
+        asset_config.max_price_age,
+        asset_config.max_confidence_bps,
+        now,
+    )?;
+    
+    asset_config.price_usd = price.price_usd;
+    asset_config.last_price_update = now;
+    
+    oracle::asset_value_usd(balance, asset_config.decimals, price.price_usd, asset_config.haircut_bps)
+}
+
+/// Value outstanding ARU at the ILI-derived target price
+fn sync_liabilities(
+    vault: &mut ReserveVault,
+    mint_state: &MintState,
+    ili_oracle: &ILIOracle,
+    aru_decimals: u8,
+    now: i64,
+) -> Result<()> {
+    let (aru_price_usd, liabilities_usd) = value_liabilities(mint_state, ili_oracle, aru_decimals)?;
+    
+    vault.aru_price_usd = aru_price_usd;
+    vault.liabilities_usd = liabilities_usd;
+    vault.last_liability_update = now;
+    
+    Ok(())
+}
+
+/// ARU target price and the USD value of outstanding supply at that price
+fn value_liabilities(
+    mint_state: &MintState,
+    ili_oracle: &ILIOracle,
+    aru_decimals: u8,
+) -> Result<(u64, u64)> {
+    require!(
+        mint_state.total_supply == 0 || ili_oracle.current_ili > 0,
+        ErrorCode::InvalidILIPrice
+    );
+    
+    let aru_price_usd = oracle::ili_price_usd(ili_oracle.current_ili)?;
+    let liabilities_usd = oracle::asset_value_usd(
+        mint_state.total_supply,
+        aru_decimals,
+        aru_price_usd,
+        0,
+    )?;
+    
+    Ok((aru_price_usd, liabilities_usd))
+}
+
+/// Check through the instructions sysvar that a flash loan is repaid in this transaction.
+///
+/// The loan must be invoked at the top level, and the next ars-reserve instruction
+/// after it must be `repay_flash_loan` on the same config for the same amount.
+fn verify_flash_repayment(
+    instructions: &AccountInfo,
+    flash_loan_config: Pubkey,
+    amount: u64,
+) -> Result<()> {
+    let current_index = load_current_index_checked(instructions)? as usize;
+    let current = load_instruction_at_checked(current_index, instructions)?;
+    require_keys_eq!(current.program_id, crate::ID, ErrorCode::FlashLoanCpiForbidden);
+    
+    let mut index = current_index + 1;
+    while let Ok(instruction) = load_instruction_at_checked(index, instructions) {
+        if instruction.program_id == crate::ID {
+            let repays = instruction.data.len() >= 16
+                && instruction.data[..8] == crate::instruction::RepayFlashLoan::DISCRIMINATOR
+                && instruction.data[8..16] == amount.to_le_bytes()
+                && instruction.accounts.get(2).map(|meta| meta.pubkey) == Some(flash_loan_config);
+            require!(repays, ErrorCode::FlashLoanNotRepaid);
+            
+            return Ok(());
+        }
+        index += 1;
+    }
+    
+    err!(ErrorCode::FlashLoanNotRepaid)
+}
+
+/// Check through the instructions sysvar that reserve balances are not lent out.
+///
+/// Attestations must be invoked at the top level, where no flash loan can be
+/// outstanding unless this transaction takes one; such transactions are rejected.
+fn verify_no_flash_loan(instructions: &AccountInfo) -> Result<()> {
+    let current_index = load_current_index_checked(instructions)? as usize;
+    let current = load_instruction_at_checked(current_index, instructions)?;
+    require_keys_eq!(current.program_id, crate::ID, ErrorCode::AttestationCpiForbidden);
+    
+    let mut index = 0;
+    while let Ok(instruction) = load_instruction_at_checked(index, instructions) {
+        let borrows = instruction.program_id == crate::ID
+            && instruction.data.len() >= 8
+            && instruction.data[..8] == crate::instruction::FlashLoan::DISCRIMINATOR;
+        require!(!borrows, ErrorCode::AttestationDuringFlashLoan);
+        index += 1;
+    }
+    
+    Ok(())
+}
+
+/// Remaining accounts must be exactly the adapter accounts governance registered
+fn check_strategy_accounts(strategy: &StrategyAllocation, accounts: &[AccountInfo]) -> Result<()> {
+    require!(
+        accounts.len() == strategy.adapter_accounts.len()
+            && accounts.iter().zip(&strategy.adapter_accounts).all(|(account, key)| account.key == key),
+        ErrorCode::StrategyAccountsMismatch
+    );
+    
+    Ok(())
+}
+
+/// Withdraw from a strategy and return what actually reached the reserve
+fn withdraw_strategy_funds<'info>(
+    accounts: &mut ManageStrategy<'info>,
+    adapter_accounts: &[AccountInfo<'info>],
+    amount: u64,
+) -> Result<u64> {
+    let vault = &accounts.vault;
+    let vault_seeds = &[
+        b"vault",
+        vault.authority.as_ref(),
+        &[vault.bump],
+    ];
+    let signer = &[&vault_seeds[..]];
+    let balance_before = accounts.vault_token_account.amount;
+    
+    strategy_adapter::withdraw(
+        &accounts.adapter_program.to_account_info(),
+        &vault.to_account_info(),
+        &accounts.vault_token_account.to_account_info(),
+        &accounts.token_program.to_account_info(),
+        adapter_accounts,
+        amount,
+        signer,
+    )?;
+    accounts.vault_token_account.reload()?;
+    
+    accounts.vault_token_account.amount
+        .checked_sub(balance_before)
+        .ok_or(error!(ErrorCode::ArithmeticOverflow))
+}
+
+/// Book a strategy gain or loss against the asset's reserve balance at its last price
+fn book_strategy_result(
+    vault: &mut ReserveVault,
+    asset_config: &mut AssetConfig,
+    gain: u64,
+    loss: u64,
+) -> Result<()> {
+    if gain == 0 && loss == 0 {
+        return Ok(());
+    }
+    
+    let old_value = asset_config.value_usd;
+    asset_config.balance = asset_config.balance
+        .checked_add(gain)
+        .ok_or(ErrorCode::ArithmeticOverflow)?
+        .checked_sub(loss)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+    asset_config.clamp_yield_basis();
+    asset_config.value_usd = oracle::asset_value_usd(
+        asset_config.balance,
+        asset_config.decimals,
+        asset_config.price_usd,
+        asset_config.haircut_bps,
+    )?;
+    
+    vault.total_value_usd = vault.total_value_usd
+        .checked_sub(old_value)
+        .ok_or(ErrorCode::ArithmeticOverflow)?
+        .checked_add(asset_config.value_usd)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+    vault.vhr = calculate_vhr(vault.total_value_usd, vault.liabilities_usd)?;
+    
+    Ok(())
+}
+
+/// Move a strategy's share of the asset's `deployed` total from `before` to `after`
+fn sync_deployed(asset_config: &mut AssetConfig, before: u64, after: u64) -> Result<()> {
+    asset_config.deployed = asset_config.deployed
+        .saturating_sub(before)
+        .checked_add(after)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+    
+    Ok(())
+}
+
+/// `value` less a fee of `fee_bps`
+fn apply_fee(value: u64, fee_bps: u16) -> Result<u64> {
+    let fee = (value as u128)
+        .checked_mul(fee_bps as u128)
+        .ok_or(ErrorCode::ArithmeticOverflow)?
+        .checked_div(10000)
+        .ok_or(ErrorCode::ArithmeticOverflow)? as u64;
+    
+    value.checked_sub(fee).ok_or(error!(ErrorCode::ArithmeticOverflow))
+}
+
+fn calculate_vhr(total_value_usd: u64, liabilities_usd: u64) -> Result<u64> {
+    if liabilities_usd == 0 {
+        return Ok(u64::MAX);
+    }
+    
+    let ratio = (total_value_usd as u128)
+        .checked_mul(10000)
+        .ok_or(ErrorCode::ArithmeticOverflow)?
+        .checked_div(liabilities_usd as u128)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+    
+    // A ratio beyond u64 is still fully collateralized, never a wrap to a low value
+    Ok(u64::try_from(ratio).unwrap_or(u64::MAX))
+}
+
+/// Read an asset's flow fee schedule, `None` while governance has not set one.
+///
+/// The schedule PDA is always passed, so a flow cannot skip the fee by leaving
+/// it out; once a schedule exists the fee recipient must match it.
+fn load_flow_fee_schedule(
+    flow_fee_schedule: &AccountInfo,
+    fee_recipient: Option<&Account<TokenAccount>>,
+) -> Result<Option<FlowFeeSchedule>> {
+    if flow_fee_schedule.data_is_empty() {
+        return Ok(None);
+    }
+    
+    require_keys_eq!(
+        *flow_fee_schedule.owner,
+        crate::ID,
+        anchor_lang::error::ErrorCode::AccountOwnedByWrongProgram
+    );
+    let schedule = FlowFeeSchedule::try_deserialize(&mut &flow_fee_schedule.try_borrow_data()?[..])?;
+    require!(
+        fee_recipient.map(|recipient| recipient.key()) == Some(schedule.fee_recipient),
+        ErrorCode::InvalidFeeRecipient
+    );
+    
+    Ok(Some(schedule))
+}
+
+/// Fee on a flow of `amount` that leaves the asset worth `value_usd` of
+/// `total_value_usd`, as (fee, fee_bps, weight_bps)
+fn flow_fee(
+    schedule: Option<&FlowFeeSchedule>,
+    asset_config: &AssetConfig,
+    amount: u64,
+    value_usd: u64,
+    total_value_usd: u64,
+    deposit: bool,
+) -> Result<(u64, u16, u16)> {
+    let weight_bps = calculate_weight(value_usd, total_value_usd)?;
+    let Some(schedule) = schedule else {
+        return Ok((0, 0, weight_bps));
+    };
+    
+    let fee_bps = schedule.fee_bps(asset_config, weight_bps, deposit);
+    let fee = FlowFeeSchedule::fee(amount, fee_bps).ok_or(ErrorCode::ArithmeticOverflow)?;
+    
+    Ok((fee, fee_bps, weight_bps))
+}
+
+/// Pay `amount` of a depositor's asset out of the vault, net of the flow fee,
+/// against the epoch outflow cap. The caller debits the position.
+#[allow(clippy::too_many_arguments)]
+fn pay_out_withdrawal<'info>(
+    vault: &mut Account<'info, ReserveVault>,
+    asset_config: &mut Account<'info, AssetConfig>,
+    withdrawal_queue: &mut WithdrawalQueue,
+    price_feed: &AccountInfo<'info>,
+    mint_state: &MintState,
+    ili_oracle: &ILIOracle,
+    aru_decimals: u8,
+    vault_token_account: &Account<'info, TokenAccount>,
+    recipient: &Account<'info, TokenAccount>,
+    schedule: Option<&FlowFeeSchedule>,
+    fee_recipient: &Option<Account<'info, TokenAccount>>,
+    token_program: &Program<'info, Token>,
+    user: Pubkey,
+    amount: u64,
+    now: i64,
+) -> Result<()> {
+    require!(
+        amount <= vault_token_account.amount && amount <= asset_config.balance,
+        ErrorCode::InsufficientBalance
+    );
+    require!(
+        amount <= asset_config.idle_balance(),
+        ErrorCode::InsufficientIdleBalance
+    );
+    
+    let new_balance = asset_config.balance
+        .checked_sub(amount)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+    let new_value = revalue_asset(asset_config, new_balance, price_feed, now)?;
+    
+    let new_total_value = vault.total_value_usd
+        .checked_sub(asset_config.value_usd)
+        .ok_or(ErrorCode::ArithmeticOverflow)?
+        .checked_add(new_value)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+    
+    withdrawal_queue.roll_epoch(mint_state.current_epoch, vault.total_value_usd)?;
+    withdrawal_queue.record_outflow(oracle::asset_value_usd(
+        amount,
+        asset_config.decimals,
+        asset_config.price_usd,
+        asset_config.haircut_bps,
+    )?)?;
+    
+    sync_liabilities(vault, mint_state, ili_oracle, aru_decimals, now)?;
+    
+    let new_vhr = calculate_vhr(new_total_value, vault.liabilities_usd)?;
+    
+    require!(new_vhr >= vault.min_vhr, ErrorCode::VHRTooLow);
+    
+    let (fee, fee_bps, weight_bps) = flow_fee(
+        schedule,
+        asset_config,
+        amount,
+        new_value,
+        new_total_value,
+        false,
+    )?;
+    
+    let vault_seeds = &[
+        b"vault",
+        vault.authority.as_ref(),
+        &[vault.bump],
+    ];
+    let signer = &[&vault_seeds[..]];
+    
+    token::transfer(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            Transfer {
+                from: vault_token_account.to_account_info(),
+                to: recipient.to_account_info(),
+                authority: vault.to_account_info(),
+            },
+            signer,
+        ),
+        amount
+            .checked_sub(fee)
+            .ok_or(ErrorCode::ArithmeticOverflow)?,
+    )?;
+    
+    if let Some(schedule) = schedule {
+        if fee > 0 {
+            token::transfer(
+                CpiContext::new_with_signer(
+                    token_program.to_account_info(),
+                    Transfer {
+                        from: vault_token_account.to_account_info(),
+                        to: fee_recipient_info(fee_recipient)?,
+                        authority: vault.to_account_info(),
+                    },
+                    signer,
+                ),
+                fee,
+            )?;
+        }
+        
+        emit!(FlowFeeCharged {
+            reserve_vault: vault.key(),
+            mint: asset_config.mint,
+            user,
+            deposit: false,
+            amount,
+            fee,
+            fee_bps,
+            weight_bps,
+            destination: schedule.destination,
+            timestamp: now,
+        });
+    }
+    
+    asset_config.balance = new_balance;
+    asset_config.position_balance = asset_config.position_balance.saturating_sub(amount);
+    asset_config.value_usd = new_value;
+    asset_config.current_weight_bps = calculate_weight(new_value, new_total_value)?;
+    
+    vault.total_value_usd = new_total_value;
+    vault.vhr = new_vhr;
+    
+    Ok(())
+}
+
+fn fee_recipient_info<'info>(
+    fee_recipient: &Option<Account<'info, TokenAccount>>,
+) -> Result<AccountInfo<'info>> {
+    fee_recipient
+        .as_ref()
+        .map(|recipient| recipient.to_account_info())
+        .ok_or(error!(ErrorCode::InvalidFeeRecipient))
+}
+
+/// USD value backing one whole ARU, zero while nothing is outstanding
+fn calculate_nav(total_value_usd: u64, aru_supply: u64, aru_decimals: u8) -> Result<u64> {
+    if aru_supply == 0 {
+        return Ok(0);
+    }
+    
+    let nav = (total_value_usd as u128)
+        .checked_mul(
+            10u128
+                .checked_pow(aru_decimals as u32)
+                .ok_or(ErrorCode::ArithmeticOverflow)?,
+        )
+        .ok_or(ErrorCode::ArithmeticOverflow)?
+        .checked_div(aru_supply as u128)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+    
+    u64::try_from(nav).map_err(|_| error!(ErrorCode::ArithmeticOverflow))
+}
+
+fn calculate_weight(asset_value_usd: u64, total_value_usd: u64) -> Result<u16> {
+    if total_value_usd == 0 {
+        return Ok(0);
+    }
+    
+    let weight = (asset_value_usd as u128)
+        .checked_mul(10000)
+        .ok_or(ErrorCode::ArithmeticOverflow)?
+        .checked_div(total_value_usd as u128)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+    
+    Ok(weight.min(10000) as u16)
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = ReserveVault::LEN,
+        seeds = [b"vault", authority.key().as_ref()],
+        bump
+    )]
+    pub vault: Account<'info, ReserveVault>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = AssetRegistry::LEN,
+        seeds = [b"asset_registry", vault.key().as_ref()],
+        bump
+    )]
+    pub asset_registry: Account<'info, AssetRegistry>,
+    
+    pub mint_state: Account<'info, MintState>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateVault<'info> {
+    /// CHECK: Legacy-layout vault, decoded manually in the handler
+    #[account(
+        mut,
+        seeds = [b"vault", authority.key().as_ref()],
+        bump,
+        owner = crate::ID
+    )]
+    pub vault: UncheckedAccount<'info>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = AssetRegistry::LEN,
+        seeds = [b"asset_registry", vault.key().as_ref()],
+        bump
+    )]
+    pub asset_registry: Account<'info, AssetRegistry>,
+    
+    pub mint_state: Account<'info, MintState>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AddAsset<'info> {
+    #[account(
+        seeds = [b"vault", vault.authority.as_ref()],
+        bump = vault.bump,
+        has_one = authority @ ErrorCode::Unauthorized,
+        has_one = asset_registry
+    )]
+    pub vault: Account<'info, ReserveVault>,
+    
+    #[account(mut)]
+    pub asset_registry: Account<'info, AssetRegistry>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = AssetConfig::LEN,
+        seeds = [b"asset", vault.key().as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub asset_config: Account<'info, AssetConfig>,
+    
+    pub mint: Account<'info, Mint>,
+    
+    #[account(
+        constraint = asset_vault.mint == mint.key() @ ErrorCode::InvalidAssetVault,
+        constraint = asset_vault.owner == vault.key() @ ErrorCode::InvalidAssetVault
+    )]
+    pub asset_vault: Account<'info, TokenAccount>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateAsset<'info> {
+    #[account(
+        seeds = [b"vault", vault.authority.as_ref()],
+        bump = vault.bump,
+        has_one = authority @ ErrorCode::Unauthorized,
+        has_one = asset_registry
+    )]
+    pub vault: Account<'info, ReserveVault>,
+    
+    #[account(mut)]
+    pub asset_registry: Account<'info, AssetRegistry>,
+    
+    #[account(
+        mut,
+        seeds = [b"asset", vault.key().as_ref(), asset_config.mint.as_ref()],
+        bump = asset_config.bump
+    )]
+    pub asset_config: Account<'info, AssetConfig>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveAsset<'info> {
+    #[account(
+        seeds = [b"vault", vault.authority.as_ref()],
+        bump = vault.bump,
+        has_one = authority @ ErrorCode::Unauthorized,
+        has_one = asset_registry
+    )]
+    pub vault: Account<'info, ReserveVault>,
+    
+    #[account(mut)]
+    pub asset_registry: Account<'info, AssetRegistry>,
+    
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"asset", vault.key().as_ref(), asset_config.mint.as_ref()],
+        bump = asset_config.bump
+    )]
+    pub asset_config: Account<'info, AssetConfig>,
+    
+    #[account(address = asset_config.vault @ ErrorCode::InvalidAssetVault)]
+    pub asset_vault: Account<'info, TokenAccount>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct OpenPosition<'info> {
+    #[account(
+        seeds = [b"asset", asset_config.reserve_vault.as_ref(), asset_config.mint.as_ref()],
+        bump = asset_config.bump
+    )]
+    pub asset_config: Account<'info, AssetConfig>,
+    
+    #[account(
+        init,
+        payer = owner,
+        space = DepositorPosition::LEN,
+        seeds = [b"position", asset_config.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub position: Account<'info, DepositorPosition>,
+    
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClosePosition<'info> {
+    #[account(
+        mut,
+        close = owner,
+        seeds = [b"position", position.asset_config.as_ref(), owner.key().as_ref()],
+        bump = position.bump,
+        has_one = owner @ ErrorCode::Unauthorized
+    )]
+    pub position: Account<'info, DepositorPosition>,
+    
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    #[account(
+        mut,
+        seeds = [b"vault", vault.authority.as_ref()],
+        bump = vault.bump,
+        constraint = !vault.settled @ ErrorCode::ReserveSettled
+    )]
+    pub vault: Account<'info, ReserveVault>,
+    
+    #[account(
+        mut,
+        seeds = [b"asset", vault.key().as_ref(), asset_config.mint.as_ref()],
+        bump = asset_config.bump
+    )]
+    pub asset_config: Account<'info, AssetConfig>,
+    
+    #[account(
+        mut,
+        seeds = [b"position", asset_config.key().as_ref(), user.key().as_ref()],
+        bump = position.bump
+    )]
+    pub position: Account<'info, DepositorPosition>,
+    
+    /// CHECK: Pyth or Switchboard price account, validated against asset_config.oracle_source
+    #[account(address = asset_config.oracle_source @ ErrorCode::InvalidOracle)]
+    pub price_feed: AccountInfo<'info>,
+    
+    #[account(address = vault.mint_state @ ErrorCode::InvalidLiabilitySource)]
+    pub mint_state: Account<'info, MintState>,
+    
+    #[account(address = mint_state.aru_mint @ ErrorCode::InvalidLiabilitySource)]
+    pub aru_mint: Account<'info, Mint>,
+    
+    #[account(
+        seeds = [b"ili_oracle"],
+        bump = ili_oracle.bump,
+        seeds::program = ars_core::ID
+    )]
+    pub ili_oracle: Account<'info, ILIOracle>,
+    
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = user_token_account.mint == asset_config.mint @ ErrorCode::InvalidAssetVault
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        address = asset_config.vault @ ErrorCode::InvalidAssetVault
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+    
+    /// CHECK: Flow fee schedule PDA, empty while the asset has none
+    #[account(
+        seeds = [b"flow_fee", asset_config.key().as_ref()],
+        bump
+    )]
+    pub flow_fee_schedule: UncheckedAccount<'info>,
+    
+    /// Required once the asset has a flow fee schedule
+    #[account(mut)]
+    pub fee_recipient: Option<Account<'info, TokenAccount>>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    #[account(
+        mut,
+        seeds = [b"vault", vault.authority.as_ref()],
+        bump = vault.bump,
+        constraint = !vault.settled @ ErrorCode::ReserveSettled
+    )]
+    pub vault: Account<'info, ReserveVault>,
+    
+    #[account(
+        mut,
+        seeds = [b"asset", vault.key().as_ref(), asset_config.mint.as_ref()],
+        bump = asset_config.bump
+    )]
+    pub asset_config: Account<'info, AssetConfig>,
+    
+    // Only the owner, or the vault authority acting for governance, may withdraw a position
+    #[account(
+        mut,
+        seeds = [b"position", asset_config.key().as_ref(), position.owner.as_ref()],
+        bump = position.bump,
+        constraint = user.key() == position.owner
+            || user.key() == vault.authority @ ErrorCode::Unauthorized
+    )]
+    pub position: Account<'info, DepositorPosition>,
+    
+    #[account(
+        mut,
+        seeds = [b"withdrawal_queue", vault.key().as_ref()],
+        bump = withdrawal_queue.bump
+    )]
+    pub withdrawal_queue: Account<'info, WithdrawalQueue>,
+    
+    /// CHECK: Pyth or Switchboard price account, validated against asset_config.oracle_source
+    #[account(address = asset_config.oracle_source @ ErrorCode::InvalidOracle)]
+    pub price_feed: AccountInfo<'info>,
+    
+    #[account(address = vault.mint_state @ ErrorCode::InvalidLiabilitySource)]
+    pub mint_state: Account<'info, MintState>,
+    
+    #[account(address = mint_state.aru_mint @ ErrorCode::InvalidLiabilitySource)]
+    pub aru_mint: Account<'info, Mint>,
+    
+    #[account(
+        seeds = [b"ili_oracle"],
+        bump = ili_oracle.bump,
+        seeds::program = ars_core::ID
+    )]
+    pub ili_oracle: Account<'info, ILIOracle>,
+    
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = user_token_account.mint == asset_config.mint @ ErrorCode::InvalidAssetVault,
+        constraint = user_token_account.owner == position.owner @ ErrorCode::InvalidRecipient
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        address = asset_config.vault @ ErrorCode::InvalidAssetVault
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+    
+    /// CHECK: Flow fee schedule PDA, empty while the asset has none
+    #[account(
+        seeds = [b"flow_fee", asset_config.key().as_ref()],
+        bump
+    )]
+    pub flow_fee_schedule: UncheckedAccount<'info>,
+    
+    /// Required once the asset has a flow fee schedule
+    #[account(mut)]
+    pub fee_recipient: Option<Account<'info, TokenAccount>>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct AddFlowFeeSchedule<'info> {
+    #[account(
+        seeds = [b"vault", vault.authority.as_ref()],
+        bump = vault.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub vault: Account<'info, ReserveVault>,
+    
+    #[account(
+        seeds = [b"asset", vault.key().as_ref(), asset_config.mint.as_ref()],
+        bump = asset_config.bump
+    )]
+    pub asset_config: Account<'info, AssetConfig>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = FlowFeeSchedule::LEN,
+        seeds = [b"flow_fee", asset_config.key().as_ref()],
+        bump
+    )]
+    pub flow_fee_schedule: Account<'info, FlowFeeSchedule>,
+    
+    #[account(constraint = fee_recipient.mint == asset_config.mint @ ErrorCode::InvalidFeeRecipient)]
+    pub fee_recipient: Account<'info, TokenAccount>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateFlowFeeSchedule<'info> {
+    #[account(
+        seeds = [b"vault", vault.authority.as_ref()],
+        bump = vault.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub vault: Account<'info, ReserveVault>,
+    
+    #[account(
+        seeds = [b"asset", vault.key().as_ref(), asset_config.mint.as_ref()],
+        bump = asset_config.bump
+    )]
+    pub asset_config: Account<'info, AssetConfig>,
+    
+    #[account(
+        mut,
+        seeds = [b"flow_fee", asset_config.key().as_ref()],
+        bump = flow_fee_schedule.bump
+    )]
+    pub flow_fee_schedule: Account<'info, FlowFeeSchedule>,
+    
+    #[account(constraint = fee_recipient.mint == asset_config.mint @ ErrorCode::InvalidFeeRecipient)]
+    pub fee_recipient: Account<'info, TokenAccount>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeWithdrawalQueue<'info> {
+    #[account(
+        seeds = [b"vault", vault.authority.as_ref()],
+        bump = vault.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub vault: Account<'info, ReserveVault>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = WithdrawalQueue::LEN,
+        seeds = [b"withdrawal_queue", vault.key().as_ref()],
+        bump
+    )]
+    pub withdrawal_queue: Account<'info, WithdrawalQueue>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetOutflowCap<'info> {
+    #[account(
+        seeds = [b"vault", vault.authority.as_ref()],
+        bump = vault.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub vault: Account<'info, ReserveVault>,
+    
+    #[account(
+        mut,
+        seeds = [b"withdrawal_queue", vault.key().as_ref()],
+        bump = withdrawal_queue.bump
+    )]
+    pub withdrawal_queue: Account<'info, WithdrawalQueue>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct QueueWithdrawal<'info> {
+    #[account(
+        mut,
+        seeds = [b"vault", vault.authority.as_ref()],
+        bump = vault.bump,
+        constraint = !vault.settled @ ErrorCode::ReserveSettled
+    )]
+    pub vault: Account<'info, ReserveVault>,
+    
+    #[account(
+        mut,
+        seeds = [b"withdrawal_queue", vault.key().as_ref()],
+        bump = withdrawal_queue.bump
+    )]
+    pub withdrawal_queue: Account<'info, WithdrawalQueue>,
+    
+    #[account(
+        init,
+        payer = owner,
+        space = WithdrawalRequest::LEN,
+        seeds = [
+            b"withdrawal_request",
+            vault.key().as_ref(),
+            withdrawal_queue.next_request_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub withdrawal_request: Account<'info, WithdrawalRequest>,
+    
+    #[account(
+        mut,
+        seeds = [b"asset", vault.key().as_ref(), asset_config.mint.as_ref()],
+        bump = asset_config.bump
+    )]
+    pub asset_config: Account<'info, AssetConfig>,
+    
+    #[account(
+        mut,
+        seeds = [b"position", asset_config.key().as_ref(), owner.key().as_ref()],
+        bump = position.bump
+    )]
+    pub position: Account<'info, DepositorPosition>,
+    
+    /// CHECK: Pyth or Switchboard price account, validated against asset_config.oracle_source
+    #[account(address = asset_config.oracle_source @ ErrorCode::InvalidOracle)]
+    pub price_feed: AccountInfo<'info>,
+    
+    #[account(address = vault.mint_state @ ErrorCode::InvalidLiabilitySource)]
+    pub mint_state: Account<'info, MintState>,
+    
+    #[account(address = mint_state.aru_mint @ ErrorCode::InvalidLiabilitySource)]
+    pub aru_mint: Account<'info, Mint>,
+    
+    #[account(
+        seeds = [b"ili_oracle"],
+        bump = ili_oracle.bump,
+        seeds::program = ars_core::ID
+    )]
+    pub ili_oracle: Account<'info, ILIOracle>,
+    
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = owner_token_account.mint == asset_config.mint @ ErrorCode::InvalidAssetVault,
+        constraint = owner_token_account.owner == owner.key() @ ErrorCode::InvalidRecipient
+    )]
+    pub owner_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        address = asset_config.vault @ ErrorCode::InvalidAssetVault
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+    
+    /// CHECK: Flow fee schedule PDA, empty while the asset has none
+    #[account(
+        seeds = [b"flow_fee", asset_config.key().as_ref()],
+        bump
+    )]
+    pub flow_fee_schedule: UncheckedAccount<'info>,
+    
+    /// Required once the asset has a flow fee schedule
+    #[account(mut)]
+    pub fee_recipient: Option<Account<'info, TokenAccount>>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelWithdrawal<'info> {
+    #[account(
+        mut,
+        seeds = [b"withdrawal_queue", withdrawal_request.reserve_vault.as_ref()],
+        bump = withdrawal_queue.bump
+    )]
+    pub withdrawal_queue: Account<'info, WithdrawalQueue>,
+    
+    #[account(
+        mut,
+        close = owner,
+        has_one = owner @ ErrorCode::Unauthorized,
+        has_one = position
+    )]
+    pub withdrawal_request: Account<'info, WithdrawalRequest>,
+    
+    #[account(mut)]
+    pub position: Account<'info, DepositorPosition>,
+    
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ProcessWithdrawal<'info> {
+    #[account(
+        mut,
+        seeds = [b"vault", vault.authority.as_ref()],
+        bump = vault.bump,
+        constraint = !vault.settled @ ErrorCode::ReserveSettled
+    )]
+    pub vault: Account<'info, ReserveVault>,
+    
+    #[account(
+        mut,
+        seeds = [b"withdrawal_queue", vault.key().as_ref()],
+        bump = withdrawal_queue.bump
+    )]
+    pub withdrawal_queue: Account<'info, WithdrawalQueue>,
+    
+    /// Closed to the owner once paid in full
+    #[account(
+        mut,
+        has_one = owner,
+        has_one = position,
+        has_one = asset_config
+    )]
+    pub withdrawal_request: Account<'info, WithdrawalRequest>,
+    
+    #[account(
+        mut,
+        seeds = [b"asset", vault.key().as_ref(), asset_config.mint.as_ref()],
+        bump = asset_config.bump
+    )]
+    pub asset_config: Account<'info, AssetConfig>,
+    
+    #[account(mut)]
+    pub position: Account<'info, DepositorPosition>,
+    
+    /// CHECK: Pyth or Switchboard price account, validated against asset_config.oracle_source
+    #[account(address = asset_config.oracle_source @ ErrorCode::InvalidOracle)]
+    pub price_feed: AccountInfo<'info>,
+    
+    #[account(address = vault.mint_state @ ErrorCode::InvalidLiabilitySource)]
+    pub mint_state: Account<'info, MintState>,
+    
+    #[account(address = mint_state.aru_mint @ ErrorCode::InvalidLiabilitySource)]
+    pub aru_mint: Account<'info, Mint>,
+    
+    #[account(
+        seeds = [b"ili_oracle"],
+        bump = ili_oracle.bump,
+        seeds::program = ars_core::ID
+    )]
+    pub ili_oracle: Account<'info, ILIOracle>,
+    
+    /// CHECK: Request owner, refunded the request rent
+    #[account(mut)]
+    pub owner: UncheckedAccount<'info>,
+    
+    #[account(
+        mut,
+        constraint = owner_token_account.mint == asset_config.mint @ ErrorCode::InvalidAssetVault,
+        constraint = owner_token_account.owner == owner.key() @ ErrorCode::InvalidRecipient
+    )]
+    pub owner_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        address = asset_config.vault @ ErrorCode::InvalidAssetVault
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+    
+    /// CHECK: Flow fee schedule PDA, empty while the asset has none
+    #[account(
+        seeds = [b"flow_fee", asset_config.key().as_ref()],
+        bump
+    )]
+    pub flow_fee_schedule: UncheckedAccount<'info>,
+    
+    /// Required once the asset has a flow fee schedule
+    #[account(mut)]
+    pub fee_recipient: Option<Account<'info, TokenAccount>>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct RefreshValuation<'info> {
+    #[account(
+        mut,
+        seeds = [b"vault", vault.authority.as_ref()],
+        bump = vault.bump,
+        has_one = asset_registry
+    )]
+    pub vault: Account<'info, ReserveVault>,
+    
+    pub asset_registry: Account<'info, AssetRegistry>,
+    
+    #[account(address = vault.mint_state @ ErrorCode::InvalidLiabilitySource)]
+    pub mint_state: Account<'info, MintState>,
+    
+    #[account(address = mint_state.aru_mint @ ErrorCode::InvalidLiabilitySource)]
+    pub aru_mint: Account<'info, Mint>,
+    
+    #[account(
+        seeds = [b"ili_oracle"],
+        bump = ili_oracle.bump,
+        seeds::program = ars_core::ID
+    )]
+    pub ili_oracle: Account<'info, ILIOracle>,
+}
+
+#[derive(Accounts)]
+pub struct RefreshVhr<'info> {
+    #[account(
+        mut,
+        seeds = [b"vault", vault.authority.as_ref()],
+        bump = vault.bump
+    )]
+    pub vault: Account<'info, ReserveVault>,
+    
+    #[account(address = vault.mint_state @ ErrorCode::InvalidLiabilitySource)]
+    pub mint_state: Account<'info, MintState>,
+    
+    #[account(address = mint_state.aru_mint @ ErrorCode::InvalidLiabilitySource)]
+    pub aru_mint: Account<'info, Mint>,
+    
+    #[account(
+        seeds = [b"ili_oracle"],
+        bump = ili_oracle.bump,
+        seeds::program = ars_core::ID
+    )]
+    pub ili_oracle: Account<'info, ILIOracle>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeRedemptionConfig<'info> {
+    #[account(
+        seeds = [b"vault", vault.authority.as_ref()],
+        bump = vault.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub vault: Account<'info, ReserveVault>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = RedemptionConfig::LEN,
+        seeds = [b"redemption_config", vault.key().as_ref()],
+        bump
+    )]
+    pub redemption_config: Account<'info, RedemptionConfig>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateRedemptionConfig<'info> {
+    #[account(
+        seeds = [b"vault", vault.authority.as_ref()],
+        bump = vault.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub vault: Account<'info, ReserveVault>,
+    
+    #[account(
+        mut,
+        seeds = [b"redemption_config", vault.key().as_ref()],
+        bump = redemption_config.bump
+    )]
+    pub redemption_config: Account<'info, RedemptionConfig>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Redeem<'info> {
+    #[account(
+        mut,
+        seeds = [b"vault", vault.authority.as_ref()],
+        bump = vault.bump,
+        has_one = asset_registry,
+        constraint = !vault.settled @ ErrorCode::ReserveSettled
+    )]
+    pub vault: Account<'info, ReserveVault>,
+    
+    pub asset_registry: Account<'info, AssetRegistry>,
+    
+    #[account(
+        mut,
+        seeds = [b"redemption_config", vault.key().as_ref()],
+        bump = redemption_config.bump
+    )]
+    pub redemption_config: Account<'info, RedemptionConfig>,
+    
+    #[account(
+        mut,
+        address = vault.mint_state @ ErrorCode::InvalidLiabilitySource
+    )]
+    pub mint_state: Account<'info, MintState>,
+    
+    #[account(
+        mut,
+        address = mint_state.aru_mint @ ErrorCode::InvalidLiabilitySource
+    )]
+    pub aru_mint: Account<'info, Mint>,
+    
+    #[account(
+        seeds = [b"ili_oracle"],
+        bump = ili_oracle.bump,
+        seeds::program = ars_core::ID
+    )]
+    pub ili_oracle: Account<'info, ILIOracle>,
+    
+    #[account(
+        mut,
+        constraint = user_aru_account.mint == aru_mint.key() @ ErrorCode::InvalidAssetVault
+    )]
+    pub user_aru_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"insurance_fund", vault.key().as_ref()],
+        bump = insurance_fund.bump
+    )]
+    pub insurance_fund: Account<'info, InsuranceFund>,
+    
+    #[account(
+        mut,
+        address = insurance_fund.aru_account @ ErrorCode::InvalidRecipient
+    )]
+    pub insurance_aru_account: Account<'info, TokenAccount>,
+    
+    pub user: Signer<'info>,
+    
+    pub ars_token_program: Program<'info, ArsToken>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct AddPsmAsset<'info> {
+    #[account(
+        seeds = [b"vault", vault.authority.as_ref()],
+        bump = vault.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub vault: Account<'info, ReserveVault>,
+    
+    #[account(
+        seeds = [b"asset", vault.key().as_ref(), asset_config.mint.as_ref()],
+        bump = asset_config.bump
+    )]
+    pub asset_config: Account<'info, AssetConfig>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = PsmConfig::LEN,
+        seeds = [b"psm", asset_config.key().as_ref()],
+        bump
+    )]
+    pub psm_config: Account<'info, PsmConfig>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdatePsmAsset<'info> {
+    #[account(
+        seeds = [b"vault", vault.authority.as_ref()],
+        bump = vault.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub vault: Account<'info, ReserveVault>,
+    
+    #[account(
+        mut,
+        seeds = [b"psm", psm_config.asset_config.as_ref()],
+        bump = psm_config.bump,
+        constraint = psm_config.reserve_vault == vault.key() @ ErrorCode::Unauthorized
+    )]
+    pub psm_config: Account<'info, PsmConfig>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct PsmSwap<'info> {
+    #[account(
+        mut,
+        seeds = [b"vault", vault.authority.as_ref()],
+        bump = vault.bump,
+        constraint = !vault.settled @ ErrorCode::ReserveSettled
+    )]
+    pub vault: Account<'info, ReserveVault>,
+    
+    #[account(
+        mut,
+        seeds = [b"asset", vault.key().as_ref(), asset_config.mint.as_ref()],
+        bump = asset_config.bump
+    )]
+    pub asset_config: Account<'info, AssetConfig>,
+    
+    #[account(
+        mut,
+        seeds = [b"psm", asset_config.key().as_ref()],
+        bump = psm_config.bump
+    )]
+    pub psm_config: Account<'info, PsmConfig>,
+    
+    /// CHECK: Pyth or Switchboard price account, validated against asset_config.oracle_source
+    #[account(address = asset_config.oracle_source @ ErrorCode::InvalidOracle)]
+    pub price_feed: AccountInfo<'info>,
+    
+    #[account(
+        mut,
+        address = vault.mint_state @ ErrorCode::InvalidLiabilitySource
+    )]
+    pub mint_state: Account<'info, MintState>,
+    
+    #[account(
+        mut,
+        seeds = [b"minter", mint_state.key().as_ref(), vault.key().as_ref()],
+        bump = minter_account.bump,
+        seeds::program = ars_token::ID
+    )]
+    pub minter_account: Account<'info, Minter>,
+    
+    #[account(
+        mut,
+        address = mint_state.aru_mint @ ErrorCode::InvalidLiabilitySource
+    )]
+    pub aru_mint: Account<'info, Mint>,
+    
+    #[account(
+        seeds = [b"ili_oracle"],
+        bump = ili_oracle.bump,
+        seeds::program = ars_core::ID
+    )]
+    pub ili_oracle: Account<'info, ILIOracle>,
+    
+    pub user: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = user_token_account.mint == asset_config.mint @ ErrorCode::InvalidAssetVault
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        address = asset_config.vault @ ErrorCode::InvalidAssetVault
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = user_aru_account.mint == aru_mint.key() @ ErrorCode::InvalidAssetVault
+    )]
+    pub user_aru_account: Account<'info, TokenAccount>,
+    
+    pub ars_token_program: Program<'info, ArsToken>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeRebalanceConfig<'info> {
+    #[account(
+        seeds = [b"vault", vault.authority.as_ref()],
+        bump = vault.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub vault: Account<'info, ReserveVault>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = RebalanceConfig::LEN,
+        seeds = [b"rebalance_config", vault.key().as_ref()],
+        bump
+    )]
+    pub rebalance_config: Account<'info, RebalanceConfig>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateRebalanceConfig<'info> {
+    #[account(
+        seeds = [b"vault", vault.authority.as_ref()],
+        bump = vault.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub vault: Account<'info, ReserveVault>,
+    
+    #[account(
+        mut,
+        seeds = [b"rebalance_config", vault.key().as_ref()],
+        bump = rebalance_config.bump
+    )]
+    pub rebalance_config: Account<'info, RebalanceConfig>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Rebalance<'info> {
+    #[account(
+        mut,
+        seeds = [b"vault", vault.authority.as_ref()],
+        bump = vault.bump,
+        has_one = authority @ ErrorCode::Unauthorized,
+        constraint = !vault.settled @ ErrorCode::ReserveSettled
+    )]
+    pub vault: Account<'info, ReserveVault>,
+    
+    #[account(
+        seeds = [b"rebalance_config", vault.key().as_ref()],
+        bump = rebalance_config.bump,
+        has_one = swap_adapter @ ErrorCode::InvalidSwapAdapter
+    )]
+    pub rebalance_config: Account<'info, RebalanceConfig>,
+    
+    #[account(
+        mut,
+        seeds = [b"asset", vault.key().as_ref(), from_asset.mint.as_ref()],
+        bump = from_asset.bump
+    )]
+    pub from_asset: Account<'info, AssetConfig>,
+    
+    #[account(
+        mut,
+        seeds = [b"asset", vault.key().as_ref(), to_asset.mint.as_ref()],
+        bump = to_asset.bump
+    )]
+    pub to_asset: Account<'info, AssetConfig>,
+    
+    /// CHECK: Pyth or Switchboard price account, validated against from_asset.oracle_source
+    #[account(address = from_asset.oracle_source @ ErrorCode::InvalidOracle)]
+    pub from_price_feed: AccountInfo<'info>,
+    
+    /// CHECK: Pyth or Switchboard price account, validated against to_asset.oracle_source
+    #[account(address = to_asset.oracle_source @ ErrorCode::InvalidOracle)]
+    pub to_price_feed: AccountInfo<'info>,
+    
+    #[account(
+        mut,
+        address = from_asset.vault @ ErrorCode::InvalidAssetVault
+    )]
+    pub from_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        address = to_asset.vault @ ErrorCode::InvalidAssetVault
+    )]
+    pub to_vault: Account<'info, TokenAccount>,
+    
+    /// CHECK: Swap adapter program, validated against rebalance_config.swap_adapter
+    #[account(executable)]
+    pub swap_adapter: UncheckedAccount<'info>,
+    
+    pub authority: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeLstYield<'info> {
+    #[account(
+        seeds = [b"vault", vault.authority.as_ref()],
+        bump = vault.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub vault: Account<'info, ReserveVault>,
+    
+    #[account(
+        mut,
+        seeds = [b"asset", vault.key().as_ref(), asset_config.mint.as_ref()],
+        bump = asset_config.bump
+    )]
+    pub asset_config: Account<'info, AssetConfig>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = LstYieldTracker::LEN,
+        seeds = [b"lst_yield", asset_config.key().as_ref()],
+        bump
+    )]
+    pub lst_yield: Account<'info, LstYieldTracker>,
+    
+    /// CHECK: Marinade or SPL stake pool state, parsed and matched to the asset mint
+    pub stake_pool: AccountInfo<'info>,
+    
+    #[account(constraint = recipient.mint == asset_config.mint @ ErrorCode::InvalidRecipient)]
+    pub recipient: Account<'info, TokenAccount>,
+    
+    #[account(address = vault.mint_state @ ErrorCode::InvalidLiabilitySource)]
+    pub mint_state: Account<'info, MintState>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetYieldBucket<'info> {
+    #[account(
+        seeds = [b"vault", vault.authority.as_ref()],
+        bump = vault.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub vault: Account<'info, ReserveVault>,
+    
+    #[account(
+        seeds = [b"asset", vault.key().as_ref(), asset_config.mint.as_ref()],
+        bump = asset_config.bump
+    )]
+    pub asset_config: Account<'info, AssetConfig>,
+    
+    #[account(
+        mut,
+        seeds = [b"lst_yield", asset_config.key().as_ref()],
+        bump = lst_yield.bump
+    )]
+    pub lst_yield: Account<'info, LstYieldTracker>,
+    
+    #[account(constraint = recipient.mint == asset_config.mint @ ErrorCode::InvalidRecipient)]
+    pub recipient: Account<'info, TokenAccount>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct HarvestLstYield<'info> {
+    #[account(
+        mut,
+        seeds = [b"vault", vault.authority.as_ref()],
+        bump = vault.bump,
+        constraint = !vault.settled @ ErrorCode::ReserveSettled
+    )]
+    pub vault: Account<'info, ReserveVault>,
+    
+    #[account(
+        mut,
+        seeds = [b"asset", vault.key().as_ref(), asset_config.mint.as_ref()],
+        bump = asset_config.bump
+    )]
+    pub asset_config: Account<'info, AssetConfig>,
+    
+    #[account(
+        mut,
+        seeds = [b"lst_yield", asset_config.key().as_ref()],
+        bump = lst_yield.bump,
+        has_one = stake_pool @ ErrorCode::InvalidStakePool
+    )]
+    pub lst_yield: Account<'info, LstYieldTracker>,
+    
+    /// CHECK: Stake pool state, validated against lst_yield.stake_pool
+    pub stake_pool: AccountInfo<'info>,
+    
+    /// CHECK: Pyth or Switchboard price account, validated against asset_config.oracle_source
+    #[account(address = asset_config.oracle_source @ ErrorCode::InvalidOracle)]
+    pub price_feed: AccountInfo<'info>,
+    
+    #[account(address = vault.mint_state @ ErrorCode::InvalidLiabilitySource)]
+    pub mint_state: Account<'info, MintState>,
+}
+
+#[derive(Accounts)]
+pub struct DistributeLstYield<'info> {
+    #[account(
+        seeds = [b"vault", vault.authority.as_ref()],
+        bump = vault.bump,
+        constraint = !vault.settled @ ErrorCode::ReserveSettled
+    )]
+    pub vault: Account<'info, ReserveVault>,
+    
+    #[account(
+        seeds = [b"asset", vault.key().as_ref(), asset_config.mint.as_ref()],
+        bump = asset_config.bump
+    )]
+    pub asset_config: Account<'info, AssetConfig>,
+    
+    #[account(
+        mut,
+        seeds = [b"lst_yield", asset_config.key().as_ref()],
+        bump = lst_yield.bump,
+        has_one = recipient @ ErrorCode::InvalidRecipient
+    )]
+    pub lst_yield: Account<'info, LstYieldTracker>,
+    
+    #[account(
+        mut,
+        address = asset_config.vault @ ErrorCode::InvalidAssetVault
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+    
+    #[account(mut)]
+    pub recipient: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeInsuranceFund<'info> {
+    #[account(
+        seeds = [b"vault", vault.authority.as_ref()],
+        bump = vault.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub vault: Account<'info, ReserveVault>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = InsuranceFund::LEN,
+        seeds = [b"insurance_fund", vault.key().as_ref()],
+        bump
+    )]
+    pub insurance_fund: Account<'info, InsuranceFund>,
+    
+    #[account(address = vault.mint_state @ ErrorCode::InvalidLiabilitySource)]
+    pub mint_state: Account<'info, MintState>,
+    
+    /// Fund-owned ARU account receiving fee shares
+    #[account(
+        constraint = aru_account.owner == insurance_fund.key() @ ErrorCode::InvalidRecipient,
+        constraint = aru_account.mint == mint_state.aru_mint @ ErrorCode::InvalidRecipient
+    )]
+    pub aru_account: Account<'info, TokenAccount>,
+    
+    /// Reserve-owned ARU account receiving stability fees from ars-core
+    #[account(
+        constraint = stability_fee_account.owner == vault.key() @ ErrorCode::InvalidAssetVault,
+        constraint = stability_fee_account.mint == mint_state.aru_mint @ ErrorCode::InvalidAssetVault
+    )]
+    pub stability_fee_account: Account<'info, TokenAccount>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetInsuranceFeeShares<'info> {
+    #[account(
+        seeds = [b"vault", vault.authority.as_ref()],
+        bump = vault.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub vault: Account<'info, ReserveVault>,
+    
+    #[account(
+        mut,
+        seeds = [b"insurance_fund", vault.key().as_ref()],
+        bump = insurance_fund.bump
+    )]
+    pub insurance_fund: Account<'info, InsuranceFund>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct FundInsuranceFromSlash<'info> {
+    #[account(
+        seeds = [b"vault", vault.authority.as_ref()],
+        bump = vault.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub vault: Account<'info, ReserveVault>,
+    
+    #[account(
+        mut,
+        seeds = [b"insurance_fund", vault.key().as_ref()],
+        bump = insurance_fund.bump
+    )]
+    pub insurance_fund: Account<'info, InsuranceFund>,
+    
+    #[account(
+        seeds = [b"asset", vault.key().as_ref(), asset_config.mint.as_ref()],
+        bump = asset_config.bump
+    )]
+    pub asset_config: Account<'info, AssetConfig>,
+    
+    /// CHECK: Pyth or Switchboard price account, validated against asset_config.oracle_source
+    #[account(address = asset_config.oracle_source @ ErrorCode::InvalidOracle)]
+    pub price_feed: AccountInfo<'info>,
+    
+    #[account(mut)]
+    pub authority_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = fund_token_account.owner == insurance_fund.key() @ ErrorCode::InvalidAssetVault,
+        constraint = fund_token_account.mint == asset_config.mint @ ErrorCode::InvalidAssetVault
+    )]
+    pub fund_token_account: Account<'info, TokenAccount>,
+    
+    pub authority: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct RouteStabilityFees<'info> {
+    #[account(
+        seeds = [b"vault", vault.authority.as_ref()],
+        bump = vault.bump
+    )]
+    pub vault: Account<'info, ReserveVault>,
+    
+    #[account(
+        mut,
+        seeds = [b"insurance_fund", vault.key().as_ref()],
+        bump = insurance_fund.bump,
+        has_one = aru_account @ ErrorCode::InvalidRecipient,
+        has_one = stability_fee_account @ ErrorCode::InvalidAssetVault
+    )]
+    pub insurance_fund: Account<'info, InsuranceFund>,
+    
+    #[account(mut)]
+    pub aru_account: Account<'info, TokenAccount>,
+    
+    #[account(mut)]
+    pub stability_fee_account: Account<'info, TokenAccount>,
+    
+    #[account(address = aru_account.mint @ ErrorCode::InvalidLiabilitySource)]
+    pub aru_mint: Account<'info, Mint>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CoverShortfall<'info> {
+    #[account(
+        mut,
+        seeds = [b"vault", vault.authority.as_ref()],
+        bump = vault.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub vault: Account<'info, ReserveVault>,
+    
+    #[account(
+        mut,
+        seeds = [b"insurance_fund", vault.key().as_ref()],
+        bump = insurance_fund.bump
+    )]
+    pub insurance_fund: Account<'info, InsuranceFund>,
+    
+    #[account(
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+        seeds::program = ars_core::ID
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    
+    #[account(
+        mut,
+        seeds = [b"asset", vault.key().as_ref(), asset_config.mint.as_ref()],
+        bump = asset_config.bump
+    )]
+    pub asset_config: Account<'info, AssetConfig>,
+    
+    /// CHECK: Pyth or Switchboard price account, validated against asset_config.oracle_source
+    #[account(address = asset_config.oracle_source @ ErrorCode::InvalidOracle)]
+    pub price_feed: AccountInfo<'info>,
+    
+    #[account(
+        mut,
+        constraint = fund_token_account.owner == insurance_fund.key() @ ErrorCode::InvalidAssetVault,
+        constraint = fund_token_account.mint == asset_config.mint @ ErrorCode::InvalidAssetVault
+    )]
+    pub fund_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        address = asset_config.vault @ ErrorCode::InvalidAssetVault
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+    
+    #[account(address = vault.mint_state @ ErrorCode::InvalidLiabilitySource)]
+    pub mint_state: Account<'info, MintState>,
+    
+    #[account(address = mint_state.aru_mint @ ErrorCode::InvalidLiabilitySource)]
+    pub aru_mint: Account<'info, Mint>,
+    
+    #[account(
+        seeds = [b"ili_oracle"],
+        bump = ili_oracle.bump,
+        seeds::program = ars_core::ID
+    )]
+    pub ili_oracle: Account<'info, ILIOracle>,
+    
+    pub authority: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CoverShortfallWithAru<'info> {
+    #[account(
+        mut,
+        seeds = [b"vault", vault.authority.as_ref()],
+        bump = vault.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub vault: Account<'info, ReserveVault>,
+    
+    #[account(
+        mut,
+        seeds = [b"insurance_fund", vault.key().as_ref()],
+        bump = insurance_fund.bump,
+        has_one = aru_account @ ErrorCode::InvalidRecipient
+    )]
+    pub insurance_fund: Account<'info, InsuranceFund>,
+    
+    #[account(
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+        seeds::program = ars_core::ID
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    
+    #[account(mut)]
+    pub aru_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        address = vault.mint_state @ ErrorCode::InvalidLiabilitySource
+    )]
+    pub mint_state: Account<'info, MintState>,
+    
+    #[account(
+        mut,
+        address = mint_state.aru_mint @ ErrorCode::InvalidLiabilitySource
+    )]
+    pub aru_mint: Account<'info, Mint>,
+    
+    #[account(
+        seeds = [b"ili_oracle"],
+        bump = ili_oracle.bump,
+        seeds::program = ars_core::ID
+    )]
+    pub ili_oracle: Account<'info, ILIOracle>,
+    
+    pub authority: Signer<'info>,
+    
+    pub ars_token_program: Program<'info, ArsToken>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ReportInsuranceFund<'info> {
+    #[account(
+        seeds = [b"vault", vault.authority.as_ref()],
+        bump = vault.bump,
+        has_one = asset_registry
+    )]
+    pub vault: Account<'info, ReserveVault>,
+    
+    pub asset_registry: Account<'info, AssetRegistry>,
+    
+    #[account(
+        mut,
+        seeds = [b"insurance_fund", vault.key().as_ref()],
+        bump = insurance_fund.bump,
+        has_one = aru_account @ ErrorCode::InvalidRecipient
+    )]
+    pub insurance_fund: Account<'info, InsuranceFund>,
+    
+    pub aru_account: Account<'info, TokenAccount>,
+    
+    #[account(address = aru_account.mint @ ErrorCode::InvalidLiabilitySource)]
+    pub aru_mint: Account<'info, Mint>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeAttestationLog<'info> {
+    #[account(
+        seeds = [b"vault", vault.authority.as_ref()],
+        bump = vault.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub vault: Account<'info, ReserveVault>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = AttestationLog::LEN,
+        seeds = [b"attestation_log", vault.key().as_ref()],
+        bump
+    )]
+    pub attestation_log: Account<'info, AttestationLog>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AttestReserves<'info> {
+    #[account(
+        seeds = [b"vault", vault.authority.as_ref()],
+        bump = vault.bump,
+        has_one = asset_registry
+    )]
+    pub vault: Account<'info, ReserveVault>,
+    
+    pub asset_registry: Account<'info, AssetRegistry>,
+    
+    #[account(
+        mut,
+        seeds = [b"attestation_log", vault.key().as_ref()],
+        bump = attestation_log.bump
+    )]
+    pub attestation_log: Account<'info, AttestationLog>,
+    
+    /// One attestation per epoch; a second attempt fails on the existing account
+    #[account(
+        init,
+        payer = payer,
+        space = ReserveAttestation::LEN,
+        seeds = [
+            b"attestation",
+            vault.key().as_ref(),
+            mint_state.current_epoch.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub attestation: Account<'info, ReserveAttestation>,
+    
+    #[account(address = vault.mint_state @ ErrorCode::InvalidLiabilitySource)]
+    pub mint_state: Account<'info, MintState>,
+    
+    #[account(address = mint_state.aru_mint @ ErrorCode::InvalidLiabilitySource)]
+    pub aru_mint: Account<'info, Mint>,
+    
+    #[account(
+        seeds = [b"ili_oracle"],
+        bump = ili_oracle.bump,
+        seeds::program = ars_core::ID
+    )]
+    pub ili_oracle: Account<'info, ILIOracle>,
+    
+    /// CHECK: Instructions sysvar, used to rule out an outstanding flash loan
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: AccountInfo<'info>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitiateSettlement<'info> {
+    #[account(
+        mut,
+        seeds = [b"vault", vault.authority.as_ref()],
+        bump = vault.bump,
+        has_one = asset_registry,
+        constraint = !vault.settled @ ErrorCode::ReserveSettled
+    )]
+    pub vault: Account<'info, ReserveVault>,
+    
+    pub asset_registry: Account<'info, AssetRegistry>,
+    
+    #[account(
+        init,
+        payer = payer,
+        space = Settlement::LEN,
+        seeds = [b"settlement", vault.key().as_ref()],
+        bump
+    )]
+    pub settlement: Account<'info, Settlement>,
+    
+    #[account(
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+        seeds::program = ars_core::ID
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    
+    #[account(address = vault.mint_state @ ErrorCode::InvalidLiabilitySource)]
+    pub mint_state: Account<'info, MintState>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimSettlement<'info> {
+    #[account(
+        seeds = [b"vault", vault.authority.as_ref()],
+        bump = vault.bump
+    )]
+    pub vault: Account<'info, ReserveVault>,
+    
+    #[account(
+        mut,
+        seeds = [b"settlement", vault.key().as_ref()],
+        bump = settlement.bump
+    )]
+    pub settlement: Account<'info, Settlement>,
+    
+    #[account(
+        mut,
+        address = vault.mint_state @ ErrorCode::InvalidLiabilitySource
+    )]
+    pub mint_state: Account<'info, MintState>,
+    
+    #[account(
+        mut,
+        address = mint_state.aru_mint @ ErrorCode::InvalidLiabilitySource
+    )]
+    pub aru_mint: Account<'info, Mint>,
+    
+    #[account(
+        mut,
+        constraint = user_aru_account.mint == aru_mint.key() @ ErrorCode::InvalidAssetVault
+    )]
+    pub user_aru_account: Account<'info, TokenAccount>,
+    
+    pub user: Signer<'info>,
+    
+    pub ars_token_program: Program<'info, ArsToken>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ExitSettledPosition<'info> {
+    #[account(
+        seeds = [b"vault", vault.authority.as_ref()],
+        bump = vault.bump,
+        constraint = vault.settled @ ErrorCode::SettlementNotActive
+    )]
+    pub vault: Account<'info, ReserveVault>,
+    
+    #[account(
+        mut,
+        seeds = [b"asset", vault.key().as_ref(), asset_config.mint.as_ref()],
+        bump = asset_config.bump
+    )]
+    pub asset_config: Account<'info, AssetConfig>,
+    
+    #[account(
+        mut,
+        seeds = [b"position", asset_config.key().as_ref(), owner.key().as_ref()],
+        bump = position.bump
+    )]
+    pub position: Account<'info, DepositorPosition>,
+    
+    pub owner: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = owner_token_account.mint == asset_config.mint @ ErrorCode::InvalidAssetVault,
+        constraint = owner_token_account.owner == owner.key() @ ErrorCode::InvalidRecipient
+    )]
+    pub owner_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        address = asset_config.vault @ ErrorCode::InvalidAssetVault
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeRecapAuction<'info> {
+    #[account(
+        seeds = [b"vault", vault.authority.as_ref()],
+        bump = vault.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub vault: Account<'info, ReserveVault>,
+    
+    #[account(
+        seeds = [b"asset", vault.key().as_ref(), asset_config.mint.as_ref()],
+        bump = asset_config.bump
+    )]
+    pub asset_config: Account<'info, AssetConfig>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = RecapAuction::LEN,
+        seeds = [b"recap_auction", vault.key().as_ref()],
+        bump
+    )]
+    pub recap_auction: Account<'info, RecapAuction>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateRecapAuction<'info> {
+    #[account(
+        seeds = [b"vault", vault.authority.as_ref()],
+        bump = vault.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub vault: Account<'info, ReserveVault>,
+    
+    #[account(
+        mut,
+        seeds = [b"recap_auction", vault.key().as_ref()],
+        bump = recap_auction.bump
+    )]
+    pub recap_auction: Account<'info, RecapAuction>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CrankRecapAuction<'info> {
+    #[account(
+        mut,
+        seeds = [b"vault", vault.authority.as_ref()],
+        bump = vault.bump,
+        constraint = !vault.settled @ ErrorCode::ReserveSettled
+    )]
+    pub vault: Account<'info, ReserveVault>,
+    
+    #[account(
+        mut,
+        seeds = [b"recap_auction", vault.key().as_ref()],
+        bump = recap_auction.bump
+    )]
+    pub recap_auction: Account<'info, RecapAuction>,
+    
+    #[account(
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+        seeds::program = ars_core::ID
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    
+    #[account(address = vault.mint_state @ ErrorCode::InvalidLiabilitySource)]
+    pub mint_state: Account<'info, MintState>,
+    
+    #[account(address = mint_state.aru_mint @ ErrorCode::InvalidLiabilitySource)]
+    pub aru_mint: Account<'info, Mint>,
+    
+    #[account(
+        seeds = [b"ili_oracle"],
+        bump = ili_oracle.bump,
+        seeds::program = ars_core::ID
+    )]
+    pub ili_oracle: Account<'info, ILIOracle>,
+}
+
+#[derive(Accounts)]
+pub struct BidRecapAuction<'info> {
+    #[account(
+        mut,
+        seeds = [b"vault", vault.authority.as_ref()],
+        bump = vault.bump,
+        constraint = !vault.settled @ ErrorCode::ReserveSettled
+    )]
+    pub vault: Account<'info, ReserveVault>,
+    
+    #[account(
+        mut,
+        seeds = [b"recap_auction", vault.key().as_ref()],
+        bump = recap_auction.bump,
+        has_one = asset_config @ ErrorCode::InvalidAssetVault
+    )]
+    pub recap_auction: Account<'info, RecapAuction>,
     
-    let ratio = total_value_usd
-        .checked_mul(10000)
-        .ok_or(ErrorCode::ArithmeticOverflow)?
-        .checked_div(liabilities_usd)
-        .ok_or(ErrorCode::ArithmeticOverflow)?;
+    #[account(
+        mut,
+        seeds = [b"asset", vault.key().as_ref(), asset_config.mint.as_ref()],
+        bump = asset_config.bump
+    )]
+    pub asset_config: Account<'info, AssetConfig>,
+    
+    #[account(
+        init,
+        payer = bidder,
+        space = RecapBond::LEN,
+        seeds = [
+            b"recap_bond",
+            recap_auction.key().as_ref(),
+            recap_auction.bonds_issued.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub bond: Account<'info, RecapBond>,
+    
+    /// CHECK: Pyth or Switchboard price account, validated against asset_config.oracle_source
+    #[account(address = asset_config.oracle_source @ ErrorCode::InvalidOracle)]
+    pub price_feed: AccountInfo<'info>,
+    
+    #[account(
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+        seeds::program = ars_core::ID
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    
+    #[account(address = vault.mint_state @ ErrorCode::InvalidLiabilitySource)]
+    pub mint_state: Account<'info, MintState>,
     
-    Ok(ratio as u16)
+    #[account(address = mint_state.aru_mint @ ErrorCode::InvalidLiabilitySource)]
+    pub aru_mint: Account<'info, Mint>,
+    
+    #[account(
+        seeds = [b"ili_oracle"],
+        bump = ili_oracle.bump,
+        seeds::program = ars_core::ID
+    )]
+    pub ili_oracle: Account<'info, ILIOracle>,
+    
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = bidder_token_account.mint == asset_config.mint @ ErrorCode::InvalidAssetVault
+    )]
+    pub bidder_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        address = asset_config.vault @ ErrorCode::InvalidAssetVault
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct Initialize<'info> {
+pub struct RedeemRecapBond<'info> {
+    #[account(
+        mut,
+        seeds = [b"vault", vault.authority.as_ref()],
+        bump = vault.bump,
+        constraint = !vault.settled @ ErrorCode::ReserveSettled
+    )]
+    pub vault: Account<'info, ReserveVault>,
+    
+    #[account(
+        mut,
+        seeds = [b"recap_auction", vault.key().as_ref()],
+        bump = recap_auction.bump
+    )]
+    pub recap_auction: Account<'info, RecapAuction>,
+    
+    #[account(
+        mut,
+        seeds = [b"recap_bond", recap_auction.key().as_ref(), bond.id.to_le_bytes().as_ref()],
+        bump = bond.bump,
+        has_one = owner @ ErrorCode::Unauthorized,
+        close = owner
+    )]
+    pub bond: Account<'info, RecapBond>,
+    
+    #[account(
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+        seeds::program = ars_core::ID
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    
+    #[account(
+        mut,
+        address = vault.mint_state @ ErrorCode::InvalidLiabilitySource
+    )]
+    pub mint_state: Account<'info, MintState>,
+    
+    #[account(
+        mut,
+        seeds = [b"minter", mint_state.key().as_ref(), vault.key().as_ref()],
+        bump = minter_account.bump,
+        seeds::program = ars_token::ID
+    )]
+    pub minter_account: Account<'info, Minter>,
+    
+    #[account(
+        mut,
+        address = mint_state.aru_mint @ ErrorCode::InvalidLiabilitySource
+    )]
+    pub aru_mint: Account<'info, Mint>,
+    
+    #[account(
+        seeds = [b"ili_oracle"],
+        bump = ili_oracle.bump,
+        seeds::program = ars_core::ID
+    )]
+    pub ili_oracle: Account<'info, ILIOracle>,
+    
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = owner_aru_account.mint == aru_mint.key() @ ErrorCode::InvalidAssetVault,
+        constraint = owner_aru_account.owner == owner.key() @ ErrorCode::InvalidRecipient
+    )]
+    pub owner_aru_account: Account<'info, TokenAccount>,
+    
+    pub ars_token_program: Program<'info, ArsToken>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct AddFlashLoanAsset<'info> {
+    #[account(
+        seeds = [b"vault", vault.authority.as_ref()],
+        bump = vault.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub vault: Account<'info, ReserveVault>,
+    
+    #[account(
+        seeds = [b"asset", vault.key().as_ref(), asset_config.mint.as_ref()],
+        bump = asset_config.bump
+    )]
+    pub asset_config: Account<'info, AssetConfig>,
+    
     #[account(
         init,
         payer = authority,
-        space = ReserveVault::LEN,
-        seeds = [b"vault", authority.key().as_ref()],
+        space = FlashLoanConfig::LEN,
+        seeds = [b"flash_loan", asset_config.key().as_ref()],
         bump
     )]
-    pub vault: Account<'info, ReserveVault>,
+    pub flash_loan_config: Account<'info, FlashLoanConfig>,
     
     #[account(mut)]
     pub authority: Signer<'info>,
     
-    /// CHECK: USDC vault token account
-    pub usdc_vault: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateFlashLoanAsset<'info> {
+    #[account(
+        seeds = [b"vault", vault.authority.as_ref()],
+        bump = vault.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub vault: Account<'info, ReserveVault>,
+    
+    #[account(
+        mut,
+        seeds = [b"flash_loan", flash_loan_config.asset_config.as_ref()],
+        bump = flash_loan_config.bump,
+        constraint = flash_loan_config.reserve_vault == vault.key() @ ErrorCode::Unauthorized
+    )]
+    pub flash_loan_config: Account<'info, FlashLoanConfig>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct FlashLoan<'info> {
+    #[account(
+        seeds = [b"vault", vault.authority.as_ref()],
+        bump = vault.bump,
+        constraint = !vault.settled @ ErrorCode::ReserveSettled
+    )]
+    pub vault: Account<'info, ReserveVault>,
+    
+    #[account(
+        seeds = [b"asset", vault.key().as_ref(), asset_config.mint.as_ref()],
+        bump = asset_config.bump
+    )]
+    pub asset_config: Account<'info, AssetConfig>,
+    
+    #[account(
+        mut,
+        seeds = [b"flash_loan", asset_config.key().as_ref()],
+        bump = flash_loan_config.bump
+    )]
+    pub flash_loan_config: Account<'info, FlashLoanConfig>,
+    
+    pub borrower: Signer<'info>,
     
-    /// CHECK: SOL vault token account
-    pub sol_vault: AccountInfo<'info>,
+    #[account(
+        mut,
+        constraint = borrower_token_account.mint == asset_config.mint @ ErrorCode::InvalidAssetVault
+    )]
+    pub borrower_token_account: Account<'info, TokenAccount>,
     
-    /// CHECK: mSOL vault token account
-    pub msol_vault: AccountInfo<'info>,
+    #[account(
+        mut,
+        address = asset_config.vault @ ErrorCode::InvalidAssetVault
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
     
-    /// CHECK: JitoSOL vault token account
-    pub jitosol_vault: AccountInfo<'info>,
+    /// CHECK: Instructions sysvar, used to find the matching repayment
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: AccountInfo<'info>,
     
-    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
 }
 
 #[derive(Accounts)]
-pub struct Deposit<'info> {
+pub struct RepayFlashLoan<'info> {
     #[account(
         mut,
         seeds = [b"vault", vault.authority.as_ref()],
@@ -189,47 +5625,233 @@ pub struct Deposit<'info> {
     )]
     pub vault: Account<'info, ReserveVault>,
     
-    #[account(mut)]
-    pub user: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"asset", vault.key().as_ref(), asset_config.mint.as_ref()],
+        bump = asset_config.bump
+    )]
+    pub asset_config: Account<'info, AssetConfig>,
     
-    #[account(mut)]
-    pub user_token_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"flash_loan", asset_config.key().as_ref()],
+        bump = flash_loan_config.bump
+    )]
+    pub flash_loan_config: Account<'info, FlashLoanConfig>,
     
-    #[account(mut)]
+    pub borrower: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = borrower_token_account.mint == asset_config.mint @ ErrorCode::InvalidAssetVault
+    )]
+    pub borrower_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        address = asset_config.vault @ ErrorCode::InvalidAssetVault
+    )]
     pub vault_token_account: Account<'info, TokenAccount>,
     
     pub token_program: Program<'info, Token>,
 }
 
 #[derive(Accounts)]
-pub struct Withdraw<'info> {
+pub struct AddStrategy<'info> {
     #[account(
-        mut,
         seeds = [b"vault", vault.authority.as_ref()],
-        bump = vault.bump
+        bump = vault.bump,
+        has_one = authority @ ErrorCode::Unauthorized
     )]
     pub vault: Account<'info, ReserveVault>,
     
-    #[account(mut)]
-    pub user: Signer<'info>,
+    #[account(
+        seeds = [b"asset", vault.key().as_ref(), asset_config.mint.as_ref()],
+        bump = asset_config.bump
+    )]
+    pub asset_config: Account<'info, AssetConfig>,
     
-    #[account(mut)]
-    pub user_token_account: Account<'info, TokenAccount>,
+    #[account(
+        init,
+        payer = authority,
+        space = StrategyAllocation::LEN,
+        seeds = [b"strategy", asset_config.key().as_ref(), adapter_program.key().as_ref()],
+        bump
+    )]
+    pub strategy: Account<'info, StrategyAllocation>,
+    
+    /// CHECK: Strategy adapter program implementing deposit, withdraw and report_value
+    #[account(executable)]
+    pub adapter_program: UncheckedAccount<'info>,
     
     #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateStrategy<'info> {
+    #[account(
+        seeds = [b"vault", vault.authority.as_ref()],
+        bump = vault.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub vault: Account<'info, ReserveVault>,
+    
+    #[account(
+        mut,
+        seeds = [b"strategy", strategy.asset_config.as_ref(), strategy.adapter_program.as_ref()],
+        bump = strategy.bump,
+        constraint = strategy.reserve_vault == vault.key() @ ErrorCode::Unauthorized
+    )]
+    pub strategy: Account<'info, StrategyAllocation>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ManageStrategy<'info> {
+    #[account(
+        mut,
+        seeds = [b"vault", vault.authority.as_ref()],
+        bump = vault.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub vault: Account<'info, ReserveVault>,
+    
+    #[account(
+        mut,
+        seeds = [b"asset", vault.key().as_ref(), asset_config.mint.as_ref()],
+        bump = asset_config.bump
+    )]
+    pub asset_config: Account<'info, AssetConfig>,
+    
+    #[account(
+        mut,
+        seeds = [b"strategy", asset_config.key().as_ref(), adapter_program.key().as_ref()],
+        bump = strategy.bump
+    )]
+    pub strategy: Account<'info, StrategyAllocation>,
+    
+    #[account(
+        mut,
+        address = asset_config.vault @ ErrorCode::InvalidAssetVault
+    )]
     pub vault_token_account: Account<'info, TokenAccount>,
     
+    /// CHECK: Strategy adapter program, fixed by the strategy PDA seeds
+    #[account(executable)]
+    pub adapter_program: UncheckedAccount<'info>,
+    
+    pub authority: Signer<'info>,
+    
     pub token_program: Program<'info, Token>,
 }
 
 #[derive(Accounts)]
-pub struct Rebalance<'info> {
+pub struct ReportStrategy<'info> {
     #[account(
         mut,
         seeds = [b"vault", vault.authority.as_ref()],
-        bump = vault.bump
+        bump = vault.bump,
+        constraint = !vault.settled @ ErrorCode::ReserveSettled
+    )]
+    pub vault: Account<'info, ReserveVault>,
+    
+    #[account(
+        mut,
+        seeds = [b"asset", vault.key().as_ref(), asset_config.mint.as_ref()],
+        bump = asset_config.bump
+    )]
+    pub asset_config: Account<'info, AssetConfig>,
+    
+    #[account(
+        mut,
+        seeds = [b"strategy", asset_config.key().as_ref(), adapter_program.key().as_ref()],
+        bump = strategy.bump
+    )]
+    pub strategy: Account<'info, StrategyAllocation>,
+    
+    /// CHECK: Strategy adapter program, fixed by the strategy PDA seeds
+    #[account(executable)]
+    pub adapter_program: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeNavSnapshot<'info> {
+    #[account(
+        seeds = [b"vault", vault.authority.as_ref()],
+        bump = vault.bump,
+        has_one = authority @ ErrorCode::Unauthorized
     )]
     pub vault: Account<'info, ReserveVault>,
     
+    #[account(
+        init,
+        payer = authority,
+        space = NavSnapshot::LEN,
+        seeds = [b"nav_snapshot", vault.key().as_ref()],
+        bump
+    )]
+    pub nav_snapshot: Account<'info, NavSnapshot>,
+    
+    /// CHECK: Raw Pyth v2 price layout, written only by update_nav
+    #[account(
+        init,
+        payer = authority,
+        space = oracle::PYTH_PRICE_ACCOUNT_LEN,
+        owner = crate::ID,
+        seeds = [b"nav_price", vault.key().as_ref()],
+        bump
+    )]
+    pub nav_price_feed: UncheckedAccount<'info>,
+    
+    #[account(mut)]
     pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateNav<'info> {
+    #[account(
+        seeds = [b"vault", vault.authority.as_ref()],
+        bump = vault.bump
+    )]
+    pub vault: Account<'info, ReserveVault>,
+    
+    #[account(
+        mut,
+        seeds = [b"nav_snapshot", vault.key().as_ref()],
+        bump = nav_snapshot.bump,
+        has_one = price_feed @ ErrorCode::InvalidOracle
+    )]
+    pub nav_snapshot: Account<'info, NavSnapshot>,
+    
+    /// CHECK: Address pinned by the snapshot; the PDA is owned by this program
+    #[account(mut)]
+    pub price_feed: UncheckedAccount<'info>,
+    
+    #[account(address = vault.mint_state @ ErrorCode::InvalidLiabilitySource)]
+    pub mint_state: Account<'info, MintState>,
+    
+    #[account(address = mint_state.aru_mint @ ErrorCode::InvalidLiabilitySource)]
+    pub aru_mint: Account<'info, Mint>,
+    
+    #[account(
+        seeds = [b"ili_oracle"],
+        bump = ili_oracle.bump,
+        seeds::program = ars_core::ID
+    )]
+    pub ili_oracle: Account<'info, ILIOracle>,
+}
+
+#[derive(Accounts)]
+pub struct GetNav<'info> {
+    #[account(
+        seeds = [b"nav_snapshot", nav_snapshot.reserve_vault.as_ref()],
+        bump = nav_snapshot.bump
+    )]
+    pub nav_snapshot: Account<'info, NavSnapshot>,
 }
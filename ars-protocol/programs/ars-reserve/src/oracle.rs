@@ -0,0 +1,296 @@
+use anchor_lang::prelude::*;
+use ars_core::ILI_DECIMALS;
+
+use crate::errors::ErrorCode;
+
+/// USD values are carried with 6 decimals, matching USDC
+pub const USD_DECIMALS: i32 = 6;
+
+pub const PYTH_MAGIC: u32 = 0xa1b2c3d4;
+pub const PYTH_ATYPE_PRICE: u32 = 3;
+pub const PYTH_STATUS_UNKNOWN: u32 = 0;
+pub const PYTH_STATUS_TRADING: u32 = 1;
+pub const PYTH_VERSION: u32 = 2;
+pub const PYTH_PTYPE_PRICE: u32 = 1;
+
+/// Size of a Pyth v2 price account, including the 32 publisher components
+pub const PYTH_PRICE_ACCOUNT_LEN: usize = 3312;
+
+// Pyth v2 price account offsets
+const PYTH_MAGIC_OFFSET: usize = 0;
+const PYTH_VERSION_OFFSET: usize = 4;
+const PYTH_ATYPE_OFFSET: usize = 8;
+const PYTH_SIZE_OFFSET: usize = 12;
+const PYTH_PTYPE_OFFSET: usize = 16;
+const PYTH_EXPO_OFFSET: usize = 20;
+const PYTH_LAST_SLOT_OFFSET: usize = 32;
+const PYTH_VALID_SLOT_OFFSET: usize = 40;
+const PYTH_EMA_PRICE_OFFSET: usize = 48;
+const PYTH_TIMESTAMP_OFFSET: usize = 96;
+const PYTH_PREV_SLOT_OFFSET: usize = 176;
+const PYTH_PREV_PRICE_OFFSET: usize = 184;
+const PYTH_PREV_CONF_OFFSET: usize = 192;
+const PYTH_PREV_TIMESTAMP_OFFSET: usize = 200;
+const PYTH_AGG_PRICE_OFFSET: usize = 208;
+const PYTH_AGG_CONF_OFFSET: usize = 216;
+const PYTH_AGG_STATUS_OFFSET: usize = 224;
+const PYTH_AGG_PUB_SLOT_OFFSET: usize = 232;
+
+pub const SWITCHBOARD_AGGREGATOR_DISCRIMINATOR: [u8; 8] = [217, 230, 65, 101, 201, 162, 27, 125];
+
+// Switchboard v2 AggregatorAccountData offsets (latest_confirmed_round)
+const SWITCHBOARD_ROUND_OPEN_TIMESTAMP_OFFSET: usize = 358;
+const SWITCHBOARD_RESULT_MANTISSA_OFFSET: usize = 366;
+const SWITCHBOARD_RESULT_SCALE_OFFSET: usize = 382;
+const SWITCHBOARD_STD_DEV_MANTISSA_OFFSET: usize = 386;
+const SWITCHBOARD_STD_DEV_SCALE_OFFSET: usize = 402;
+
+pub struct OraclePrice {
+    pub price_usd: u64,
+    pub conf_usd: u64,
+    pub publish_time: i64,
+}
+
+/// Read a Pyth or Switchboard price account and normalize it to USD_DECIMALS
+pub fn load_price(
+    price_feed: &AccountInfo,
+    max_price_age: i64,
+    max_confidence_bps: u16,
+    now: i64,
+) -> Result<OraclePrice> {
+    let data = price_feed.try_borrow_data()?;
+
+    let price = if data.len() >= 8 && data[..8] == SWITCHBOARD_AGGREGATOR_DISCRIMINATOR {
+        parse_switchboard(&data)?
+    } else {
+        parse_pyth(&data)?
+    };
+
+    require!(
+        now.saturating_sub(price.publish_time) <= max_price_age,
+        ErrorCode::StalePrice
+    );
+    require!(price.price_usd > 0, ErrorCode::InvalidPrice);
+
+    let conf_bps = (price.conf_usd as u128)
+        .checked_mul(10000)
+        .ok_or(ErrorCode::ArithmeticOverflow)?
+        .checked_div(price.price_usd as u128)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+    require!(
+        conf_bps <= max_confidence_bps as u128,
+        ErrorCode::PriceConfidenceTooWide
+    );
+
+    Ok(price)
+}
+
+/// USD value of `amount` base units after the asset haircut
+pub fn asset_value_usd(
+    amount: u64,
+    decimals: u8,
+    price_usd: u64,
+    haircut_bps: u16,
+) -> Result<u64> {
+    let value = (amount as u128)
+        .checked_mul(price_usd as u128)
+        .ok_or(ErrorCode::ArithmeticOverflow)?
+        .checked_div(
+            10u128
+                .checked_pow(decimals as u32)
+                .ok_or(ErrorCode::ArithmeticOverflow)?,
+        )
+        .ok_or(ErrorCode::ArithmeticOverflow)?
+        .checked_mul(10000u128 - haircut_bps as u128)
+        .ok_or(ErrorCode::ArithmeticOverflow)?
+        .checked_div(10000)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+    u64::try_from(value).map_err(|_| error!(ErrorCode::ArithmeticOverflow))
+}
+
+/// Base units of an asset worth `value_usd` at `price_usd`, before any haircut
+pub fn amount_for_value_usd(
+    value_usd: u64,
+    decimals: u8,
+    price_usd: u64,
+) -> Result<u64> {
+    let amount = (value_usd as u128)
+        .checked_mul(
+            10u128
+                .checked_pow(decimals as u32)
+                .ok_or(ErrorCode::ArithmeticOverflow)?,
+        )
+        .ok_or(ErrorCode::ArithmeticOverflow)?
+        .checked_div(price_usd as u128)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+    u64::try_from(amount).map_err(|_| error!(ErrorCode::ArithmeticOverflow))
+}
+
+/// Largest amount of an asset whose haircut value stays within `value_usd`
+pub fn amount_within_value_usd(
+    value_usd: u64,
+    decimals: u8,
+    price_usd: u64,
+    haircut_bps: u16,
+) -> Result<u64> {
+    if haircut_bps >= 10000 {
+        return Ok(u64::MAX);
+    }
+
+    let gross_value_usd = (value_usd as u128)
+        .checked_mul(10000)
+        .ok_or(ErrorCode::ArithmeticOverflow)?
+        .checked_div(10000u128 - haircut_bps as u128)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+    amount_for_value_usd(
+        u64::try_from(gross_value_usd).map_err(|_| error!(ErrorCode::ArithmeticOverflow))?,
+        decimals,
+        price_usd,
+    )
+}
+
+/// ARU target price in USD implied by the current ILI
+pub fn ili_price_usd(current_ili: u64) -> Result<u64> {
+    normalize(current_ili as i128, -ILI_DECIMALS)
+}
+
+/// Publish a USD_DECIMALS price into a Pyth v2 price account layout.
+///
+/// The previous aggregate is rolled into the `prev_*` fields, and the EMA is
+/// pinned to the latest price since there is a single publisher. A price that
+/// is not `trading` is marked unknown so consumers reject it.
+pub fn write_pyth_price(
+    data: &mut [u8],
+    price_usd: u64,
+    trading: bool,
+    slot: u64,
+    timestamp: i64,
+) -> Result<()> {
+    require!(data.len() >= PYTH_PRICE_ACCOUNT_LEN, ErrorCode::InvalidOracle);
+    let price = i64::try_from(price_usd).map_err(|_| error!(ErrorCode::ArithmeticOverflow))?;
+
+    if read_u32(data, PYTH_MAGIC_OFFSET)? == PYTH_MAGIC {
+        let prev_slot = read_u64(data, PYTH_AGG_PUB_SLOT_OFFSET)?;
+        let prev_price = read_bytes::<8>(data, PYTH_AGG_PRICE_OFFSET)?;
+        let prev_conf = read_bytes::<8>(data, PYTH_AGG_CONF_OFFSET)?;
+        let prev_timestamp = read_bytes::<8>(data, PYTH_TIMESTAMP_OFFSET)?;
+        write_bytes(data, PYTH_PREV_SLOT_OFFSET, &prev_slot.to_le_bytes());
+        write_bytes(data, PYTH_PREV_PRICE_OFFSET, &prev_price);
+        write_bytes(data, PYTH_PREV_CONF_OFFSET, &prev_conf);
+        write_bytes(data, PYTH_PREV_TIMESTAMP_OFFSET, &prev_timestamp);
+    }
+
+    let status = if trading { PYTH_STATUS_TRADING } else { PYTH_STATUS_UNKNOWN };
+
+    write_bytes(data, PYTH_MAGIC_OFFSET, &PYTH_MAGIC.to_le_bytes());
+    write_bytes(data, PYTH_VERSION_OFFSET, &PYTH_VERSION.to_le_bytes());
+    write_bytes(data, PYTH_ATYPE_OFFSET, &PYTH_ATYPE_PRICE.to_le_bytes());
+    write_bytes(data, PYTH_SIZE_OFFSET, &(PYTH_PRICE_ACCOUNT_LEN as u32).to_le_bytes());
+    write_bytes(data, PYTH_PTYPE_OFFSET, &PYTH_PTYPE_PRICE.to_le_bytes());
+    write_bytes(data, PYTH_EXPO_OFFSET, &(-USD_DECIMALS).to_le_bytes());
+    write_bytes(data, PYTH_LAST_SLOT_OFFSET, &slot.to_le_bytes());
+    write_bytes(data, PYTH_VALID_SLOT_OFFSET, &slot.to_le_bytes());
+    // ema_price is a (val, numer, denom) rational; ema_conf stays zero
+    write_bytes(data, PYTH_EMA_PRICE_OFFSET, &price.to_le_bytes());
+    write_bytes(data, PYTH_EMA_PRICE_OFFSET + 8, &price.to_le_bytes());
+    write_bytes(data, PYTH_EMA_PRICE_OFFSET + 16, &1i64.to_le_bytes());
+    write_bytes(data, PYTH_TIMESTAMP_OFFSET, &timestamp.to_le_bytes());
+    write_bytes(data, PYTH_AGG_PRICE_OFFSET, &price.to_le_bytes());
+    write_bytes(data, PYTH_AGG_CONF_OFFSET, &0u64.to_le_bytes());
+    write_bytes(data, PYTH_AGG_STATUS_OFFSET, &status.to_le_bytes());
+    write_bytes(data, PYTH_AGG_PUB_SLOT_OFFSET, &slot.to_le_bytes());
+
+    Ok(())
+}
+
+fn parse_pyth(data: &[u8]) -> Result<OraclePrice> {
+    require!(
+        read_u32(data, PYTH_MAGIC_OFFSET)? == PYTH_MAGIC
+            && read_u32(data, PYTH_ATYPE_OFFSET)? == PYTH_ATYPE_PRICE,
+        ErrorCode::InvalidOracle
+    );
+    require!(
+        read_u32(data, PYTH_AGG_STATUS_OFFSET)? == PYTH_STATUS_TRADING,
+        ErrorCode::InvalidPrice
+    );
+
+    let expo = read_i32(data, PYTH_EXPO_OFFSET)?;
+    let price = read_i64(data, PYTH_AGG_PRICE_OFFSET)? as i128;
+    let conf = read_u64(data, PYTH_AGG_CONF_OFFSET)? as i128;
+
+    Ok(OraclePrice {
+        price_usd: normalize(price, expo)?,
+        conf_usd: normalize(conf, expo)?,
+        publish_time: read_i64(data, PYTH_TIMESTAMP_OFFSET)?,
+    })
+}
+
+fn parse_switchboard(data: &[u8]) -> Result<OraclePrice> {
+    let price = read_i128(data, SWITCHBOARD_RESULT_MANTISSA_OFFSET)?;
+    let price_scale = read_u32(data, SWITCHBOARD_RESULT_SCALE_OFFSET)?;
+    let std_dev = read_i128(data, SWITCHBOARD_STD_DEV_MANTISSA_OFFSET)?;
+    let std_dev_scale = read_u32(data, SWITCHBOARD_STD_DEV_SCALE_OFFSET)?;
+
+    let price_expo = i32::try_from(price_scale).map_err(|_| error!(ErrorCode::InvalidOracle))?;
+    let std_dev_expo = i32::try_from(std_dev_scale).map_err(|_| error!(ErrorCode::InvalidOracle))?;
+
+    Ok(OraclePrice {
+        price_usd: normalize(price, -price_expo)?,
+        conf_usd: normalize(std_dev.checked_abs().ok_or(ErrorCode::ArithmeticOverflow)?, -std_dev_expo)?,
+        publish_time: read_i64(data, SWITCHBOARD_ROUND_OPEN_TIMESTAMP_OFFSET)?,
+    })
+}
+
+/// Rescale `mantissa * 10^expo` to USD_DECIMALS fixed point
+fn normalize(mantissa: i128, expo: i32) -> Result<u64> {
+    require!(mantissa >= 0, ErrorCode::InvalidPrice);
+
+    let shift = expo
+        .checked_add(USD_DECIMALS)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+    let factor = 10i128
+        .checked_pow(shift.unsigned_abs())
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+    let value = if shift >= 0 {
+        mantissa.checked_mul(factor)
+    } else {
+        mantissa.checked_div(factor)
+    }
+    .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+    u64::try_from(value).map_err(|_| error!(ErrorCode::ArithmeticOverflow))
+}
+
+fn read_bytes<const N: usize>(data: &[u8], offset: usize) -> Result<[u8; N]> {
+    data.get(offset..offset + N)
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or(error!(ErrorCode::InvalidOracle))
+}
+
+fn write_bytes(data: &mut [u8], offset: usize, bytes: &[u8]) {
+    data[offset..offset + bytes.len()].copy_from_slice(bytes);
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<u32> {
+    Ok(u32::from_le_bytes(read_bytes(data, offset)?))
+}
+
+fn read_i32(data: &[u8], offset: usize) -> Result<i32> {
+    Ok(i32::from_le_bytes(read_bytes(data, offset)?))
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Result<u64> {
+    Ok(u64::from_le_bytes(read_bytes(data, offset)?))
+}
+
+fn read_i64(data: &[u8], offset: usize) -> Result<i64> {
+    Ok(i64::from_le_bytes(read_bytes(data, offset)?))
+}
+
+fn read_i128(data: &[u8], offset: usize) -> Result<i128> {
+    Ok(i128::from_le_bytes(read_bytes(data, offset)?))
+}
@@ -0,0 +1,115 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+
+/// LST exchange rates are carried as lamports per 10^9 pool token base units
+pub const LST_RATE_DECIMALS: u32 = 9;
+
+pub const MARINADE_PROGRAM_ID: Pubkey = pubkey!("MarBmsSgKXdrN1egZf5sqe1TMai9K1rChYNDJgjq7aD");
+
+pub const SPL_STAKE_POOL_PROGRAM_ID: Pubkey = pubkey!("SPoo1Ku8WFXoNDMHPsrGSTSG1Y47rzgn41SLUNakuHy");
+
+pub const MARINADE_STATE_DISCRIMINATOR: [u8; 8] = [216, 146, 107, 94, 104, 75, 182, 177];
+
+/// Marinade scales `msol_price` by 2^32
+const MARINADE_PRICE_DENOMINATOR: u128 = 0x1_0000_0000;
+
+// Marinade State offsets
+const MARINADE_MSOL_MINT_OFFSET: usize = 8;
+const MARINADE_MSOL_PRICE_OFFSET: usize = 512;
+
+pub const SPL_ACCOUNT_TYPE_STAKE_POOL: u8 = 1;
+
+// SPL StakePool offsets (JitoSOL)
+const SPL_ACCOUNT_TYPE_OFFSET: usize = 0;
+const SPL_POOL_MINT_OFFSET: usize = 162;
+const SPL_TOTAL_LAMPORTS_OFFSET: usize = 258;
+const SPL_POOL_TOKEN_SUPPLY_OFFSET: usize = 266;
+
+pub struct StakePoolRate {
+    pub pool_mint: Pubkey,
+    pub rate: u64,
+}
+
+/// Read a Marinade or SPL stake pool and return its LST exchange rate
+pub fn load_exchange_rate(stake_pool: &AccountInfo) -> Result<StakePoolRate> {
+    let data = stake_pool.try_borrow_data()?;
+
+    let pool_rate = if *stake_pool.owner == MARINADE_PROGRAM_ID {
+        parse_marinade(&data)?
+    } else if *stake_pool.owner == SPL_STAKE_POOL_PROGRAM_ID {
+        parse_spl_stake_pool(&data)?
+    } else {
+        return err!(ErrorCode::InvalidStakePool);
+    };
+
+    require!(pool_rate.rate > 0, ErrorCode::InvalidStakePool);
+
+    Ok(pool_rate)
+}
+
+/// Pool token base units of a `balance` that accrued since `last_rate`, at `rate`
+pub fn accrued_yield(balance: u64, last_rate: u64, rate: u64) -> Result<u64> {
+    if rate <= last_rate {
+        return Ok(0);
+    }
+
+    let amount = (balance as u128)
+        .checked_mul((rate - last_rate) as u128)
+        .ok_or(ErrorCode::ArithmeticOverflow)?
+        .checked_div(rate as u128)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+    u64::try_from(amount).map_err(|_| error!(ErrorCode::ArithmeticOverflow))
+}
+
+fn parse_marinade(data: &[u8]) -> Result<StakePoolRate> {
+    require!(
+        data.len() >= 8 && data[..8] == MARINADE_STATE_DISCRIMINATOR,
+        ErrorCode::InvalidStakePool
+    );
+
+    let msol_price = read_u64(data, MARINADE_MSOL_PRICE_OFFSET)? as u128;
+    let rate = msol_price
+        .checked_mul(10u128.pow(LST_RATE_DECIMALS))
+        .ok_or(ErrorCode::ArithmeticOverflow)?
+        .checked_div(MARINADE_PRICE_DENOMINATOR)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+    Ok(StakePoolRate {
+        pool_mint: Pubkey::new_from_array(read_bytes(data, MARINADE_MSOL_MINT_OFFSET)?),
+        rate: u64::try_from(rate).map_err(|_| error!(ErrorCode::ArithmeticOverflow))?,
+    })
+}
+
+fn parse_spl_stake_pool(data: &[u8]) -> Result<StakePoolRate> {
+    require!(
+        data.get(SPL_ACCOUNT_TYPE_OFFSET) == Some(&SPL_ACCOUNT_TYPE_STAKE_POOL),
+        ErrorCode::InvalidStakePool
+    );
+
+    let total_lamports = read_u64(data, SPL_TOTAL_LAMPORTS_OFFSET)? as u128;
+    let pool_token_supply = read_u64(data, SPL_POOL_TOKEN_SUPPLY_OFFSET)? as u128;
+    require!(pool_token_supply > 0, ErrorCode::InvalidStakePool);
+
+    let rate = total_lamports
+        .checked_mul(10u128.pow(LST_RATE_DECIMALS))
+        .ok_or(ErrorCode::ArithmeticOverflow)?
+        .checked_div(pool_token_supply)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+    Ok(StakePoolRate {
+        pool_mint: Pubkey::new_from_array(read_bytes(data, SPL_POOL_MINT_OFFSET)?),
+        rate: u64::try_from(rate).map_err(|_| error!(ErrorCode::ArithmeticOverflow))?,
+    })
+}
+
+fn read_bytes<const N: usize>(data: &[u8], offset: usize) -> Result<[u8; N]> {
+    data.get(offset..offset + N)
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or(error!(ErrorCode::InvalidStakePool))
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Result<u64> {
+    Ok(u64::from_le_bytes(read_bytes(data, offset)?))
+}
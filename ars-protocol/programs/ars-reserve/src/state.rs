@@ -1,7 +1,52 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hash;
+
+use crate::errors::ErrorCode;
+
+pub const MAX_ASSETS: usize = 16;
+
+pub const MAX_QUEUED_WITHDRAWALS: usize = 32;
+
+pub const MAX_QUEUED_WITHDRAWALS_PER_OWNER: usize = 4;
 
 #[account]
 pub struct ReserveVault {
+    pub authority: Pubkey,
+    pub asset_registry: Pubkey,
+    pub mint_state: Pubkey,
+    pub total_value_usd: u64,
+    pub liabilities_usd: u64,
+    pub aru_price_usd: u64,
+    pub last_liability_update: i64,
+    pub vhr: u64,
+    pub last_rebalance: i64,
+    pub rebalance_threshold_bps: u16,
+    pub min_vhr: u64,
+    /// Frozen for global settlement; assets leave only through settlement claims
+    pub settled: bool,
+    pub bump: u8,
+}
+
+impl ReserveVault {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // authority
+        32 + // asset_registry
+        32 + // mint_state
+        8 + // total_value_usd
+        8 + // liabilities_usd
+        8 + // aru_price_usd
+        8 + // last_liability_update
+        8 + // vhr
+        8 + // last_rebalance
+        2 + // rebalance_threshold_bps
+        8 + // min_vhr
+        1 + // settled
+        1; // bump
+}
+
+/// `ReserveVault` layout before per-asset accounting, read by `migrate_vault`
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct LegacyReserveVault {
     pub authority: Pubkey,
     pub usdc_vault: Pubkey,
     pub sol_vault: Pubkey,
@@ -16,7 +61,7 @@ pub struct ReserveVault {
     pub bump: u8,
 }
 
-impl ReserveVault {
+impl LegacyReserveVault {
     pub const LEN: usize = 8 + // discriminator
         32 + // authority
         32 + // usdc_vault
@@ -32,8 +77,25 @@ impl ReserveVault {
         1; // bump
 }
 
+#[account]
+pub struct AssetRegistry {
+    pub reserve_vault: Pubkey,
+    pub assets: Vec<Pubkey>,
+    pub total_target_weight_bps: u16,
+    pub bump: u8,
+}
+
+impl AssetRegistry {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // reserve_vault
+        4 + 32 * MAX_ASSETS + // assets
+        2 + // total_target_weight_bps
+        1; // bump
+}
+
 #[account]
 pub struct AssetConfig {
+    pub reserve_vault: Pubkey,
     pub mint: Pubkey,
     pub vault: Pubkey,
     pub target_weight_bps: u16,
@@ -42,11 +104,27 @@ pub struct AssetConfig {
     pub volatility_threshold_bps: u16,
     pub current_weight_bps: u16,
     pub oracle_source: Pubkey,
+    pub max_price_age: i64,
+    pub max_confidence_bps: u16,
+    pub haircut_bps: u16,
+    pub decimals: u8,
+    pub balance: u64,
+    /// Part of `balance` owed to depositor positions, including queued withdrawals
+    pub position_balance: u64,
+    /// Part of `balance` deployed into yield strategies
+    pub deployed: u64,
+    /// Protocol-owned balance held since the last LST yield harvest; only this
+    /// part has appreciated from the tracker's `last_rate`
+    pub yield_basis: u64,
+    pub price_usd: u64,
+    pub value_usd: u64,
+    pub last_price_update: i64,
     pub bump: u8,
 }
 
 impl AssetConfig {
     pub const LEN: usize = 8 + // discriminator
+        32 + // reserve_vault
         32 + // mint
         32 + // vault
         2 + // target_weight_bps
@@ -55,5 +133,993 @@ impl AssetConfig {
         2 + // volatility_threshold_bps
         2 + // current_weight_bps
         32 + // oracle_source
+        8 + // max_price_age
+        2 + // max_confidence_bps
+        2 + // haircut_bps
+        1 + // decimals
+        8 + // balance
+        8 + // position_balance
+        8 + // deployed
+        8 + // yield_basis
+        8 + // price_usd
+        8 + // value_usd
+        8 + // last_price_update
+        1; // bump
+}
+
+impl AssetConfig {
+    /// Part of `balance` held in the reserve's token account rather than in strategies
+    pub fn idle_balance(&self) -> u64 {
+        self.balance.saturating_sub(self.deployed)
+    }
+    
+    /// Part of `balance` the protocol owns, leaving out what depositor
+    /// positions hold in kind
+    pub fn protocol_balance(&self) -> u64 {
+        self.balance.saturating_sub(self.position_balance)
+    }
+    
+    /// Idle part of the protocol-owned balance
+    pub fn protocol_idle_balance(&self) -> u64 {
+        self.idle_balance().saturating_sub(self.position_balance)
+    }
+    
+    /// Keep the yield basis within what the protocol still holds. Called wherever
+    /// the protocol-owned balance falls, so tokens bought back before the next
+    /// harvest are not counted as held since `last_rate`.
+    pub fn clamp_yield_basis(&mut self) {
+        self.yield_basis = self.yield_basis.min(self.protocol_balance());
+    }
+    
+    pub fn apply(&mut self, params: &AssetParams) {
+        self.target_weight_bps = params.target_weight_bps;
+        self.min_weight_bps = params.min_weight_bps;
+        self.max_weight_bps = params.max_weight_bps;
+        self.volatility_threshold_bps = params.volatility_threshold_bps;
+        self.oracle_source = params.oracle_source;
+        self.max_price_age = params.max_price_age;
+        self.max_confidence_bps = params.max_confidence_bps;
+        self.haircut_bps = params.haircut_bps;
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct AssetParams {
+    pub target_weight_bps: u16,
+    pub min_weight_bps: u16,
+    pub max_weight_bps: u16,
+    pub volatility_threshold_bps: u16,
+    pub oracle_source: Pubkey,
+    pub max_price_age: i64,
+    pub max_confidence_bps: u16,
+    pub haircut_bps: u16,
+}
+
+impl AssetParams {
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.min_weight_bps <= self.target_weight_bps
+                && self.target_weight_bps <= self.max_weight_bps
+                && self.max_weight_bps <= 10000,
+            ErrorCode::InvalidWeights
+        );
+        require!(self.volatility_threshold_bps <= 10000, ErrorCode::InvalidThreshold);
+        require!(
+            self.max_price_age > 0 && self.max_confidence_bps <= 10000,
+            ErrorCode::InvalidOracle
+        );
+        require!(self.haircut_bps < 10000, ErrorCode::InvalidHaircut);
+        
+        Ok(())
+    }
+}
+
+/// A depositor's claim on one reserve asset, in the asset's base units
+#[account]
+pub struct DepositorPosition {
+    pub owner: Pubkey,
+    pub asset_config: Pubkey,
+    pub amount: u64,
+    pub total_deposited: u64,
+    pub total_withdrawn: u64,
+    pub last_update: i64,
+    pub bump: u8,
+}
+
+impl DepositorPosition {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // owner
+        32 + // asset_config
+        8 + // amount
+        8 + // total_deposited
+        8 + // total_withdrawn
+        8 + // last_update
+        1; // bump
+}
+
+/// Per-epoch outflow cap and the FIFO of withdrawals waiting for capacity
+#[account]
+pub struct WithdrawalQueue {
+    pub reserve_vault: Pubkey,
+    pub outflow_cap_bps: u16,
+    /// Smallest USD value a queued request may hold
+    pub min_request_usd: u64,
+    pub epoch: u64,
+    pub epoch_cap_usd: u64,
+    pub epoch_outflow_usd: u64,
+    pub next_request_id: u64,
+    pub pending: Vec<PendingWithdrawal>,
+    pub bump: u8,
+}
+
+impl WithdrawalQueue {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // reserve_vault
+        2 + // outflow_cap_bps
+        8 + // min_request_usd
+        8 + // epoch
+        8 + // epoch_cap_usd
+        8 + // epoch_outflow_usd
+        8 + // next_request_id
+        4 + PendingWithdrawal::LEN * MAX_QUEUED_WITHDRAWALS + // pending
+        1; // bump
+    
+    /// Move to `current_epoch`, sizing its cap off the reserve value at the first outflow
+    pub fn roll_epoch(&mut self, current_epoch: u64, total_value_usd: u64) -> Result<()> {
+        if self.epoch != current_epoch || self.epoch_outflow_usd == 0 {
+            self.epoch = current_epoch;
+            self.epoch_outflow_usd = 0;
+            self.epoch_cap_usd = self.cap_for(total_value_usd)?;
+        }
+        
+        Ok(())
+    }
+    
+    /// Epoch outflow cap for a reserve worth `total_value_usd`
+    pub fn cap_for(&self, total_value_usd: u64) -> Result<u64> {
+        Ok((total_value_usd as u128)
+            .checked_mul(self.outflow_cap_bps as u128)
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            .checked_div(10000)
+            .ok_or(ErrorCode::ArithmeticOverflow)? as u64)
+    }
+    
+    /// Outflow the current epoch can still pay out directly for `asset_config`.
+    /// Nothing is left while requests for the asset wait, so they are paid first.
+    pub fn remaining_usd(&self, asset_config: &Pubkey) -> u64 {
+        if self.has_pending(asset_config) {
+            return 0;
+        }
+        
+        self.epoch_cap_usd.saturating_sub(self.epoch_outflow_usd)
+    }
+    
+    pub fn has_pending(&self, asset_config: &Pubkey) -> bool {
+        self.pending.iter().any(|p| p.asset_config == *asset_config)
+    }
+    
+    pub fn pending_for_owner(&self, owner: &Pubkey) -> usize {
+        self.pending.iter().filter(|p| p.owner == *owner).count()
+    }
+    
+    pub fn record_outflow(&mut self, value_usd: u64) -> Result<()> {
+        let epoch_outflow_usd = self.epoch_outflow_usd
+            .checked_add(value_usd)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        require!(
+            epoch_outflow_usd <= self.epoch_cap_usd,
+            ErrorCode::OutflowCapExceeded
+        );
+        
+        self.epoch_outflow_usd = epoch_outflow_usd;
+        
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct PendingWithdrawal {
+    pub request: Pubkey,
+    pub owner: Pubkey,
+    pub asset_config: Pubkey,
+}
+
+impl PendingWithdrawal {
+    pub const LEN: usize = 32 + // request
+        32 + // owner
+        32; // asset_config
+}
+
+#[account]
+pub struct WithdrawalRequest {
+    pub reserve_vault: Pubkey,
+    pub owner: Pubkey,
+    pub asset_config: Pubkey,
+    pub position: Pubkey,
+    pub id: u64,
+    pub amount: u64,
+    pub requested_epoch: u64,
+    pub requested_at: i64,
+    pub bump: u8,
+}
+
+impl WithdrawalRequest {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // reserve_vault
+        32 + // owner
+        32 + // asset_config
+        32 + // position
+        8 + // id
+        8 + // amount
+        8 + // requested_epoch
+        8 + // requested_at
+        1; // bump
+}
+
+/// Swap adapter and per-leg limits used by `rebalance`
+#[account]
+pub struct RebalanceConfig {
+    pub reserve_vault: Pubkey,
+    pub swap_adapter: Pubkey,
+    pub max_slippage_bps: u16,
+    pub max_trade_bps: u16,
+    pub bump: u8,
+}
+
+impl RebalanceConfig {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // reserve_vault
+        32 + // swap_adapter
+        2 + // max_slippage_bps
+        2 + // max_trade_bps
+        1; // bump
+    
+    pub fn set(
+        &mut self,
+        swap_adapter: Pubkey,
+        max_slippage_bps: u16,
+        max_trade_bps: u16,
+    ) -> Result<()> {
+        require!(max_slippage_bps <= 10000, ErrorCode::InvalidSlippage);
+        require!(
+            max_trade_bps > 0 && max_trade_bps <= 10000,
+            ErrorCode::InvalidThreshold
+        );
+        
+        self.swap_adapter = swap_adapter;
+        self.max_slippage_bps = max_slippage_bps;
+        self.max_trade_bps = max_trade_bps;
+        
+        Ok(())
+    }
+}
+
+/// Redemption fee and per-epoch cap, with usage tracked per ars-token epoch
+#[account]
+pub struct RedemptionConfig {
+    pub reserve_vault: Pubkey,
+    pub fee_bps: u16,
+    pub epoch_cap_bps: u16,
+    pub epoch: u64,
+    pub epoch_supply: u64,
+    pub epoch_redeemed: u64,
+    pub total_redeemed: u64,
+    pub total_fees_usd: u64,
+    pub bump: u8,
+}
+
+impl RedemptionConfig {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // reserve_vault
+        2 + // fee_bps
+        2 + // epoch_cap_bps
+        8 + // epoch
+        8 + // epoch_supply
+        8 + // epoch_redeemed
+        8 + // total_redeemed
+        8 + // total_fees_usd
+        1; // bump
+    
+    pub fn set(&mut self, fee_bps: u16, epoch_cap_bps: u16) -> Result<()> {
+        require!(fee_bps < 10000, ErrorCode::InvalidFee);
+        require!(epoch_cap_bps <= 10000, ErrorCode::InvalidThreshold);
+        
+        self.fee_bps = fee_bps;
+        self.epoch_cap_bps = epoch_cap_bps;
+        
+        Ok(())
+    }
+}
+
+/// Peg stability module terms for one approved stable asset
+#[account]
+pub struct PsmConfig {
+    pub reserve_vault: Pubkey,
+    pub asset_config: Pubkey,
+    pub mint_fee_bps: u16,
+    pub redeem_fee_bps: u16,
+    pub debt_ceiling: u64,
+    pub debt: u64,
+    pub bump: u8,
+}
+
+impl PsmConfig {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // reserve_vault
+        32 + // asset_config
+        2 + // mint_fee_bps
+        2 + // redeem_fee_bps
+        8 + // debt_ceiling
+        8 + // debt
+        1; // bump
+    
+    pub fn apply(&mut self, params: &PsmParams) {
+        self.mint_fee_bps = params.mint_fee_bps;
+        self.redeem_fee_bps = params.redeem_fee_bps;
+        self.debt_ceiling = params.debt_ceiling;
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct PsmParams {
+    pub mint_fee_bps: u16,
+    pub redeem_fee_bps: u16,
+    pub debt_ceiling: u64,
+}
+
+impl PsmParams {
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.mint_fee_bps < 10000 && self.redeem_fee_bps < 10000,
+            ErrorCode::InvalidFee
+        );
+        
+        Ok(())
+    }
+}
+
+/// Flash loan terms and exposure limit for one reserve asset
+#[account]
+pub struct FlashLoanConfig {
+    pub reserve_vault: Pubkey,
+    pub asset_config: Pubkey,
+    pub fee_bps: u16,
+    /// Largest loan as a share of the asset's reserve balance
+    pub max_loan_bps: u16,
+    /// Amount lent by a flash loan awaiting repayment in the current transaction
+    pub outstanding: u64,
+    pub total_borrowed: u64,
+    pub total_fees: u64,
+    pub bump: u8,
+}
+
+impl FlashLoanConfig {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // reserve_vault
+        32 + // asset_config
+        2 + // fee_bps
+        2 + // max_loan_bps
+        8 + // outstanding
+        8 + // total_borrowed
+        8 + // total_fees
+        1; // bump
+    
+    pub fn apply(&mut self, params: &FlashLoanParams) {
+        self.fee_bps = params.fee_bps;
+        self.max_loan_bps = params.max_loan_bps;
+    }
+    
+    /// Fee owed on `amount`, rounded up so no loan is free
+    pub fn fee(&self, amount: u64) -> Option<u64> {
+        (amount as u128)
+            .checked_mul(self.fee_bps as u128)?
+            .checked_add(9999)?
+            .checked_div(10000)?
+            .try_into()
+            .ok()
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct FlashLoanParams {
+    pub fee_bps: u16,
+    pub max_loan_bps: u16,
+}
+
+impl FlashLoanParams {
+    pub fn validate(&self) -> Result<()> {
+        require!(self.fee_bps < 10000, ErrorCode::InvalidFee);
+        require!(self.max_loan_bps <= 10000, ErrorCode::InvalidThreshold);
+        
+        Ok(())
+    }
+}
+
+/// Where flow fees are paid
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FeeDestination {
+    Treasury,
+    InsuranceFund,
+}
+
+/// Entry and exit fees for one reserve asset, banded by its weight after the flow
+#[account]
+pub struct FlowFeeSchedule {
+    pub reserve_vault: Pubkey,
+    pub asset_config: Pubkey,
+    pub destination: FeeDestination,
+    pub fee_recipient: Pubkey,
+    pub deposit_fee_bps: u16,
+    pub withdraw_fee_bps: u16,
+    /// Added when the flow leaves the asset outside its weight band on the side it pushed toward
+    pub surcharge_bps: u16,
+    /// Taken off when the flow leaves the asset outside its band but pulled it back toward target
+    pub discount_bps: u16,
+    pub bump: u8,
+}
+
+impl FlowFeeSchedule {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // reserve_vault
+        32 + // asset_config
+        1 + // destination
+        32 + // fee_recipient
+        2 + // deposit_fee_bps
+        2 + // withdraw_fee_bps
+        2 + // surcharge_bps
+        2 + // discount_bps
+        1; // bump
+    
+    pub fn apply(&mut self, params: &FlowFeeParams) {
+        self.deposit_fee_bps = params.deposit_fee_bps;
+        self.withdraw_fee_bps = params.withdraw_fee_bps;
+        self.surcharge_bps = params.surcharge_bps;
+        self.discount_bps = params.discount_bps;
+    }
+    
+    /// Fee rate for a deposit or withdrawal leaving the asset at `weight_bps`.
+    ///
+    /// Deposits into an overweight asset and withdrawals from an underweight one
+    /// pay the surcharge; the opposite flows get the discount.
+    pub fn fee_bps(&self, asset_config: &AssetConfig, weight_bps: u16, deposit: bool) -> u16 {
+        let base = if deposit { self.deposit_fee_bps } else { self.withdraw_fee_bps };
+        let overweight = weight_bps > asset_config.max_weight_bps;
+        let underweight = weight_bps < asset_config.min_weight_bps;
+        
+        if (deposit && overweight) || (!deposit && underweight) {
+            base.saturating_add(self.surcharge_bps)
+        } else if overweight || underweight {
+            base.saturating_sub(self.discount_bps)
+        } else {
+            base
+        }
+    }
+    
+    /// Fee owed on `amount` at `fee_bps`, rounded up so splitting a flow saves nothing
+    pub fn fee(amount: u64, fee_bps: u16) -> Option<u64> {
+        (amount as u128)
+            .checked_mul(fee_bps as u128)?
+            .checked_add(9999)?
+            .checked_div(10000)?
+            .try_into()
+            .ok()
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct FlowFeeParams {
+    pub deposit_fee_bps: u16,
+    pub withdraw_fee_bps: u16,
+    pub surcharge_bps: u16,
+    pub discount_bps: u16,
+}
+
+impl FlowFeeParams {
+    pub fn validate(&self) -> Result<()> {
+        // The surcharged rate must stay below 100% so every flow moves something
+        require!(
+            (self.deposit_fee_bps as u32) + (self.surcharge_bps as u32) < 10000
+                && (self.withdraw_fee_bps as u32) + (self.surcharge_bps as u32) < 10000,
+            ErrorCode::InvalidFee
+        );
+        
+        Ok(())
+    }
+}
+
+/// Where harvested LST yield is paid
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum YieldBucket {
+    Treasury,
+    Savings,
+}
+
+/// Exchange-rate high-water mark and realized yield for one liquid-staking asset
+#[account]
+pub struct LstYieldTracker {
+    pub reserve_vault: Pubkey,
+    pub asset_config: Pubkey,
+    pub stake_pool: Pubkey,
+    pub yield_bucket: YieldBucket,
+    pub recipient: Pubkey,
+    pub last_rate: u64,
+    pub epoch: u64,
+    pub epoch_yield: u64,
+    pub accrued_yield: u64,
+    pub total_yield: u64,
+    pub last_harvest: i64,
+    pub bump: u8,
+}
+
+impl LstYieldTracker {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // reserve_vault
+        32 + // asset_config
+        32 + // stake_pool
+        1 + // yield_bucket
+        32 + // recipient
+        8 + // last_rate
+        8 + // epoch
+        8 + // epoch_yield
+        8 + // accrued_yield
+        8 + // total_yield
+        8 + // last_harvest
+        1; // bump
+    
+    /// Book `amount` against `current_epoch`, starting a fresh tally when the epoch changes
+    pub fn record_yield(&mut self, current_epoch: u64, amount: u64) -> Result<()> {
+        if self.epoch != current_epoch {
+            self.epoch = current_epoch;
+            self.epoch_yield = 0;
+        }
+        
+        self.epoch_yield = self.epoch_yield
+            .checked_add(amount)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        self.accrued_yield = self.accrued_yield
+            .checked_add(amount)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        self.total_yield = self.total_yield
+            .checked_add(amount)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        
+        Ok(())
+    }
+}
+
+/// Inflow channels of the insurance fund
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum InsuranceSource {
+    Slash,
+    RedemptionFee,
+    StabilityFee,
+}
+
+/// Backstop capital held in token accounts owned by this PDA.
+/// None of it is counted in the reserve's `total_value_usd` or VHR.
+#[account]
+pub struct InsuranceFund {
+    pub reserve_vault: Pubkey,
+    pub aru_account: Pubkey,
+    pub stability_fee_account: Pubkey,
+    pub redemption_fee_share_bps: u16,
+    pub stability_fee_share_bps: u16,
+    pub stability_fee_checkpoint: u64,
+    pub slash_inflows_usd: u64,
+    pub redemption_fee_inflows_usd: u64,
+    pub stability_fee_inflows_usd: u64,
+    pub total_covered_usd: u64,
+    pub value_usd: u64,
+    pub last_report: i64,
+    pub bump: u8,
+}
+
+impl InsuranceFund {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // reserve_vault
+        32 + // aru_account
+        32 + // stability_fee_account
+        2 + // redemption_fee_share_bps
+        2 + // stability_fee_share_bps
+        8 + // stability_fee_checkpoint
+        8 + // slash_inflows_usd
+        8 + // redemption_fee_inflows_usd
+        8 + // stability_fee_inflows_usd
+        8 + // total_covered_usd
+        8 + // value_usd
+        8 + // last_report
         1; // bump
+    
+    pub fn set(&mut self, redemption_fee_share_bps: u16, stability_fee_share_bps: u16) -> Result<()> {
+        require!(
+            redemption_fee_share_bps <= 10000 && stability_fee_share_bps <= 10000,
+            ErrorCode::InvalidFee
+        );
+        
+        self.redemption_fee_share_bps = redemption_fee_share_bps;
+        self.stability_fee_share_bps = stability_fee_share_bps;
+        
+        Ok(())
+    }
+    
+    pub fn record_inflow(&mut self, source: InsuranceSource, value_usd: u64) -> Result<()> {
+        let inflows = match source {
+            InsuranceSource::Slash => &mut self.slash_inflows_usd,
+            InsuranceSource::RedemptionFee => &mut self.redemption_fee_inflows_usd,
+            InsuranceSource::StabilityFee => &mut self.stability_fee_inflows_usd,
+        };
+        *inflows = inflows
+            .checked_add(value_usd)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        
+        Ok(())
+    }
+}
+
+/// Head of a vault's attestation hash chain
+#[account]
+pub struct AttestationLog {
+    pub reserve_vault: Pubkey,
+    pub latest_epoch: u64,
+    pub latest_hash: [u8; 32],
+    pub count: u64,
+    pub bump: u8,
+}
+
+impl AttestationLog {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // reserve_vault
+        8 + // latest_epoch
+        32 + // latest_hash
+        8 + // count
+        1; // bump
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct AttestedAsset {
+    pub mint: Pubkey,
+    pub token_account: Pubkey,
+    pub balance: u64,
+    pub price_usd: u64,
+    pub value_usd: u64,
+}
+
+impl AttestedAsset {
+    pub const LEN: usize = 32 + // mint
+        32 + // token_account
+        8 + // balance
+        8 + // price_usd
+        8; // value_usd
+}
+
+/// Proof-of-reserves snapshot for one ars-token epoch, chained to the previous one
+#[account]
+pub struct ReserveAttestation {
+    pub reserve_vault: Pubkey,
+    pub epoch: u64,
+    pub prev_hash: [u8; 32],
+    pub hash: [u8; 32],
+    pub assets: Vec<AttestedAsset>,
+    pub total_value_usd: u64,
+    pub liabilities_usd: u64,
+    pub aru_supply: u64,
+    pub vhr: u64,
+    pub slot: u64,
+    pub timestamp: i64,
+    pub bump: u8,
+}
+
+impl ReserveAttestation {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // reserve_vault
+        8 + // epoch
+        32 + // prev_hash
+        32 + // hash
+        4 + AttestedAsset::LEN * MAX_ASSETS + // assets
+        8 + // total_value_usd
+        8 + // liabilities_usd
+        8 + // aru_supply
+        8 + // vhr
+        8 + // slot
+        8 + // timestamp
+        1; // bump
+    
+    /// SHA-256 of `prev_hash` followed by every attested field in declaration order,
+    /// integers little-endian and assets without a length prefix
+    pub fn compute_hash(&self) -> [u8; 32] {
+        let mut data = Vec::with_capacity(Self::LEN);
+        data.extend_from_slice(&self.prev_hash);
+        data.extend_from_slice(self.reserve_vault.as_ref());
+        data.extend_from_slice(&self.epoch.to_le_bytes());
+        for asset in &self.assets {
+            data.extend_from_slice(asset.mint.as_ref());
+            data.extend_from_slice(asset.token_account.as_ref());
+            data.extend_from_slice(&asset.balance.to_le_bytes());
+            data.extend_from_slice(&asset.price_usd.to_le_bytes());
+            data.extend_from_slice(&asset.value_usd.to_le_bytes());
+        }
+        data.extend_from_slice(&self.total_value_usd.to_le_bytes());
+        data.extend_from_slice(&self.liabilities_usd.to_le_bytes());
+        data.extend_from_slice(&self.aru_supply.to_le_bytes());
+        data.extend_from_slice(&self.vhr.to_le_bytes());
+        data.extend_from_slice(&self.slot.to_le_bytes());
+        data.extend_from_slice(&self.timestamp.to_le_bytes());
+        
+        hash(&data).to_bytes()
+    }
+}
+
+/// Reserve asset balance and oracle price frozen at global settlement
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct SettledAsset {
+    pub asset_config: Pubkey,
+    pub mint: Pubkey,
+    pub price_usd: u64,
+    pub balance: u64,
+    pub claimed: u64,
+}
+
+impl SettledAsset {
+    pub const LEN: usize = 32 + // asset_config
+        32 + // mint
+        8 + // price_usd
+        8 + // balance
+        8; // claimed
+}
+
+/// Pro-rata claim book for ARU holders once the reserve is settled
+#[account]
+pub struct Settlement {
+    pub reserve_vault: Pubkey,
+    pub aru_supply: u64,
+    pub claimed_aru: u64,
+    pub total_value_usd: u64,
+    pub started_at: i64,
+    pub assets: Vec<SettledAsset>,
+    pub bump: u8,
+}
+
+impl Settlement {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // reserve_vault
+        8 + // aru_supply
+        8 + // claimed_aru
+        8 + // total_value_usd
+        8 + // started_at
+        4 + SettledAsset::LEN * MAX_ASSETS + // assets
+        1; // bump
+    
+    /// Share of a snapshotted asset balance owed for `aru_amount` of settled supply
+    pub fn payout(&self, asset: &SettledAsset, aru_amount: u64) -> Option<u64> {
+        (asset.balance as u128)
+            .checked_mul(aru_amount as u128)?
+            .checked_div(self.aru_supply as u128)?
+            .try_into()
+            .ok()
+    }
+}
+
+/// Dutch auction selling ARU-denominated recapitalization bonds for one stable asset
+#[account]
+pub struct RecapAuction {
+    pub reserve_vault: Pubkey,
+    /// Stable collateral bids are paid in
+    pub asset_config: Pubkey,
+    /// Seconds for the discount to fall from `start_discount_bps` to `max_discount_bps`
+    pub duration: i64,
+    pub start_discount_bps: u16,
+    pub max_discount_bps: u16,
+    /// Seconds from purchase until a bond can be redeemed
+    pub bond_term: i64,
+    /// Face value in ARU that a single round may sell
+    pub max_face_per_round: u64,
+    pub active: bool,
+    pub round: u64,
+    pub started_at: i64,
+    pub round_face_sold: u64,
+    pub total_face_sold: u64,
+    pub total_raised_usd: u64,
+    /// Face value of bonds not yet redeemed
+    pub outstanding_face: u64,
+    pub bonds_issued: u64,
+    pub bump: u8,
+}
+
+impl RecapAuction {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // reserve_vault
+        32 + // asset_config
+        8 + // duration
+        2 + // start_discount_bps
+        2 + // max_discount_bps
+        8 + // bond_term
+        8 + // max_face_per_round
+        1 + // active
+        8 + // round
+        8 + // started_at
+        8 + // round_face_sold
+        8 + // total_face_sold
+        8 + // total_raised_usd
+        8 + // outstanding_face
+        8 + // bonds_issued
+        1; // bump
+    
+    pub fn apply(&mut self, params: &RecapAuctionParams) {
+        self.duration = params.duration;
+        self.start_discount_bps = params.start_discount_bps;
+        self.max_discount_bps = params.max_discount_bps;
+        self.bond_term = params.bond_term;
+        self.max_face_per_round = params.max_face_per_round;
+    }
+    
+    /// Discount on face value at `now`, falling linearly to `max_discount_bps`
+    pub fn discount_bps(&self, now: i64) -> u16 {
+        let elapsed = now.saturating_sub(self.started_at).clamp(0, self.duration);
+        let range = i64::from(self.max_discount_bps - self.start_discount_bps);
+        
+        self.start_discount_bps + (range * elapsed / self.duration) as u16
+    }
+    
+    pub fn expired(&self, now: i64) -> bool {
+        now >= self.started_at.saturating_add(self.duration)
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct RecapAuctionParams {
+    pub duration: i64,
+    pub start_discount_bps: u16,
+    pub max_discount_bps: u16,
+    pub bond_term: i64,
+    pub max_face_per_round: u64,
+}
+
+impl RecapAuctionParams {
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.duration > 0 && self.bond_term >= 0 && self.max_face_per_round > 0,
+            ErrorCode::InvalidAmount
+        );
+        require!(
+            self.start_discount_bps <= self.max_discount_bps && self.max_discount_bps < 10000,
+            ErrorCode::InvalidFee
+        );
+        
+        Ok(())
+    }
+}
+
+/// Claim on `face_aru` newly minted ARU, junior to existing holders
+#[account]
+pub struct RecapBond {
+    pub auction: Pubkey,
+    pub owner: Pubkey,
+    pub id: u64,
+    pub round: u64,
+    pub face_aru: u64,
+    pub paid_usd: u64,
+    pub matures_at: i64,
+    pub bump: u8,
+}
+
+impl RecapBond {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // auction
+        32 + // owner
+        8 + // id
+        8 + // round
+        8 + // face_aru
+        8 + // paid_usd
+        8 + // matures_at
+        1; // bump
+}
+
+/// Most adapter accounts a strategy can register
+pub const MAX_STRATEGY_ACCOUNTS: usize = 8;
+
+/// Governance-capped deployment of one reserve asset into an external yield venue
+#[account]
+pub struct StrategyAllocation {
+    pub reserve_vault: Pubkey,
+    pub asset_config: Pubkey,
+    pub adapter_program: Pubkey,
+    /// Venue accounts passed after the interface accounts, fixed by governance
+    pub adapter_accounts: Vec<Pubkey>,
+    /// Largest deployment as a share of the asset's reserve balance
+    pub max_allocation_bps: u16,
+    /// Underlying base units held by the strategy, at the last deposit, withdrawal or report
+    pub deployed: u64,
+    pub total_gains: u64,
+    pub total_losses: u64,
+    pub last_report: i64,
+    /// Set by an emergency unwind; deployments stay blocked until governance clears it
+    pub unwound: bool,
+    pub bump: u8,
+}
+
+impl StrategyAllocation {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // reserve_vault
+        32 + // asset_config
+        32 + // adapter_program
+        4 + 32 * MAX_STRATEGY_ACCOUNTS + // adapter_accounts
+        2 + // max_allocation_bps
+        8 + // deployed
+        8 + // total_gains
+        8 + // total_losses
+        8 + // last_report
+        1 + // unwound
+        1; // bump
+    
+    /// Move `deployed` to the venue's `value`, returning (gain, loss)
+    pub fn mark_to(&mut self, value: u64) -> Result<(u64, u64)> {
+        let gain = value.saturating_sub(self.deployed);
+        let loss = self.deployed.saturating_sub(value);
+        
+        self.total_gains = self.total_gains
+            .checked_add(gain)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        self.total_losses = self.total_losses
+            .checked_add(loss)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        self.deployed = value;
+        
+        Ok((gain, loss))
+    }
+}
+
+/// Crank-updated NAV of the reserve, mirrored into a Pyth-layout price account
+#[account]
+pub struct NavSnapshot {
+    pub reserve_vault: Pubkey,
+    pub price_feed: Pubkey,
+    pub total_value_usd: u64,
+    pub liabilities_usd: u64,
+    pub aru_supply: u64,
+    /// USD value backing one whole ARU, USD_DECIMALS fixed point
+    pub nav_per_aru: u64,
+    pub vhr: u64,
+    pub slot: u64,
+    pub timestamp: i64,
+    pub bump: u8,
+    pub price_feed_bump: u8,
+}
+
+impl NavSnapshot {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // reserve_vault
+        32 + // price_feed
+        8 + // total_value_usd
+        8 + // liabilities_usd
+        8 + // aru_supply
+        8 + // nav_per_aru
+        8 + // vhr
+        8 + // slot
+        8 + // timestamp
+        1 + // bump
+        1; // price_feed_bump
+    
+    pub fn quote(&self) -> NavQuote {
+        NavQuote {
+            total_value_usd: self.total_value_usd,
+            liabilities_usd: self.liabilities_usd,
+            aru_supply: self.aru_supply,
+            nav_per_aru: self.nav_per_aru,
+            vhr: self.vhr,
+            slot: self.slot,
+            timestamp: self.timestamp,
+        }
+    }
+}
+
+/// `get_nav` return data
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct NavQuote {
+    pub total_value_usd: u64,
+    pub liabilities_usd: u64,
+    pub aru_supply: u64,
+    pub nav_per_aru: u64,
+    pub vhr: u64,
+    pub slot: u64,
+    pub timestamp: i64,
 }
@@ -0,0 +1,139 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::{get_return_data, invoke_signed};
+
+use crate::errors::ErrorCode;
+
+/// Anchor discriminator of `deposit(amount: u64)`
+pub const DEPOSIT_DISCRIMINATOR: [u8; 8] = [242, 35, 198, 137, 82, 225, 242, 182];
+
+/// Anchor discriminator of `withdraw(amount: u64)`
+pub const WITHDRAW_DISCRIMINATOR: [u8; 8] = [183, 18, 70, 156, 148, 109, 161, 34];
+
+/// Anchor discriminator of `report_value() -> u64`
+pub const REPORT_VALUE_DISCRIMINATOR: [u8; 8] = [174, 151, 171, 75, 154, 188, 28, 230];
+
+/// Passed to `withdraw` to unwind the whole position
+pub const WITHDRAW_ALL: u64 = u64::MAX;
+
+/// Move `amount` from `source` into the strategy.
+///
+/// Adapters take `[authority, source, token_program]` followed by their own
+/// accounts (market, reserve, obligation, ...).
+pub fn deposit<'info>(
+    adapter_program: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    source: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    adapter_accounts: &[AccountInfo<'info>],
+    amount: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    invoke(
+        adapter_program,
+        vec![
+            AccountMeta::new_readonly(authority.key(), true),
+            AccountMeta::new(source.key(), false),
+            AccountMeta::new_readonly(token_program.key(), false),
+        ],
+        vec![authority.clone(), source.clone(), token_program.clone()],
+        adapter_accounts,
+        &instruction_data(DEPOSIT_DISCRIMINATOR, Some(amount)),
+        signer_seeds,
+    )
+}
+
+/// Return up to `amount` of the underlying asset to `destination`.
+///
+/// Adapters take `[authority, destination, token_program]` followed by their
+/// own accounts, and treat `WITHDRAW_ALL` as the full position.
+pub fn withdraw<'info>(
+    adapter_program: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    destination: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    adapter_accounts: &[AccountInfo<'info>],
+    amount: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    invoke(
+        adapter_program,
+        vec![
+            AccountMeta::new_readonly(authority.key(), true),
+            AccountMeta::new(destination.key(), false),
+            AccountMeta::new_readonly(token_program.key(), false),
+        ],
+        vec![authority.clone(), destination.clone(), token_program.clone()],
+        adapter_accounts,
+        &instruction_data(WITHDRAW_DISCRIMINATOR, Some(amount)),
+        signer_seeds,
+    )
+}
+
+/// Value of the position in underlying base units, returned by the adapter
+/// through return data.
+///
+/// Adapters take `[authority]` followed by their own accounts.
+pub fn report_value<'info>(
+    adapter_program: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    adapter_accounts: &[AccountInfo<'info>],
+    signer_seeds: &[&[&[u8]]],
+) -> Result<u64> {
+    invoke(
+        adapter_program,
+        vec![AccountMeta::new_readonly(authority.key(), true)],
+        vec![authority.clone()],
+        adapter_accounts,
+        &instruction_data(REPORT_VALUE_DISCRIMINATOR, None),
+        signer_seeds,
+    )?;
+
+    let (program_id, data) = get_return_data().ok_or(ErrorCode::InvalidStrategyReport)?;
+    require_keys_eq!(program_id, adapter_program.key(), ErrorCode::InvalidStrategyReport);
+
+    data.get(..8)
+        .and_then(|bytes| bytes.try_into().ok())
+        .map(u64::from_le_bytes)
+        .ok_or(error!(ErrorCode::InvalidStrategyReport))
+}
+
+fn instruction_data(discriminator: [u8; 8], amount: Option<u64>) -> Vec<u8> {
+    let mut data = Vec::with_capacity(16);
+    data.extend_from_slice(&discriminator);
+    if let Some(amount) = amount {
+        data.extend_from_slice(&amount.to_le_bytes());
+    }
+    data
+}
+
+/// Invoke the adapter with the interface accounts followed by its own accounts
+fn invoke<'info>(
+    adapter_program: &AccountInfo<'info>,
+    mut accounts: Vec<AccountMeta>,
+    mut account_infos: Vec<AccountInfo<'info>>,
+    adapter_accounts: &[AccountInfo<'info>],
+    data: &[u8],
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    accounts.extend(adapter_accounts.iter().map(|account| {
+        if account.is_writable {
+            AccountMeta::new(account.key(), account.is_signer)
+        } else {
+            AccountMeta::new_readonly(account.key(), account.is_signer)
+        }
+    }));
+
+    let instruction = Instruction {
+        program_id: adapter_program.key(),
+        accounts,
+        data: data.to_vec(),
+    };
+
+    account_infos.extend(adapter_accounts.iter().cloned());
+    account_infos.push(adapter_program.clone());
+
+    invoke_signed(&instruction, &account_infos, signer_seeds)?;
+
+    Ok(())
+}
@@ -0,0 +1,62 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke_signed;
+
+/// Anchor discriminator of `swap(amount_in: u64, min_amount_out: u64)`
+pub const SWAP_DISCRIMINATOR: [u8; 8] = [248, 198, 158, 145, 225, 117, 135, 200];
+
+/// Invoke a swap adapter program.
+///
+/// Adapters take `[authority, source, destination, token_program]` followed by
+/// their own accounts (pool, route, Jupiter program, ...), passed through from
+/// the caller's remaining accounts.
+#[allow(clippy::too_many_arguments)]
+pub fn swap<'info>(
+    adapter_program: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    source: &AccountInfo<'info>,
+    destination: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    adapter_accounts: &[AccountInfo<'info>],
+    amount_in: u64,
+    min_amount_out: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(authority.key(), true),
+        AccountMeta::new(source.key(), false),
+        AccountMeta::new(destination.key(), false),
+        AccountMeta::new_readonly(token_program.key(), false),
+    ];
+    accounts.extend(adapter_accounts.iter().map(|account| {
+        if account.is_writable {
+            AccountMeta::new(account.key(), account.is_signer)
+        } else {
+            AccountMeta::new_readonly(account.key(), account.is_signer)
+        }
+    }));
+
+    let mut data = Vec::with_capacity(24);
+    data.extend_from_slice(&SWAP_DISCRIMINATOR);
+    data.extend_from_slice(&amount_in.to_le_bytes());
+    data.extend_from_slice(&min_amount_out.to_le_bytes());
+
+    let instruction = Instruction {
+        program_id: adapter_program.key(),
+        accounts,
+        data,
+    };
+
+    let mut account_infos = vec![
+        authority.clone(),
+        source.clone(),
+        destination.clone(),
+        token_program.clone(),
+    ];
+    account_infos.extend(adapter_accounts.iter().cloned());
+    account_infos.push(adapter_program.clone());
+
+    invoke_signed(&instruction, &account_infos, signer_seeds)?;
+
+    Ok(())
+}
@@ -22,4 +22,55 @@ pub enum ErrorCode {
     
     #[msg("Epoch duration not complete")]
     EpochNotComplete,
+    
+    #[msg("Unauthorized access")]
+    Unauthorized,
+    
+    #[msg("Invalid amount")]
+    InvalidAmount,
+    
+    #[msg("Invalid savings rate")]
+    InvalidSavingsRate,
+    
+    #[msg("Insufficient staked balance")]
+    InsufficientStake,
+    
+    #[msg("Invalid lock duration")]
+    InvalidLockDuration,
+    
+    #[msg("Lock has expired")]
+    LockExpired,
+    
+    #[msg("Lock has not expired")]
+    LockNotExpired,
+    
+    #[msg("An active lock already exists")]
+    LockAlreadyExists,
+    
+    #[msg("Invalid vesting schedule")]
+    InvalidVestingSchedule,
+    
+    #[msg("Vesting is not cancellable")]
+    VestingNotCancellable,
+    
+    #[msg("Vesting already cancelled")]
+    VestingCancelled,
+    
+    #[msg("Nothing to claim")]
+    NothingToClaim,
+    
+    #[msg("Invalid emission decay")]
+    InvalidEmissionDecay,
+    
+    #[msg("Rewards already emitted for this epoch")]
+    EmissionAlreadyDone,
+    
+    #[msg("Minter ceiling exceeded")]
+    MinterCeilingExceeded,
+    
+    #[msg("Burn exceeds ARU outstanding from this minter")]
+    MinterDebtExceeded,
+    
+    #[msg("Minting is frozen by global settlement")]
+    MintingFrozen,
 }
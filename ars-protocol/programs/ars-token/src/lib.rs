@@ -1,5 +1,5 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Mint, MintTo, Burn};
+use anchor_spl::token::{self, Token, TokenAccount, Mint, MintTo, Burn, Transfer};
 
 declare_id!("ARSM8uCNGUDYCVJPNnoKenBNTzKbJANyJS3KpbUVEmQb");
 
@@ -33,8 +33,10 @@ pub mod ars_token {
         mint_state.total_supply = 0;
         mint_state.epoch_minted = 0;
         mint_state.epoch_burned = 0;
+        mint_state.epoch_emitted = 0;
         mint_state.mint_cap_per_epoch_bps = mint_cap_per_epoch_bps;
         mint_state.burn_cap_per_epoch_bps = burn_cap_per_epoch_bps;
+        mint_state.minting_frozen = false;
         mint_state.bump = ctx.bumps.mint_state;
 
         Ok(())
@@ -45,6 +47,7 @@ pub mod ars_token {
         amount: u64,
     ) -> Result<()> {
         let mint_state = &mut ctx.accounts.mint_state;
+        require!(!mint_state.minting_frozen, ErrorCode::MintingFrozen);
         
         let mint_cap = mint_state.total_supply
             .checked_mul(mint_state.mint_cap_per_epoch_bps as u64)
@@ -105,8 +108,9 @@ pub mod ars_token {
             .checked_add(amount)
             .ok_or(ErrorCode::ArithmeticOverflow)?;
         
+        // Settlement claims burn ARU without the epoch throttle
         require!(
-            new_epoch_burned <= burn_cap,
+            mint_state.minting_frozen || new_epoch_burned <= burn_cap,
             ErrorCode::BurnCapExceeded
         );
         
@@ -155,6 +159,7 @@ pub mod ars_token {
             .checked_sub(mint_state.epoch_burned as i64)
             .ok_or(ErrorCode::ArithmeticOverflow)?;
         epoch_history.final_supply = mint_state.total_supply;
+        epoch_history.agent_rewards_emitted = mint_state.epoch_emitted;
         
         mint_state.current_epoch = mint_state.current_epoch
             .checked_add(1)
@@ -162,62 +167,1211 @@ pub mod ars_token {
         mint_state.epoch_start = current_time;
         mint_state.epoch_minted = 0;
         mint_state.epoch_burned = 0;
+        mint_state.epoch_emitted = 0;
         
         Ok(())
     }
+
+    pub fn initialize_savings_vault(
+        ctx: Context<InitializeSavingsVault>,
+        savings_rate_bps: u16,
+    ) -> Result<()> {
+        require!(savings_rate_bps <= 10000, ErrorCode::InvalidSavingsRate);
+        
+        let savings_vault = &mut ctx.accounts.savings_vault;
+        savings_vault.mint_state = ctx.accounts.mint_state.key();
+        savings_vault.share_mint = ctx.accounts.share_mint.key();
+        savings_vault.aru_vault = ctx.accounts.aru_vault.key();
+        savings_vault.savings_rate_bps = savings_rate_bps;
+        savings_vault.exchange_rate = EXCHANGE_RATE_SCALE;
+        savings_vault.last_update = Clock::get()?.unix_timestamp;
+        savings_vault.total_shares = 0;
+        savings_vault.bump = ctx.bumps.savings_vault;
+        
+        Ok(())
+    }
+
+    pub fn deposit_savings(
+        ctx: Context<DepositSavings>,
+        amount: u64,
+    ) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidAmount);
+        
+        let savings_vault = &mut ctx.accounts.savings_vault;
+        savings_vault.accrue(Clock::get()?.unix_timestamp, ctx.accounts.aru_vault.amount)?;
+        
+        let shares = savings_vault.shares_for_amount(amount)?;
+        require!(shares > 0, ErrorCode::InvalidAmount);
+        
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.user_aru_account.to_account_info(),
+                    to: ctx.accounts.aru_vault.to_account_info(),
+                    authority: ctx.accounts.user.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+        
+        let vault_seeds = &[
+            b"savings_vault",
+            savings_vault.mint_state.as_ref(),
+            &[savings_vault.bump],
+        ];
+        let signer = &[&vault_seeds[..]];
+        
+        token::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                MintTo {
+                    mint: ctx.accounts.share_mint.to_account_info(),
+                    to: ctx.accounts.user_share_account.to_account_info(),
+                    authority: savings_vault.to_account_info(),
+                },
+                signer,
+            ),
+            shares,
+        )?;
+        
+        savings_vault.total_shares = savings_vault.total_shares
+            .checked_add(shares)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        
+        Ok(())
+    }
+
+    pub fn withdraw_savings(
+        ctx: Context<WithdrawSavings>,
+        shares: u64,
+    ) -> Result<()> {
+        require!(shares > 0, ErrorCode::InvalidAmount);
+        
+        let savings_vault = &mut ctx.accounts.savings_vault;
+        savings_vault.accrue(Clock::get()?.unix_timestamp, ctx.accounts.aru_vault.amount)?;
+        
+        let amount = savings_vault.amount_for_shares(shares)?;
+        
+        token::burn(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Burn {
+                    mint: ctx.accounts.share_mint.to_account_info(),
+                    from: ctx.accounts.user_share_account.to_account_info(),
+                    authority: ctx.accounts.user.to_account_info(),
+                },
+            ),
+            shares,
+        )?;
+        
+        let vault_seeds = &[
+            b"savings_vault",
+            savings_vault.mint_state.as_ref(),
+            &[savings_vault.bump],
+        ];
+        let signer = &[&vault_seeds[..]];
+        
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.aru_vault.to_account_info(),
+                    to: ctx.accounts.user_aru_account.to_account_info(),
+                    authority: savings_vault.to_account_info(),
+                },
+                signer,
+            ),
+            amount,
+        )?;
+        
+        savings_vault.total_shares = savings_vault.total_shares
+            .checked_sub(shares)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        
+        Ok(())
+    }
+
+    pub fn update_savings_rate(
+        ctx: Context<UpdateSavingsRate>,
+        savings_rate_bps: u16,
+    ) -> Result<()> {
+        require!(savings_rate_bps <= 10000, ErrorCode::InvalidSavingsRate);
+        
+        let savings_vault = &mut ctx.accounts.savings_vault;
+        
+        // Settle growth at the old rate before switching
+        savings_vault.accrue(Clock::get()?.unix_timestamp, ctx.accounts.aru_vault.amount)?;
+        savings_vault.savings_rate_bps = savings_rate_bps;
+        
+        Ok(())
+    }
+
+    pub fn initialize_stake_vault(
+        _ctx: Context<InitializeStakeVault>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn initialize_checkpoints(
+        ctx: Context<InitializeCheckpoints>,
+    ) -> Result<()> {
+        let checkpoints = &mut ctx.accounts.checkpoints;
+        checkpoints.owner = ctx.accounts.owner.key();
+        checkpoints.mint_state = ctx.accounts.mint_state.key();
+        checkpoints.aru_mint = ctx.accounts.mint_state.aru_mint;
+        checkpoints.checkpoints = Vec::new();
+        checkpoints.bump = ctx.bumps.checkpoints;
+        
+        Ok(())
+    }
+
+    pub fn stake(
+        ctx: Context<Stake>,
+        amount: u64,
+    ) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidAmount);
+        
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.owner_aru_account.to_account_info(),
+                    to: ctx.accounts.stake_vault.to_account_info(),
+                    authority: ctx.accounts.owner.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+        
+        let checkpoints = &mut ctx.accounts.checkpoints;
+        let new_balance = checkpoints.latest_balance()
+            .checked_add(amount)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        checkpoints.record(Clock::get()?.slot, new_balance);
+        
+        Ok(())
+    }
+
+    pub fn unstake(
+        ctx: Context<Unstake>,
+        amount: u64,
+    ) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidAmount);
+        
+        let checkpoints = &mut ctx.accounts.checkpoints;
+        let new_balance = checkpoints.latest_balance()
+            .checked_sub(amount)
+            .ok_or(ErrorCode::InsufficientStake)?;
+        checkpoints.record(Clock::get()?.slot, new_balance);
+        
+        let mint_state = &ctx.accounts.mint_state;
+        let mint_seeds = &[
+            b"mint_state",
+            mint_state.authority.as_ref(),
+            &[mint_state.bump],
+        ];
+        let signer = &[&mint_seeds[..]];
+        
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.stake_vault.to_account_info(),
+                    to: ctx.accounts.owner_aru_account.to_account_info(),
+                    authority: mint_state.to_account_info(),
+                },
+                signer,
+            ),
+            amount,
+        )?;
+        
+        Ok(())
+    }
+
+    pub fn create_lock(
+        ctx: Context<CreateLock>,
+        amount: u64,
+        unlock_time: i64,
+    ) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidAmount);
+        
+        let clock = Clock::get()?;
+        let max_unlock = clock.unix_timestamp
+            .checked_add(MAX_LOCK_DURATION)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        require!(
+            unlock_time > clock.unix_timestamp && unlock_time <= max_unlock,
+            ErrorCode::InvalidLockDuration
+        );
+        require!(ctx.accounts.vote_escrow.amount == 0, ErrorCode::LockAlreadyExists);
+        
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.owner_aru_account.to_account_info(),
+                    to: ctx.accounts.stake_vault.to_account_info(),
+                    authority: ctx.accounts.owner.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+        
+        // A withdrawn lock keeps its account so its checkpoint history survives
+        let vote_escrow = &mut ctx.accounts.vote_escrow;
+        vote_escrow.owner = ctx.accounts.owner.key();
+        vote_escrow.mint_state = ctx.accounts.mint_state.key();
+        vote_escrow.aru_mint = ctx.accounts.mint_state.aru_mint;
+        vote_escrow.amount = amount;
+        vote_escrow.unlock_time = unlock_time;
+        vote_escrow.bump = ctx.bumps.vote_escrow;
+        vote_escrow.record(clock.slot);
+        
+        Ok(())
+    }
+
+    pub fn increase_lock_amount(
+        ctx: Context<ModifyLock>,
+        amount: u64,
+    ) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidAmount);
+        
+        let clock = Clock::get()?;
+        let vote_escrow = &mut ctx.accounts.vote_escrow;
+        require!(
+            vote_escrow.unlock_time > clock.unix_timestamp,
+            ErrorCode::LockExpired
+        );
+        
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.owner_aru_account.to_account_info(),
+                    to: ctx.accounts.stake_vault.to_account_info(),
+                    authority: ctx.accounts.owner.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+        
+        vote_escrow.amount = vote_escrow.amount
+            .checked_add(amount)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        vote_escrow.record(clock.slot);
+        
+        Ok(())
+    }
+
+    pub fn extend_lock(
+        ctx: Context<ModifyLock>,
+        unlock_time: i64,
+    ) -> Result<()> {
+        let clock = Clock::get()?;
+        let vote_escrow = &mut ctx.accounts.vote_escrow;
+        
+        let max_unlock = clock.unix_timestamp
+            .checked_add(MAX_LOCK_DURATION)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        require!(
+            vote_escrow.unlock_time > clock.unix_timestamp,
+            ErrorCode::LockExpired
+        );
+        require!(
+            unlock_time > vote_escrow.unlock_time && unlock_time <= max_unlock,
+            ErrorCode::InvalidLockDuration
+        );
+        
+        vote_escrow.unlock_time = unlock_time;
+        vote_escrow.record(clock.slot);
+        
+        Ok(())
+    }
+
+    pub fn withdraw_lock(
+        ctx: Context<WithdrawLock>,
+    ) -> Result<()> {
+        let clock = Clock::get()?;
+        let vote_escrow = &ctx.accounts.vote_escrow;
+        require!(
+            clock.unix_timestamp >= vote_escrow.unlock_time,
+            ErrorCode::LockNotExpired
+        );
+        require!(vote_escrow.amount > 0, ErrorCode::InvalidAmount);
+        
+        let mint_state = &ctx.accounts.mint_state;
+        let mint_seeds = &[
+            b"mint_state",
+            mint_state.authority.as_ref(),
+            &[mint_state.bump],
+        ];
+        let signer = &[&mint_seeds[..]];
+        
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.stake_vault.to_account_info(),
+                    to: ctx.accounts.owner_aru_account.to_account_info(),
+                    authority: mint_state.to_account_info(),
+                },
+                signer,
+            ),
+            vote_escrow.amount,
+        )?;
+        
+        let vote_escrow = &mut ctx.accounts.vote_escrow;
+        vote_escrow.amount = 0;
+        vote_escrow.record(clock.slot);
+        
+        Ok(())
+    }
+
+    pub fn create_vesting(
+        ctx: Context<CreateVesting>,
+        total_amount: u64,
+        start_time: i64,
+        cliff_time: i64,
+        end_time: i64,
+        cancellable: bool,
+    ) -> Result<()> {
+        require!(total_amount > 0, ErrorCode::InvalidAmount);
+        require!(
+            start_time <= cliff_time && cliff_time <= end_time && start_time < end_time,
+            ErrorCode::InvalidVestingSchedule
+        );
+        
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.funding_account.to_account_info(),
+                    to: ctx.accounts.escrow.to_account_info(),
+                    authority: ctx.accounts.authority.to_account_info(),
+                },
+            ),
+            total_amount,
+        )?;
+        
+        let vesting = &mut ctx.accounts.vesting;
+        vesting.beneficiary = ctx.accounts.beneficiary.key();
+        vesting.mint_state = ctx.accounts.mint_state.key();
+        vesting.escrow = ctx.accounts.escrow.key();
+        vesting.total_amount = total_amount;
+        vesting.released_amount = 0;
+        vesting.start_time = start_time;
+        vesting.cliff_time = cliff_time;
+        vesting.end_time = end_time;
+        vesting.cancellable = cancellable;
+        vesting.cancelled = false;
+        vesting.bump = ctx.bumps.vesting;
+        
+        Ok(())
+    }
+
+    pub fn claim_vested(
+        ctx: Context<ClaimVested>,
+    ) -> Result<()> {
+        let vesting = &mut ctx.accounts.vesting;
+        let vested = vesting.vested_amount(Clock::get()?.unix_timestamp);
+        let claimable = vested
+            .checked_sub(vesting.released_amount)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        require!(claimable > 0, ErrorCode::NothingToClaim);
+        
+        let vesting_seeds = &[
+            b"vesting",
+            vesting.mint_state.as_ref(),
+            vesting.beneficiary.as_ref(),
+            &[vesting.bump],
+        ];
+        let signer = &[&vesting_seeds[..]];
+        
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.escrow.to_account_info(),
+                    to: ctx.accounts.beneficiary_token_account.to_account_info(),
+                    authority: vesting.to_account_info(),
+                },
+                signer,
+            ),
+            claimable,
+        )?;
+        
+        vesting.released_amount = vested;
+        
+        Ok(())
+    }
+
+    pub fn cancel_vesting(
+        ctx: Context<CancelVesting>,
+    ) -> Result<()> {
+        let vesting = &mut ctx.accounts.vesting;
+        require!(vesting.cancellable, ErrorCode::VestingNotCancellable);
+        require!(!vesting.cancelled, ErrorCode::VestingCancelled);
+        
+        let current_time = Clock::get()?.unix_timestamp;
+        let vested = vesting.vested_amount(current_time);
+        let unvested = vesting.total_amount
+            .checked_sub(vested)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        
+        if unvested > 0 {
+            let vesting_seeds = &[
+                b"vesting",
+                vesting.mint_state.as_ref(),
+                vesting.beneficiary.as_ref(),
+                &[vesting.bump],
+            ];
+            let signer = &[&vesting_seeds[..]];
+            
+            token::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.escrow.to_account_info(),
+                        to: ctx.accounts.refund_account.to_account_info(),
+                        authority: vesting.to_account_info(),
+                    },
+                    signer,
+                ),
+                unvested,
+            )?;
+        }
+        
+        // Freeze the schedule at what has vested so far; the beneficiary can still claim it
+        vesting.total_amount = vested;
+        vesting.end_time = current_time.max(vesting.start_time.saturating_add(1));
+        vesting.cliff_time = vesting.cliff_time.min(vesting.end_time);
+        vesting.cancelled = true;
+        
+        Ok(())
+    }
+
+    pub fn initialize_emission_schedule(
+        ctx: Context<InitializeEmissionSchedule>,
+        emission_per_epoch: u64,
+        decay_bps: u16,
+        distributor: Pubkey,
+    ) -> Result<()> {
+        require!(decay_bps <= 10000, ErrorCode::InvalidEmissionDecay);
+        
+        let schedule = &mut ctx.accounts.emission_schedule;
+        schedule.mint_state = ctx.accounts.mint_state.key();
+        schedule.distributor = distributor;
+        schedule.reward_pool = ctx.accounts.reward_pool.key();
+        schedule.emission_per_epoch = emission_per_epoch;
+        schedule.decay_bps = decay_bps;
+        schedule.next_emission_epoch = ctx.accounts.mint_state.current_epoch;
+        schedule.total_emitted = 0;
+        schedule.bump = ctx.bumps.emission_schedule;
+        
+        Ok(())
+    }
+
+    pub fn emit_epoch_rewards(
+        ctx: Context<EmitEpochRewards>,
+    ) -> Result<u64> {
+        let mint_state = &mut ctx.accounts.mint_state;
+        let schedule = &mut ctx.accounts.emission_schedule;
+        
+        require!(!mint_state.minting_frozen, ErrorCode::MintingFrozen);
+        require!(
+            mint_state.current_epoch >= schedule.next_emission_epoch,
+            ErrorCode::EmissionAlreadyDone
+        );
+        
+        // Emissions share the epoch mint cap with every other mint
+        let mint_cap = mint_state.total_supply
+            .checked_mul(mint_state.mint_cap_per_epoch_bps as u64)
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            .checked_div(10000)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        let remaining_cap = mint_cap.saturating_sub(mint_state.epoch_minted);
+        let amount = schedule.emission_per_epoch.min(remaining_cap);
+        
+        if amount > 0 {
+            let mint_seeds = &[
+                b"mint_state",
+                mint_state.authority.as_ref(),
+                &[mint_state.bump],
+            ];
+            let signer = &[&mint_seeds[..]];
+            
+            token::mint_to(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    MintTo {
+                        mint: ctx.accounts.aru_mint.to_account_info(),
+                        to: ctx.accounts.reward_pool.to_account_info(),
+                        authority: mint_state.to_account_info(),
+                    },
+                    signer,
+                ),
+                amount,
+            )?;
+        }
+        
+        mint_state.epoch_minted = mint_state.epoch_minted
+            .checked_add(amount)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        mint_state.epoch_emitted = mint_state.epoch_emitted
+            .checked_add(amount)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        mint_state.total_supply = mint_state.total_supply
+            .checked_add(amount)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        
+        schedule.next_emission_epoch = mint_state.current_epoch
+            .checked_add(1)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        schedule.total_emitted = schedule.total_emitted
+            .checked_add(amount)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        schedule.emission_per_epoch = (schedule.emission_per_epoch as u128)
+            .checked_mul(10000u128 - schedule.decay_bps as u128)
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            .checked_div(10000)
+            .ok_or(ErrorCode::ArithmeticOverflow)? as u64;
+        
+        Ok(amount)
+    }
+
+    pub fn register_minter(
+        ctx: Context<RegisterMinter>,
+        minter: Pubkey,
+        mint_ceiling: u64,
+    ) -> Result<()> {
+        let minter_account = &mut ctx.accounts.minter_account;
+        minter_account.mint_state = ctx.accounts.mint_state.key();
+        minter_account.minter = minter;
+        minter_account.mint_ceiling = mint_ceiling;
+        minter_account.outstanding = 0;
+        minter_account.bump = ctx.bumps.minter_account;
+        
+        Ok(())
+    }
+
+    pub fn set_minter_ceiling(
+        ctx: Context<SetMinterCeiling>,
+        mint_ceiling: u64,
+    ) -> Result<()> {
+        ctx.accounts.minter_account.mint_ceiling = mint_ceiling;
+        
+        Ok(())
+    }
+
+    /// Permanently stop ARU issuance for global settlement
+    pub fn freeze_minting(
+        ctx: Context<FreezeMinting>,
+    ) -> Result<()> {
+        ctx.accounts.mint_state.minting_frozen = true;
+        
+        Ok(())
+    }
+
+    /// Mint against a registered minter's ceiling. Counted in the epoch totals
+    /// but not limited by the epoch mint cap.
+    pub fn minter_mint(
+        ctx: Context<MinterMint>,
+        amount: u64,
+    ) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidAmount);
+        
+        let mint_state = &mut ctx.accounts.mint_state;
+        let minter_account = &mut ctx.accounts.minter_account;
+        require!(!mint_state.minting_frozen, ErrorCode::MintingFrozen);
+        
+        let outstanding = minter_account.outstanding
+            .checked_add(amount)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        require!(
+            outstanding <= minter_account.mint_ceiling,
+            ErrorCode::MinterCeilingExceeded
+        );
+        
+        let mint_seeds = &[
+            b"mint_state",
+            mint_state.authority.as_ref(),
+            &[mint_state.bump],
+        ];
+        let signer = &[&mint_seeds[..]];
+        
+        token::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                MintTo {
+                    mint: ctx.accounts.aru_mint.to_account_info(),
+                    to: ctx.accounts.destination.to_account_info(),
+                    authority: mint_state.to_account_info(),
+                },
+                signer,
+            ),
+            amount,
+        )?;
+        
+        minter_account.outstanding = outstanding;
+        mint_state.epoch_minted = mint_state.epoch_minted
+            .checked_add(amount)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        mint_state.total_supply = mint_state.total_supply
+            .checked_add(amount)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        
+        Ok(())
+    }
+
+    /// Burn ARU back against a minter's outstanding balance, outside the epoch burn cap
+    pub fn minter_burn(
+        ctx: Context<MinterBurn>,
+        amount: u64,
+    ) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidAmount);
+        
+        let mint_state = &mut ctx.accounts.mint_state;
+        let minter_account = &mut ctx.accounts.minter_account;
+        
+        minter_account.outstanding = minter_account.outstanding
+            .checked_sub(amount)
+            .ok_or(ErrorCode::MinterDebtExceeded)?;
+        
+        token::burn(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Burn {
+                    mint: ctx.accounts.aru_mint.to_account_info(),
+                    from: ctx.accounts.source.to_account_info(),
+                    authority: ctx.accounts.owner.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+        
+        mint_state.epoch_burned = mint_state.epoch_burned
+            .checked_add(amount)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        mint_state.total_supply = mint_state.total_supply
+            .checked_sub(amount)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = MintState::LEN,
+        seeds = [b"mint_state", authority.key().as_ref()],
+        bump
+    )]
+    pub mint_state: Account<'info, MintState>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub aru_mint: Account<'info, Mint>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MintARU<'info> {
+    #[account(
+        mut,
+        seeds = [b"mint_state", mint_state.authority.as_ref()],
+        bump = mint_state.bump
+    )]
+    pub mint_state: Account<'info, MintState>,
+    
+    #[account(mut)]
+    pub aru_mint: Account<'info, Mint>,
+    
+    #[account(mut)]
+    pub destination: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct BurnARU<'info> {
+    #[account(
+        mut,
+        seeds = [b"mint_state", mint_state.authority.as_ref()],
+        bump = mint_state.bump
+    )]
+    pub mint_state: Account<'info, MintState>,
+    
+    #[account(mut)]
+    pub aru_mint: Account<'info, Mint>,
+    
+    #[account(mut)]
+    pub source: Account<'info, TokenAccount>,
+    
+    pub authority: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct StartNewEpoch<'info> {
+    #[account(
+        mut,
+        seeds = [b"mint_state", mint_state.authority.as_ref()],
+        bump = mint_state.bump
+    )]
+    pub mint_state: Account<'info, MintState>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = EpochHistory::LEN,
+        seeds = [b"epoch_history", mint_state.current_epoch.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub epoch_history: Account<'info, EpochHistory>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeSavingsVault<'info> {
+    #[account(
+        seeds = [b"mint_state", mint_state.authority.as_ref()],
+        bump = mint_state.bump,
+        has_one = authority @ ErrorCode::Unauthorized,
+        has_one = aru_mint
+    )]
+    pub mint_state: Account<'info, MintState>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = SavingsVault::LEN,
+        seeds = [b"savings_vault", mint_state.key().as_ref()],
+        bump
+    )]
+    pub savings_vault: Account<'info, SavingsVault>,
+    
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"share_mint", savings_vault.key().as_ref()],
+        bump,
+        mint::decimals = aru_mint.decimals,
+        mint::authority = savings_vault
+    )]
+    pub share_mint: Account<'info, Mint>,
+    
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"savings_aru", savings_vault.key().as_ref()],
+        bump,
+        token::mint = aru_mint,
+        token::authority = savings_vault
+    )]
+    pub aru_vault: Account<'info, TokenAccount>,
+    
+    pub aru_mint: Account<'info, Mint>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct DepositSavings<'info> {
+    #[account(
+        mut,
+        seeds = [b"savings_vault", savings_vault.mint_state.as_ref()],
+        bump = savings_vault.bump,
+        has_one = share_mint,
+        has_one = aru_vault
+    )]
+    pub savings_vault: Account<'info, SavingsVault>,
+    
+    #[account(mut)]
+    pub share_mint: Account<'info, Mint>,
+    
+    #[account(mut)]
+    pub aru_vault: Account<'info, TokenAccount>,
+    
+    #[account(mut)]
+    pub user_aru_account: Account<'info, TokenAccount>,
+    
+    #[account(mut)]
+    pub user_share_account: Account<'info, TokenAccount>,
+    
+    pub user: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawSavings<'info> {
+    #[account(
+        mut,
+        seeds = [b"savings_vault", savings_vault.mint_state.as_ref()],
+        bump = savings_vault.bump,
+        has_one = share_mint,
+        has_one = aru_vault
+    )]
+    pub savings_vault: Account<'info, SavingsVault>,
+    
+    #[account(mut)]
+    pub share_mint: Account<'info, Mint>,
+    
+    #[account(mut)]
+    pub aru_vault: Account<'info, TokenAccount>,
+    
+    #[account(mut)]
+    pub user_aru_account: Account<'info, TokenAccount>,
+    
+    #[account(mut)]
+    pub user_share_account: Account<'info, TokenAccount>,
+    
+    pub user: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateSavingsRate<'info> {
+    #[account(
+        seeds = [b"mint_state", mint_state.authority.as_ref()],
+        bump = mint_state.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub mint_state: Account<'info, MintState>,
+    
+    #[account(
+        mut,
+        seeds = [b"savings_vault", mint_state.key().as_ref()],
+        bump = savings_vault.bump,
+        has_one = aru_vault
+    )]
+    pub savings_vault: Account<'info, SavingsVault>,
+    
+    pub aru_vault: Account<'info, TokenAccount>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeStakeVault<'info> {
+    #[account(
+        seeds = [b"mint_state", mint_state.authority.as_ref()],
+        bump = mint_state.bump,
+        has_one = authority @ ErrorCode::Unauthorized,
+        has_one = aru_mint
+    )]
+    pub mint_state: Account<'info, MintState>,
+    
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"stake_vault", mint_state.key().as_ref()],
+        bump,
+        token::mint = aru_mint,
+        token::authority = mint_state
+    )]
+    pub stake_vault: Account<'info, TokenAccount>,
+    
+    pub aru_mint: Account<'info, Mint>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeCheckpoints<'info> {
+    #[account(
+        seeds = [b"mint_state", mint_state.authority.as_ref()],
+        bump = mint_state.bump
+    )]
+    pub mint_state: Account<'info, MintState>,
+    
+    #[account(
+        init,
+        payer = owner,
+        space = BalanceCheckpoints::LEN,
+        seeds = [b"checkpoints", mint_state.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub checkpoints: Account<'info, BalanceCheckpoints>,
+    
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct Initialize<'info> {
+pub struct Stake<'info> {
     #[account(
-        init,
-        payer = authority,
-        space = MintState::LEN,
-        seeds = [b"mint_state", authority.key().as_ref()],
+        seeds = [b"mint_state", mint_state.authority.as_ref()],
+        bump = mint_state.bump
+    )]
+    pub mint_state: Account<'info, MintState>,
+    
+    #[account(
+        mut,
+        seeds = [b"checkpoints", mint_state.key().as_ref(), owner.key().as_ref()],
+        bump = checkpoints.bump
+    )]
+    pub checkpoints: Account<'info, BalanceCheckpoints>,
+    
+    #[account(
+        mut,
+        seeds = [b"stake_vault", mint_state.key().as_ref()],
+        bump
+    )]
+    pub stake_vault: Account<'info, TokenAccount>,
+    
+    #[account(mut)]
+    pub owner_aru_account: Account<'info, TokenAccount>,
+    
+    pub owner: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct Unstake<'info> {
+    #[account(
+        seeds = [b"mint_state", mint_state.authority.as_ref()],
+        bump = mint_state.bump
+    )]
+    pub mint_state: Account<'info, MintState>,
+    
+    #[account(
+        mut,
+        seeds = [b"checkpoints", mint_state.key().as_ref(), owner.key().as_ref()],
+        bump = checkpoints.bump
+    )]
+    pub checkpoints: Account<'info, BalanceCheckpoints>,
+    
+    #[account(
+        mut,
+        seeds = [b"stake_vault", mint_state.key().as_ref()],
         bump
     )]
+    pub stake_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = owner_aru_account.owner == owner.key() @ ErrorCode::Unauthorized
+    )]
+    pub owner_aru_account: Account<'info, TokenAccount>,
+    
+    pub owner: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CreateLock<'info> {
+    #[account(
+        seeds = [b"mint_state", mint_state.authority.as_ref()],
+        bump = mint_state.bump
+    )]
     pub mint_state: Account<'info, MintState>,
     
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = VoteEscrow::LEN,
+        seeds = [b"ve_lock", mint_state.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub vote_escrow: Account<'info, VoteEscrow>,
+    
+    #[account(
+        mut,
+        seeds = [b"stake_vault", mint_state.key().as_ref()],
+        bump
+    )]
+    pub stake_vault: Account<'info, TokenAccount>,
+    
     #[account(mut)]
-    pub authority: Signer<'info>,
+    pub owner_aru_account: Account<'info, TokenAccount>,
     
-    pub aru_mint: Account<'info, Mint>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
     
+    pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct MintARU<'info> {
+pub struct ModifyLock<'info> {
+    #[account(
+        seeds = [b"mint_state", mint_state.authority.as_ref()],
+        bump = mint_state.bump
+    )]
+    pub mint_state: Account<'info, MintState>,
+    
     #[account(
         mut,
+        seeds = [b"ve_lock", mint_state.key().as_ref(), owner.key().as_ref()],
+        bump = vote_escrow.bump
+    )]
+    pub vote_escrow: Account<'info, VoteEscrow>,
+    
+    #[account(
+        mut,
+        seeds = [b"stake_vault", mint_state.key().as_ref()],
+        bump
+    )]
+    pub stake_vault: Account<'info, TokenAccount>,
+    
+    #[account(mut)]
+    pub owner_aru_account: Account<'info, TokenAccount>,
+    
+    pub owner: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawLock<'info> {
+    #[account(
         seeds = [b"mint_state", mint_state.authority.as_ref()],
         bump = mint_state.bump
     )]
     pub mint_state: Account<'info, MintState>,
     
+    #[account(
+        mut,
+        seeds = [b"ve_lock", mint_state.key().as_ref(), owner.key().as_ref()],
+        bump = vote_escrow.bump
+    )]
+    pub vote_escrow: Account<'info, VoteEscrow>,
+    
+    #[account(
+        mut,
+        seeds = [b"stake_vault", mint_state.key().as_ref()],
+        bump
+    )]
+    pub stake_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = owner_aru_account.owner == owner.key() @ ErrorCode::Unauthorized
+    )]
+    pub owner_aru_account: Account<'info, TokenAccount>,
+    
     #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CreateVesting<'info> {
+    #[account(
+        seeds = [b"mint_state", mint_state.authority.as_ref()],
+        bump = mint_state.bump,
+        has_one = authority @ ErrorCode::Unauthorized,
+        has_one = aru_mint
+    )]
+    pub mint_state: Account<'info, MintState>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = VestingAccount::LEN,
+        seeds = [b"vesting", mint_state.key().as_ref(), beneficiary.key().as_ref()],
+        bump
+    )]
+    pub vesting: Account<'info, VestingAccount>,
+    
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"vesting_escrow", vesting.key().as_ref()],
+        bump,
+        token::mint = aru_mint,
+        token::authority = vesting
+    )]
+    pub escrow: Account<'info, TokenAccount>,
+    
+    /// CHECK: Beneficiary receiving vested ARU
+    pub beneficiary: AccountInfo<'info>,
+    
     pub aru_mint: Account<'info, Mint>,
     
     #[account(mut)]
-    pub destination: Account<'info, TokenAccount>,
+    pub funding_account: Account<'info, TokenAccount>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
     
     pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
 }
 
 #[derive(Accounts)]
-pub struct BurnARU<'info> {
+pub struct ClaimVested<'info> {
     #[account(
         mut,
+        seeds = [b"vesting", vesting.mint_state.as_ref(), beneficiary.key().as_ref()],
+        bump = vesting.bump,
+        has_one = beneficiary,
+        has_one = escrow
+    )]
+    pub vesting: Account<'info, VestingAccount>,
+    
+    #[account(mut)]
+    pub escrow: Account<'info, TokenAccount>,
+    
+    #[account(mut)]
+    pub beneficiary_token_account: Account<'info, TokenAccount>,
+    
+    pub beneficiary: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CancelVesting<'info> {
+    #[account(
         seeds = [b"mint_state", mint_state.authority.as_ref()],
-        bump = mint_state.bump
+        bump = mint_state.bump,
+        has_one = authority @ ErrorCode::Unauthorized
     )]
     pub mint_state: Account<'info, MintState>,
     
+    #[account(
+        mut,
+        seeds = [b"vesting", mint_state.key().as_ref(), vesting.beneficiary.as_ref()],
+        bump = vesting.bump,
+        has_one = escrow
+    )]
+    pub vesting: Account<'info, VestingAccount>,
+    
     #[account(mut)]
-    pub aru_mint: Account<'info, Mint>,
+    pub escrow: Account<'info, TokenAccount>,
     
     #[account(mut)]
-    pub source: Account<'info, TokenAccount>,
+    pub refund_account: Account<'info, TokenAccount>,
     
     pub authority: Signer<'info>,
     
@@ -225,25 +1379,175 @@ pub struct BurnARU<'info> {
 }
 
 #[derive(Accounts)]
-pub struct StartNewEpoch<'info> {
+pub struct InitializeEmissionSchedule<'info> {
+    #[account(
+        seeds = [b"mint_state", mint_state.authority.as_ref()],
+        bump = mint_state.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub mint_state: Account<'info, MintState>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = EmissionSchedule::LEN,
+        seeds = [b"emission_schedule", mint_state.key().as_ref()],
+        bump
+    )]
+    pub emission_schedule: Account<'info, EmissionSchedule>,
+    
+    #[account(constraint = reward_pool.mint == mint_state.aru_mint)]
+    pub reward_pool: Account<'info, TokenAccount>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct EmitEpochRewards<'info> {
     #[account(
         mut,
         seeds = [b"mint_state", mint_state.authority.as_ref()],
-        bump = mint_state.bump
+        bump = mint_state.bump,
+        has_one = aru_mint
+    )]
+    pub mint_state: Account<'info, MintState>,
+    
+    #[account(
+        mut,
+        seeds = [b"emission_schedule", mint_state.key().as_ref()],
+        bump = emission_schedule.bump,
+        has_one = distributor @ ErrorCode::Unauthorized,
+        has_one = reward_pool
+    )]
+    pub emission_schedule: Account<'info, EmissionSchedule>,
+    
+    #[account(mut)]
+    pub aru_mint: Account<'info, Mint>,
+    
+    #[account(mut)]
+    pub reward_pool: Account<'info, TokenAccount>,
+    
+    pub distributor: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(minter: Pubkey)]
+pub struct RegisterMinter<'info> {
+    #[account(
+        seeds = [b"mint_state", mint_state.authority.as_ref()],
+        bump = mint_state.bump,
+        has_one = authority @ ErrorCode::Unauthorized
     )]
     pub mint_state: Account<'info, MintState>,
     
     #[account(
         init,
         payer = authority,
-        space = EpochHistory::LEN,
-        seeds = [b"epoch_history", mint_state.current_epoch.to_le_bytes().as_ref()],
+        space = Minter::LEN,
+        seeds = [b"minter", mint_state.key().as_ref(), minter.as_ref()],
         bump
     )]
-    pub epoch_history: Account<'info, EpochHistory>,
+    pub minter_account: Account<'info, Minter>,
     
     #[account(mut)]
     pub authority: Signer<'info>,
     
     pub system_program: Program<'info, System>,
 }
+
+#[derive(Accounts)]
+pub struct FreezeMinting<'info> {
+    #[account(
+        mut,
+        seeds = [b"mint_state", mint_state.authority.as_ref()],
+        bump = mint_state.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub mint_state: Account<'info, MintState>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetMinterCeiling<'info> {
+    #[account(
+        seeds = [b"mint_state", mint_state.authority.as_ref()],
+        bump = mint_state.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub mint_state: Account<'info, MintState>,
+    
+    #[account(
+        mut,
+        seeds = [b"minter", mint_state.key().as_ref(), minter_account.minter.as_ref()],
+        bump = minter_account.bump
+    )]
+    pub minter_account: Account<'info, Minter>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MinterMint<'info> {
+    #[account(
+        mut,
+        seeds = [b"mint_state", mint_state.authority.as_ref()],
+        bump = mint_state.bump,
+        has_one = aru_mint
+    )]
+    pub mint_state: Account<'info, MintState>,
+    
+    #[account(
+        mut,
+        seeds = [b"minter", mint_state.key().as_ref(), minter.key().as_ref()],
+        bump = minter_account.bump,
+        has_one = minter @ ErrorCode::Unauthorized
+    )]
+    pub minter_account: Account<'info, Minter>,
+    
+    #[account(mut)]
+    pub aru_mint: Account<'info, Mint>,
+    
+    #[account(mut)]
+    pub destination: Account<'info, TokenAccount>,
+    
+    pub minter: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct MinterBurn<'info> {
+    #[account(
+        mut,
+        seeds = [b"mint_state", mint_state.authority.as_ref()],
+        bump = mint_state.bump,
+        has_one = aru_mint
+    )]
+    pub mint_state: Account<'info, MintState>,
+    
+    #[account(
+        mut,
+        seeds = [b"minter", mint_state.key().as_ref(), minter.key().as_ref()],
+        bump = minter_account.bump,
+        has_one = minter @ ErrorCode::Unauthorized
+    )]
+    pub minter_account: Account<'info, Minter>,
+    
+    #[account(mut)]
+    pub aru_mint: Account<'info, Mint>,
+    
+    #[account(mut)]
+    pub source: Account<'info, TokenAccount>,
+    
+    pub owner: Signer<'info>,
+    
+    pub minter: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+}
@@ -1,4 +1,16 @@
 use anchor_lang::prelude::*;
+use crate::errors::ErrorCode;
+
+/// Fixed-point scale for the sARU exchange rate (1 sARU = 1 ARU at 1e12)
+pub const EXCHANGE_RATE_SCALE: u128 = 1_000_000_000_000;
+
+pub const SECONDS_PER_YEAR: i64 = 31_536_000;
+
+/// Maximum checkpoints retained per holder; the oldest is dropped when full
+pub const MAX_CHECKPOINTS: usize = 32;
+
+/// Longest veARU lock term (4 years); a full-term lock earns 1 vote per ARU
+pub const MAX_LOCK_DURATION: i64 = 4 * SECONDS_PER_YEAR;
 
 #[account]
 pub struct MintState {
@@ -10,8 +22,11 @@ pub struct MintState {
     pub total_supply: u64,
     pub epoch_minted: u64,
     pub epoch_burned: u64,
+    pub epoch_emitted: u64,
     pub mint_cap_per_epoch_bps: u16,
     pub burn_cap_per_epoch_bps: u16,
+    /// Set at global settlement; no ARU can be minted afterwards
+    pub minting_frozen: bool,
     pub bump: u8,
 }
 
@@ -25,8 +40,10 @@ impl MintState {
         8 + // total_supply
         8 + // epoch_minted
         8 + // epoch_burned
+        8 + // epoch_emitted
         2 + // mint_cap_per_epoch_bps
         2 + // burn_cap_per_epoch_bps
+        1 + // minting_frozen
         1; // bump
 }
 
@@ -39,6 +56,7 @@ pub struct EpochHistory {
     pub total_burned: u64,
     pub net_supply_change: i64,
     pub final_supply: u64,
+    pub agent_rewards_emitted: u64,
 }
 
 impl EpochHistory {
@@ -49,5 +67,308 @@ impl EpochHistory {
         8 + // total_minted
         8 + // total_burned
         8 + // net_supply_change
-        8; // final_supply
+        8 + // final_supply
+        8; // agent_rewards_emitted
+}
+
+#[account]
+pub struct SavingsVault {
+    pub mint_state: Pubkey,
+    pub share_mint: Pubkey,
+    pub aru_vault: Pubkey,
+    pub savings_rate_bps: u16,
+    pub exchange_rate: u128,
+    pub last_update: i64,
+    pub total_shares: u64,
+    pub bump: u8,
+}
+
+impl SavingsVault {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // mint_state
+        32 + // share_mint
+        32 + // aru_vault
+        2 + // savings_rate_bps
+        16 + // exchange_rate
+        8 + // last_update
+        8 + // total_shares
+        1; // bump
+
+    /// Grow the exchange rate at the savings rate up to `now`, but never past
+    /// what `funded_balance` ARU can redeem for the outstanding shares.
+    pub fn accrue(&mut self, now: i64, funded_balance: u64) -> Result<()> {
+        let elapsed = now.saturating_sub(self.last_update);
+        if elapsed <= 0 {
+            return Ok(());
+        }
+        self.last_update = now;
+
+        if self.total_shares == 0 || self.savings_rate_bps == 0 {
+            return Ok(());
+        }
+
+        let growth = self.exchange_rate
+            .checked_mul(self.savings_rate_bps as u128)
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            .checked_mul(elapsed as u128)
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            .checked_div(10_000u128 * SECONDS_PER_YEAR as u128)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        let target_rate = self.exchange_rate
+            .checked_add(growth)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+        let funded_rate = (funded_balance as u128)
+            .checked_mul(EXCHANGE_RATE_SCALE)
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            .checked_div(self.total_shares as u128)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+        self.exchange_rate = target_rate.min(funded_rate).max(self.exchange_rate);
+
+        Ok(())
+    }
+
+    pub fn shares_for_amount(&self, amount: u64) -> Result<u64> {
+        let shares = (amount as u128)
+            .checked_mul(EXCHANGE_RATE_SCALE)
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            .checked_div(self.exchange_rate)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        u64::try_from(shares).map_err(|_| ErrorCode::ArithmeticOverflow.into())
+    }
+
+    pub fn amount_for_shares(&self, shares: u64) -> Result<u64> {
+        let amount = (shares as u128)
+            .checked_mul(self.exchange_rate)
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            .checked_div(EXCHANGE_RATE_SCALE)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        u64::try_from(amount).map_err(|_| ErrorCode::ArithmeticOverflow.into())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default)]
+pub struct Checkpoint {
+    pub slot: u64,
+    pub balance: u64,
+}
+
+impl Checkpoint {
+    pub const LEN: usize = 8 + // slot
+        8; // balance
+}
+
+#[account]
+pub struct BalanceCheckpoints {
+    pub owner: Pubkey,
+    pub mint_state: Pubkey,
+    pub aru_mint: Pubkey,
+    pub checkpoints: Vec<Checkpoint>,
+    pub bump: u8,
+}
+
+impl BalanceCheckpoints {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // owner
+        32 + // mint_state
+        32 + // aru_mint
+        4 + (MAX_CHECKPOINTS * Checkpoint::LEN) + // checkpoints
+        1; // bump
+
+    pub fn latest_balance(&self) -> u64 {
+        self.checkpoints.last().map(|c| c.balance).unwrap_or(0)
+    }
+
+    /// Staked balance as of the end of `slot`. Slots older than the
+    /// retained history resolve to zero.
+    pub fn balance_at(&self, slot: u64) -> u64 {
+        self.checkpoints
+            .iter()
+            .rev()
+            .find(|c| c.slot <= slot)
+            .map(|c| c.balance)
+            .unwrap_or(0)
+    }
+
+    pub fn record(&mut self, slot: u64, balance: u64) {
+        if let Some(last) = self.checkpoints.last_mut() {
+            if last.slot == slot {
+                last.balance = balance;
+                return;
+            }
+        }
+        if self.checkpoints.len() >= MAX_CHECKPOINTS {
+            self.checkpoints.remove(0);
+        }
+        self.checkpoints.push(Checkpoint { slot, balance });
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default)]
+pub struct LockCheckpoint {
+    pub slot: u64,
+    pub amount: u64,
+    pub unlock_time: i64,
+}
+
+impl LockCheckpoint {
+    pub const LEN: usize = 8 + // slot
+        8 + // amount
+        8; // unlock_time
+
+    /// veARU weight at `timestamp`, decaying linearly to zero at unlock.
+    pub fn voting_power_at(&self, timestamp: i64) -> u64 {
+        let remaining = self.unlock_time.saturating_sub(timestamp);
+        if remaining <= 0 {
+            return 0;
+        }
+        ((self.amount as u128) * (remaining as u128) / (MAX_LOCK_DURATION as u128)) as u64
+    }
+}
+
+#[account]
+pub struct VoteEscrow {
+    pub owner: Pubkey,
+    pub mint_state: Pubkey,
+    pub aru_mint: Pubkey,
+    pub amount: u64,
+    pub unlock_time: i64,
+    pub checkpoints: Vec<LockCheckpoint>,
+    pub bump: u8,
+}
+
+impl VoteEscrow {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // owner
+        32 + // mint_state
+        32 + // aru_mint
+        8 + // amount
+        8 + // unlock_time
+        4 + (MAX_CHECKPOINTS * LockCheckpoint::LEN) + // checkpoints
+        1; // bump
+
+    /// Current veARU weight at `timestamp`.
+    pub fn voting_power_at(&self, timestamp: i64) -> u64 {
+        self.lock_at(u64::MAX).voting_power_at(timestamp)
+    }
+
+    /// Lock terms as of the end of `slot`. Slots older than the retained
+    /// history resolve to an empty lock.
+    pub fn lock_at(&self, slot: u64) -> LockCheckpoint {
+        self.checkpoints
+            .iter()
+            .rev()
+            .find(|c| c.slot <= slot)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Checkpoint the current amount and unlock time; every instruction
+    /// that changes veARU weight must call this.
+    pub fn record(&mut self, slot: u64) {
+        let checkpoint = LockCheckpoint {
+            slot,
+            amount: self.amount,
+            unlock_time: self.unlock_time,
+        };
+        if let Some(last) = self.checkpoints.last_mut() {
+            if last.slot == slot {
+                *last = checkpoint;
+                return;
+            }
+        }
+        if self.checkpoints.len() >= MAX_CHECKPOINTS {
+            self.checkpoints.remove(0);
+        }
+        self.checkpoints.push(checkpoint);
+    }
+}
+
+#[account]
+pub struct VestingAccount {
+    pub beneficiary: Pubkey,
+    pub mint_state: Pubkey,
+    pub escrow: Pubkey,
+    pub total_amount: u64,
+    pub released_amount: u64,
+    pub start_time: i64,
+    pub cliff_time: i64,
+    pub end_time: i64,
+    pub cancellable: bool,
+    pub cancelled: bool,
+    pub bump: u8,
+}
+
+impl VestingAccount {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // beneficiary
+        32 + // mint_state
+        32 + // escrow
+        8 + // total_amount
+        8 + // released_amount
+        8 + // start_time
+        8 + // cliff_time
+        8 + // end_time
+        1 + // cancellable
+        1 + // cancelled
+        1; // bump
+
+    /// Amount vested at `timestamp`: nothing before the cliff, then linear
+    /// from `start_time` to `end_time`.
+    pub fn vested_amount(&self, timestamp: i64) -> u64 {
+        if timestamp < self.cliff_time {
+            return 0;
+        }
+        if timestamp >= self.end_time {
+            return self.total_amount;
+        }
+        let elapsed = timestamp.saturating_sub(self.start_time) as u128;
+        let duration = self.end_time.saturating_sub(self.start_time) as u128;
+        ((self.total_amount as u128) * elapsed / duration) as u64
+    }
+}
+
+#[account]
+pub struct EmissionSchedule {
+    pub mint_state: Pubkey,
+    pub distributor: Pubkey,
+    pub reward_pool: Pubkey,
+    pub emission_per_epoch: u64,
+    pub decay_bps: u16,
+    pub next_emission_epoch: u64,
+    pub total_emitted: u64,
+    pub bump: u8,
+}
+
+impl EmissionSchedule {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // mint_state
+        32 + // distributor
+        32 + // reward_pool
+        8 + // emission_per_epoch
+        2 + // decay_bps
+        8 + // next_emission_epoch
+        8 + // total_emitted
+        1; // bump
+}
+
+/// Program-owned minter (e.g. the reserve peg stability module) bounded by its
+/// own ceiling rather than the epoch mint cap
+#[account]
+pub struct Minter {
+    pub mint_state: Pubkey,
+    pub minter: Pubkey,
+    pub mint_ceiling: u64,
+    pub outstanding: u64,
+    pub bump: u8,
+}
+
+impl Minter {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // mint_state
+        32 + // minter
+        8 + // mint_ceiling
+        8 + // outstanding
+        1; // bump
 }
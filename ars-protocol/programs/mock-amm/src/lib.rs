@@ -0,0 +1,178 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+
+declare_id!("D9Z7sWWA1fcQRZVusnk2Sov4iTgsYxzmoLafWbwgfDqc");
+
+#[program]
+pub mod mock_amm {
+    use super::*;
+
+    pub fn initialize_pool(
+        ctx: Context<InitializePool>,
+        rate_numerator: u64,
+        rate_denominator: u64,
+    ) -> Result<()> {
+        require!(
+            rate_numerator > 0 && rate_denominator > 0,
+            ErrorCode::InvalidRate
+        );
+        
+        let pool = &mut ctx.accounts.pool;
+        pool.source_mint = ctx.accounts.source_mint.key();
+        pool.destination_mint = ctx.accounts.destination_mint.key();
+        pool.rate_numerator = rate_numerator;
+        pool.rate_denominator = rate_denominator;
+        pool.bump = ctx.bumps.pool;
+        
+        Ok(())
+    }
+
+    /// Swap adapter entrypoint: `source` -> `destination` at the pool's fixed rate
+    pub fn swap(
+        ctx: Context<Swap>,
+        amount_in: u64,
+        min_amount_out: u64,
+    ) -> Result<()> {
+        let pool = &ctx.accounts.pool;
+        
+        let amount_out = (amount_in as u128)
+            .checked_mul(pool.rate_numerator as u128)
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            .checked_div(pool.rate_denominator as u128)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        let amount_out = u64::try_from(amount_out).map_err(|_| error!(ErrorCode::ArithmeticOverflow))?;
+        
+        require!(amount_out >= min_amount_out, ErrorCode::SlippageExceeded);
+        
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.source.to_account_info(),
+                    to: ctx.accounts.pool_source.to_account_info(),
+                    authority: ctx.accounts.authority.to_account_info(),
+                },
+            ),
+            amount_in,
+        )?;
+        
+        let pool_seeds = &[
+            b"pool",
+            pool.source_mint.as_ref(),
+            pool.destination_mint.as_ref(),
+            &[pool.bump],
+        ];
+        let signer = &[&pool_seeds[..]];
+        
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.pool_destination.to_account_info(),
+                    to: ctx.accounts.destination.to_account_info(),
+                    authority: pool.to_account_info(),
+                },
+                signer,
+            ),
+            amount_out,
+        )?;
+        
+        Ok(())
+    }
+}
+
+#[account]
+pub struct Pool {
+    pub source_mint: Pubkey,
+    pub destination_mint: Pubkey,
+    pub rate_numerator: u64,
+    pub rate_denominator: u64,
+    pub bump: u8,
+}
+
+impl Pool {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // source_mint
+        32 + // destination_mint
+        8 + // rate_numerator
+        8 + // rate_denominator
+        1; // bump
+}
+
+#[derive(Accounts)]
+pub struct InitializePool<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = Pool::LEN,
+        seeds = [b"pool", source_mint.key().as_ref(), destination_mint.key().as_ref()],
+        bump
+    )]
+    pub pool: Account<'info, Pool>,
+    
+    /// CHECK: Only used as a seed
+    pub source_mint: AccountInfo<'info>,
+    
+    /// CHECK: Only used as a seed
+    pub destination_mint: AccountInfo<'info>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+// The first four accounts follow the ars-reserve swap adapter layout
+#[derive(Accounts)]
+pub struct Swap<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = source.mint == pool.source_mint @ ErrorCode::InvalidPoolAccount
+    )]
+    pub source: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = destination.mint == pool.destination_mint @ ErrorCode::InvalidPoolAccount
+    )]
+    pub destination: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+    
+    #[account(
+        seeds = [b"pool", pool.source_mint.as_ref(), pool.destination_mint.as_ref()],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, Pool>,
+    
+    #[account(
+        mut,
+        constraint = pool_source.mint == pool.source_mint @ ErrorCode::InvalidPoolAccount,
+        constraint = pool_source.owner == pool.key() @ ErrorCode::InvalidPoolAccount
+    )]
+    pub pool_source: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = pool_destination.mint == pool.destination_mint @ ErrorCode::InvalidPoolAccount,
+        constraint = pool_destination.owner == pool.key() @ ErrorCode::InvalidPoolAccount
+    )]
+    pub pool_destination: Account<'info, TokenAccount>,
+}
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Arithmetic overflow occurred")]
+    ArithmeticOverflow,
+    
+    #[msg("Invalid pool rate")]
+    InvalidRate,
+    
+    #[msg("Token account does not belong to the pool")]
+    InvalidPoolAccount,
+    
+    #[msg("Output below minimum amount")]
+    SlippageExceeded,
+}
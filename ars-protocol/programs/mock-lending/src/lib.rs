@@ -0,0 +1,383 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+
+declare_id!("F6vTEa8zYkA9HnkEeAWFbqMNeqwuTGhYTSMXaupFqG15");
+
+#[program]
+pub mod mock_lending {
+    use super::*;
+
+    pub fn initialize_market(ctx: Context<InitializeMarket>) -> Result<()> {
+        let market = &mut ctx.accounts.market;
+        market.mint = ctx.accounts.mint.key();
+        market.vault = ctx.accounts.market_vault.key();
+        market.authority = ctx.accounts.payer.key();
+        market.total_shares = 0;
+        market.bump = ctx.bumps.market;
+        
+        Ok(())
+    }
+
+    pub fn open_obligation(ctx: Context<OpenObligation>, owner: Pubkey) -> Result<()> {
+        let obligation = &mut ctx.accounts.obligation;
+        obligation.market = ctx.accounts.market.key();
+        obligation.owner = owner;
+        obligation.shares = 0;
+        obligation.bump = ctx.bumps.obligation;
+        
+        Ok(())
+    }
+
+    /// Strategy adapter entrypoint: supply `amount` for market shares
+    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        let market = &mut ctx.accounts.market;
+        let shares = if market.total_shares == 0 || ctx.accounts.market_vault.amount == 0 {
+            amount
+        } else {
+            mul_div(amount, market.total_shares, ctx.accounts.market_vault.amount)?
+        };
+        require!(shares > 0, ErrorCode::InvalidAmount);
+        
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.source.to_account_info(),
+                    to: ctx.accounts.market_vault.to_account_info(),
+                    authority: ctx.accounts.authority.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+        
+        market.total_shares = market.total_shares
+            .checked_add(shares)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        let obligation = &mut ctx.accounts.obligation;
+        obligation.shares = obligation.shares
+            .checked_add(shares)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        
+        Ok(())
+    }
+
+    /// Strategy adapter entrypoint: return up to `amount` of the position, `u64::MAX` for all
+    pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+        let market = &ctx.accounts.market;
+        let obligation = &ctx.accounts.obligation;
+        let value = position_value(obligation.shares, market.total_shares, ctx.accounts.market_vault.amount)?;
+        
+        let (amount, shares) = if amount >= value {
+            (value, obligation.shares)
+        } else {
+            // Round shares up so the market never pays out more than it burns
+            let shares = (amount as u128)
+                .checked_mul(market.total_shares as u128)
+                .ok_or(ErrorCode::ArithmeticOverflow)?
+                .checked_add(ctx.accounts.market_vault.amount as u128 - 1)
+                .ok_or(ErrorCode::ArithmeticOverflow)?
+                .checked_div(ctx.accounts.market_vault.amount as u128)
+                .ok_or(ErrorCode::ArithmeticOverflow)?;
+            (amount, u64::try_from(shares).map_err(|_| error!(ErrorCode::ArithmeticOverflow))?)
+        };
+        
+        let mint = market.mint;
+        let market_seeds = &[
+            b"market",
+            mint.as_ref(),
+            &[market.bump],
+        ];
+        let signer = &[&market_seeds[..]];
+        
+        if amount > 0 {
+            token::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.market_vault.to_account_info(),
+                        to: ctx.accounts.destination.to_account_info(),
+                        authority: ctx.accounts.market.to_account_info(),
+                    },
+                    signer,
+                ),
+                amount,
+            )?;
+        }
+        
+        let market = &mut ctx.accounts.market;
+        market.total_shares = market.total_shares
+            .checked_sub(shares)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        let obligation = &mut ctx.accounts.obligation;
+        obligation.shares = obligation.shares
+            .checked_sub(shares)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        
+        Ok(())
+    }
+
+    /// Strategy adapter entrypoint: underlying value of the position, as return data
+    pub fn report_value(ctx: Context<ReportValue>) -> Result<u64> {
+        position_value(
+            ctx.accounts.obligation.shares,
+            ctx.accounts.market.total_shares,
+            ctx.accounts.market_vault.amount,
+        )
+    }
+
+    /// Move funds out of the market to simulate a bad-debt loss
+    pub fn simulate_loss(ctx: Context<SimulateLoss>, amount: u64) -> Result<()> {
+        let market = &ctx.accounts.market;
+        let mint = market.mint;
+        let market_seeds = &[
+            b"market",
+            mint.as_ref(),
+            &[market.bump],
+        ];
+        let signer = &[&market_seeds[..]];
+        
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.market_vault.to_account_info(),
+                    to: ctx.accounts.destination.to_account_info(),
+                    authority: ctx.accounts.market.to_account_info(),
+                },
+                signer,
+            ),
+            amount,
+        )?;
+        
+        Ok(())
+    }
+}
+
+fn mul_div(value: u64, numerator: u64, denominator: u64) -> Result<u64> {
+    let result = (value as u128)
+        .checked_mul(numerator as u128)
+        .ok_or(ErrorCode::ArithmeticOverflow)?
+        .checked_div(denominator as u128)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+    
+    u64::try_from(result).map_err(|_| error!(ErrorCode::ArithmeticOverflow))
+}
+
+fn position_value(shares: u64, total_shares: u64, market_balance: u64) -> Result<u64> {
+    if total_shares == 0 {
+        return Ok(0);
+    }
+    
+    mul_div(shares, market_balance, total_shares)
+}
+
+#[account]
+pub struct Market {
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+    pub authority: Pubkey,
+    pub total_shares: u64,
+    pub bump: u8,
+}
+
+impl Market {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // mint
+        32 + // vault
+        32 + // authority
+        8 + // total_shares
+        1; // bump
+}
+
+#[account]
+pub struct Obligation {
+    pub market: Pubkey,
+    pub owner: Pubkey,
+    pub shares: u64,
+    pub bump: u8,
+}
+
+impl Obligation {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // market
+        32 + // owner
+        8 + // shares
+        1; // bump
+}
+
+#[derive(Accounts)]
+pub struct InitializeMarket<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = Market::LEN,
+        seeds = [b"market", mint.key().as_ref()],
+        bump
+    )]
+    pub market: Account<'info, Market>,
+    
+    /// CHECK: Only used as a seed
+    pub mint: AccountInfo<'info>,
+    
+    #[account(
+        constraint = market_vault.mint == mint.key() @ ErrorCode::InvalidMarketAccount,
+        constraint = market_vault.owner == market.key() @ ErrorCode::InvalidMarketAccount
+    )]
+    pub market_vault: Account<'info, TokenAccount>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(owner: Pubkey)]
+pub struct OpenObligation<'info> {
+    #[account(
+        seeds = [b"market", market.mint.as_ref()],
+        bump = market.bump
+    )]
+    pub market: Account<'info, Market>,
+    
+    #[account(
+        init,
+        payer = payer,
+        space = Obligation::LEN,
+        seeds = [b"obligation", market.key().as_ref(), owner.as_ref()],
+        bump
+    )]
+    pub obligation: Account<'info, Obligation>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+// The first three accounts follow the ars-reserve strategy adapter layout
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = source.mint == market.mint @ ErrorCode::InvalidMarketAccount
+    )]
+    pub source: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+    
+    #[account(
+        mut,
+        seeds = [b"market", market.mint.as_ref()],
+        bump = market.bump
+    )]
+    pub market: Account<'info, Market>,
+    
+    #[account(
+        mut,
+        address = market.vault @ ErrorCode::InvalidMarketAccount
+    )]
+    pub market_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"obligation", market.key().as_ref(), authority.key().as_ref()],
+        bump = obligation.bump
+    )]
+    pub obligation: Account<'info, Obligation>,
+}
+
+// The first three accounts follow the ars-reserve strategy adapter layout
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = destination.mint == market.mint @ ErrorCode::InvalidMarketAccount
+    )]
+    pub destination: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+    
+    #[account(
+        mut,
+        seeds = [b"market", market.mint.as_ref()],
+        bump = market.bump
+    )]
+    pub market: Account<'info, Market>,
+    
+    #[account(
+        mut,
+        address = market.vault @ ErrorCode::InvalidMarketAccount
+    )]
+    pub market_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"obligation", market.key().as_ref(), authority.key().as_ref()],
+        bump = obligation.bump
+    )]
+    pub obligation: Account<'info, Obligation>,
+}
+
+// The first account follows the ars-reserve strategy adapter layout
+#[derive(Accounts)]
+pub struct ReportValue<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        seeds = [b"market", market.mint.as_ref()],
+        bump = market.bump
+    )]
+    pub market: Account<'info, Market>,
+    
+    #[account(address = market.vault @ ErrorCode::InvalidMarketAccount)]
+    pub market_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        seeds = [b"obligation", market.key().as_ref(), authority.key().as_ref()],
+        bump = obligation.bump
+    )]
+    pub obligation: Account<'info, Obligation>,
+}
+
+#[derive(Accounts)]
+pub struct SimulateLoss<'info> {
+    #[account(
+        seeds = [b"market", market.mint.as_ref()],
+        bump = market.bump,
+        has_one = authority @ ErrorCode::InvalidMarketAccount
+    )]
+    pub market: Account<'info, Market>,
+    
+    #[account(
+        mut,
+        address = market.vault @ ErrorCode::InvalidMarketAccount
+    )]
+    pub market_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = destination.mint == market.mint @ ErrorCode::InvalidMarketAccount
+    )]
+    pub destination: Account<'info, TokenAccount>,
+    
+    pub authority: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Arithmetic overflow occurred")]
+    ArithmeticOverflow,
+    
+    #[msg("Invalid amount")]
+    InvalidAmount,
+    
+    #[msg("Account does not belong to the market")]
+    InvalidMarketAccount,
+}
@@ -0,0 +1,404 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::account_info::next_account_info;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+
+// Deployed at the Percolator program ID so ars-core's hard-coded CPI target resolves to it
+declare_id!("46iB4ET4WpqfTXAqGSmyBczLBgVhd1sHre93KtU3sTg9");
+
+/// Percolator instruction tags, matching ars-core's percolator_integration
+pub const DEPOSIT_COLLATERAL_TAG: u8 = 3;
+pub const WITHDRAW_COLLATERAL_TAG: u8 = 4;
+pub const TRADE_NO_CPI_TAG: u8 = 5;
+pub const PUSH_ORACLE_PRICE_TAG: u8 = 14;
+
+/// Account table capacity of a slab
+pub const MAX_ACCOUNTS: usize = 16;
+
+/// Capital required against position notional (10% = 10x leverage)
+pub const INITIAL_MARGIN_BPS: u128 = 1000;
+
+#[program]
+pub mod mock_percolator {
+    use super::*;
+
+    pub fn initialize_slab(
+        ctx: Context<InitializeSlab>,
+        oracle_authority: Pubkey,
+        initial_price_e6: u64,
+    ) -> Result<()> {
+        let slab = &mut ctx.accounts.slab;
+        slab.admin = ctx.accounts.admin.key();
+        slab.mint = ctx.accounts.mint.key();
+        slab.vault = ctx.accounts.vault.key();
+        slab.oracle_authority = oracle_authority;
+        slab.price_e6 = initial_price_e6;
+        slab.vault_bump = ctx.bumps.vault_authority;
+        slab.accounts = Vec::new();
+        
+        Ok(())
+    }
+
+    /// Register a user or LP account; its index in the table is what the wire format addresses
+    pub fn init_account(ctx: Context<InitAccount>, owner: Pubkey, is_lp: bool) -> Result<()> {
+        let slab = &mut ctx.accounts.slab;
+        require!(slab.accounts.len() < MAX_ACCOUNTS, ErrorCode::SlabFull);
+        
+        slab.accounts.push(PercolatorAccount {
+            owner,
+            is_lp,
+            capital: 0,
+            position: 0,
+        });
+        
+        Ok(())
+    }
+
+    /// Percolator instructions are a one-byte tag followed by little-endian arguments,
+    /// not Anchor discriminators, so they all land here
+    pub fn fallback<'info>(
+        program_id: &Pubkey,
+        accounts: &'info [AccountInfo<'info>],
+        data: &[u8],
+    ) -> Result<()> {
+        let (tag, args) = data.split_first().ok_or(ErrorCode::InvalidInstruction)?;
+        
+        match *tag {
+            DEPOSIT_COLLATERAL_TAG => deposit_collateral(program_id, accounts, args),
+            WITHDRAW_COLLATERAL_TAG => withdraw_collateral(program_id, accounts, args),
+            TRADE_NO_CPI_TAG => trade_nocpi(program_id, accounts, args),
+            PUSH_ORACLE_PRICE_TAG => push_oracle_price(program_id, accounts, args),
+            _ => err!(ErrorCode::InvalidInstruction),
+        }
+    }
+}
+
+/// Tag 3: [user_idx: u16, amount: u64]
+/// Accounts: slab (w), vault (w), source (w), owner (signer), token_program
+fn deposit_collateral<'info>(
+    program_id: &Pubkey,
+    accounts: &'info [AccountInfo<'info>],
+    args: &[u8],
+) -> Result<()> {
+    require!(args.len() == 10, ErrorCode::InvalidInstruction);
+    let user_idx = read_u16(&args[0..2]);
+    let amount = read_u64(&args[2..10]);
+    require!(amount > 0, ErrorCode::InvalidAmount);
+    
+    let accounts_iter = &mut accounts.iter();
+    let slab_info = next_account_info(accounts_iter)?;
+    let vault_info = next_account_info(accounts_iter)?;
+    let source_info = next_account_info(accounts_iter)?;
+    let owner_info = next_account_info(accounts_iter)?;
+    let token_program_info = next_account_info(accounts_iter)?;
+    
+    let mut slab = load_slab(slab_info)?;
+    require_keys_eq!(vault_info.key(), slab.vault, ErrorCode::InvalidSlabAccount);
+    require_keys_eq!(token_program_info.key(), token::ID, ErrorCode::InvalidSlabAccount);
+    
+    let account = slab.account_mut(user_idx)?;
+    require!(
+        owner_info.is_signer && owner_info.key() == account.owner,
+        ErrorCode::Unauthorized
+    );
+    
+    token::transfer(
+        CpiContext::new(
+            token_program_info.clone(),
+            Transfer {
+                from: source_info.clone(),
+                to: vault_info.clone(),
+                authority: owner_info.clone(),
+            },
+        ),
+        amount,
+    )?;
+    
+    account.capital = account.capital
+        .checked_add(amount)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+    
+    slab.exit(program_id)
+}
+
+/// Tag 4: [user_idx: u16, amount: u64]
+/// Accounts: slab (w), vault (w), vault_authority, destination (w), oracle, owner (signer), token_program
+fn withdraw_collateral<'info>(
+    program_id: &Pubkey,
+    accounts: &'info [AccountInfo<'info>],
+    args: &[u8],
+) -> Result<()> {
+    require!(args.len() == 10, ErrorCode::InvalidInstruction);
+    let user_idx = read_u16(&args[0..2]);
+    let amount = read_u64(&args[2..10]);
+    require!(amount > 0, ErrorCode::InvalidAmount);
+    
+    let accounts_iter = &mut accounts.iter();
+    let slab_info = next_account_info(accounts_iter)?;
+    let vault_info = next_account_info(accounts_iter)?;
+    let vault_authority_info = next_account_info(accounts_iter)?;
+    let destination_info = next_account_info(accounts_iter)?;
+    // The mock prices off the pushed slab price, so the oracle account is only positional
+    let _oracle_info = next_account_info(accounts_iter)?;
+    let owner_info = next_account_info(accounts_iter)?;
+    let token_program_info = next_account_info(accounts_iter)?;
+    
+    let mut slab = load_slab(slab_info)?;
+    require_keys_eq!(vault_info.key(), slab.vault, ErrorCode::InvalidSlabAccount);
+    require_keys_eq!(token_program_info.key(), token::ID, ErrorCode::InvalidSlabAccount);
+    
+    let slab_key = slab_info.key();
+    let vault_seeds = &[
+        b"vault",
+        slab_key.as_ref(),
+        &[slab.vault_bump],
+    ];
+    let vault_authority = Pubkey::create_program_address(vault_seeds, program_id)
+        .map_err(|_| error!(ErrorCode::InvalidSlabAccount))?;
+    require_keys_eq!(vault_authority_info.key(), vault_authority, ErrorCode::InvalidSlabAccount);
+    
+    let price_e6 = slab.price_e6;
+    let account = slab.account_mut(user_idx)?;
+    require!(
+        owner_info.is_signer && owner_info.key() == account.owner,
+        ErrorCode::Unauthorized
+    );
+    
+    account.capital = account.capital
+        .checked_sub(amount)
+        .ok_or(ErrorCode::InsufficientCollateral)?;
+    account.require_margin(price_e6)?;
+    
+    token::transfer(
+        CpiContext::new_with_signer(
+            token_program_info.clone(),
+            Transfer {
+                from: vault_info.clone(),
+                to: destination_info.clone(),
+                authority: vault_authority_info.clone(),
+            },
+            &[&vault_seeds[..]],
+        ),
+        amount,
+    )?;
+    
+    slab.exit(program_id)
+}
+
+/// Tag 5: [user_idx: u16, lp_idx: u16, size: i128]
+/// Accounts: slab (w), oracle, owner (signer)
+///
+/// The LP takes the other side unconditionally; only the user's margin is checked.
+fn trade_nocpi<'info>(
+    program_id: &Pubkey,
+    accounts: &'info [AccountInfo<'info>],
+    args: &[u8],
+) -> Result<()> {
+    require!(args.len() == 20, ErrorCode::InvalidInstruction);
+    let user_idx = read_u16(&args[0..2]);
+    let lp_idx = read_u16(&args[2..4]);
+    let size = i128::from_le_bytes(args[4..20].try_into().unwrap());
+    require!(size != 0, ErrorCode::InvalidAmount);
+    require!(user_idx != lp_idx, ErrorCode::InvalidAccountIndex);
+    
+    let accounts_iter = &mut accounts.iter();
+    let slab_info = next_account_info(accounts_iter)?;
+    let _oracle_info = next_account_info(accounts_iter)?;
+    let owner_info = next_account_info(accounts_iter)?;
+    
+    let mut slab = load_slab(slab_info)?;
+    let price_e6 = slab.price_e6;
+    require!(price_e6 > 0, ErrorCode::InvalidPrice);
+    
+    let lp = slab.account_mut(lp_idx)?;
+    require!(lp.is_lp, ErrorCode::InvalidAccountIndex);
+    lp.position = lp.position
+        .checked_sub(size)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+    
+    let user = slab.account_mut(user_idx)?;
+    require!(!user.is_lp, ErrorCode::InvalidAccountIndex);
+    require!(
+        owner_info.is_signer && owner_info.key() == user.owner,
+        ErrorCode::Unauthorized
+    );
+    user.position = user.position
+        .checked_add(size)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+    user.require_margin(price_e6)?;
+    
+    slab.exit(program_id)
+}
+
+/// Tag 14: [price_e6: u64]
+/// Accounts: slab (w), oracle_authority (signer)
+fn push_oracle_price<'info>(
+    program_id: &Pubkey,
+    accounts: &'info [AccountInfo<'info>],
+    args: &[u8],
+) -> Result<()> {
+    require!(args.len() == 8, ErrorCode::InvalidInstruction);
+    let price_e6 = read_u64(args);
+    require!(price_e6 > 0, ErrorCode::InvalidPrice);
+    
+    let accounts_iter = &mut accounts.iter();
+    let slab_info = next_account_info(accounts_iter)?;
+    let authority_info = next_account_info(accounts_iter)?;
+    
+    let mut slab = load_slab(slab_info)?;
+    require!(
+        authority_info.is_signer && authority_info.key() == slab.oracle_authority,
+        ErrorCode::Unauthorized
+    );
+    
+    slab.price_e6 = price_e6;
+    
+    slab.exit(program_id)
+}
+
+fn load_slab<'info>(slab_info: &'info AccountInfo<'info>) -> Result<Account<'info, Slab>> {
+    require!(slab_info.is_writable, ErrorCode::InvalidSlabAccount);
+    Account::try_from(slab_info)
+}
+
+fn read_u16(bytes: &[u8]) -> u16 {
+    u16::from_le_bytes(bytes.try_into().unwrap())
+}
+
+fn read_u64(bytes: &[u8]) -> u64 {
+    u64::from_le_bytes(bytes.try_into().unwrap())
+}
+
+#[account]
+pub struct Slab {
+    pub admin: Pubkey,
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+    pub oracle_authority: Pubkey,
+    pub price_e6: u64,
+    pub vault_bump: u8,
+    pub accounts: Vec<PercolatorAccount>,
+}
+
+impl Slab {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // admin
+        32 + // mint
+        32 + // vault
+        32 + // oracle_authority
+        8 + // price_e6
+        1 + // vault_bump
+        4 + MAX_ACCOUNTS * PercolatorAccount::LEN; // accounts
+    
+    pub fn account_mut(&mut self, idx: u16) -> Result<&mut PercolatorAccount> {
+        self.accounts
+            .get_mut(idx as usize)
+            .ok_or(error!(ErrorCode::InvalidAccountIndex))
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct PercolatorAccount {
+    pub owner: Pubkey,
+    pub is_lp: bool,
+    pub capital: u64,
+    pub position: i128,
+}
+
+impl PercolatorAccount {
+    pub const LEN: usize = 32 + // owner
+        1 + // is_lp
+        8 + // capital
+        16; // position
+    
+    /// Capital must cover INITIAL_MARGIN_BPS of the position's notional at `price_e6`
+    pub fn require_margin(&self, price_e6: u64) -> Result<()> {
+        let notional = self.position
+            .unsigned_abs()
+            .checked_mul(price_e6 as u128)
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            / 1_000_000;
+        let required = notional
+            .checked_mul(INITIAL_MARGIN_BPS)
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            / 10000;
+        
+        require!(self.capital as u128 >= required, ErrorCode::InsufficientMargin);
+        
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct InitializeSlab<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = Slab::LEN
+    )]
+    pub slab: Account<'info, Slab>,
+    
+    pub mint: Account<'info, Mint>,
+    
+    /// CHECK: PDA that owns the vault; signs withdrawals
+    #[account(
+        seeds = [b"vault", slab.key().as_ref()],
+        bump
+    )]
+    pub vault_authority: AccountInfo<'info>,
+    
+    #[account(
+        constraint = vault.mint == mint.key() @ ErrorCode::InvalidSlabAccount,
+        constraint = vault.owner == vault_authority.key() @ ErrorCode::InvalidSlabAccount
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitAccount<'info> {
+    #[account(
+        mut,
+        has_one = admin @ ErrorCode::Unauthorized
+    )]
+    pub slab: Account<'info, Slab>,
+    
+    pub admin: Signer<'info>,
+}
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Arithmetic overflow occurred")]
+    ArithmeticOverflow,
+    
+    #[msg("Invalid amount")]
+    InvalidAmount,
+    
+    #[msg("Unknown instruction tag or malformed arguments")]
+    InvalidInstruction,
+    
+    #[msg("Account does not belong to the slab")]
+    InvalidSlabAccount,
+    
+    #[msg("No such account in the slab, or wrong account kind")]
+    InvalidAccountIndex,
+    
+    #[msg("Slab account table is full")]
+    SlabFull,
+    
+    #[msg("Signer does not own the account or is not the oracle authority")]
+    Unauthorized,
+    
+    #[msg("Withdrawal exceeds account capital")]
+    InsufficientCollateral,
+    
+    #[msg("Capital below initial margin for the position")]
+    InsufficientMargin,
+    
+    #[msg("Oracle price must be positive")]
+    InvalidPrice,
+}
@@ -1,6 +1,7 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { ArsCore } from "../target/types/ars_core";
+import { ArsToken } from "../target/types/ars_token";
 import { expect } from "chai";
 import { Keypair, PublicKey, SystemProgram, LAMPORTS_PER_SOL } from "@solana/web3.js";
 import { TOKEN_PROGRAM_ID, createMint, createAccount, mintTo } from "@solana/spl-token";
@@ -10,6 +11,7 @@ describe("ars-core unit tests", () => {
   anchor.setProvider(provider);
 
   const program = anchor.workspace.ArsCore as Program<ArsCore>;
+  const tokenProgram = anchor.workspace.ArsToken as Program<ArsToken>;
   
   let globalState: PublicKey;
   let iliOracle: PublicKey;
@@ -49,6 +51,25 @@ describe("ars-core unit tests", () => {
     );
   });
 
+  const checkpointsPda = (owner: PublicKey): PublicKey => {
+    const [mintState] = PublicKey.findProgramAddressSync(
+      [Buffer.from("mint_state"), authority.publicKey.toBuffer()],
+      tokenProgram.programId
+    );
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("checkpoints"), mintState.toBuffer(), owner.toBuffer()],
+      tokenProgram.programId
+    )[0];
+  };
+
+  const voteRecordPda = async (proposal: PublicKey, voter: PublicKey): Promise<PublicKey> => {
+    const proposalAccount = await program.account.policyProposal.fetch(proposal);
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("vote_record"), proposalAccount.id.toArrayLike(Buffer, "le", 8), voter.toBuffer()],
+      program.programId
+    )[0];
+  };
+
   describe("initialize", () => {
     it("should initialize protocol with valid parameters", async () => {
       const epochDuration = new anchor.BN(86400); // 24 hours
@@ -72,7 +93,7 @@ describe("ars-core unit tests", () => {
       expect(globalStateAccount.authority.toString()).to.equal(authority.publicKey.toString());
       expect(globalStateAccount.epochDuration.toNumber()).to.equal(86400);
       expect(globalStateAccount.mintBurnCapBps).to.equal(200);
-      expect(globalStateAccount.vhrThreshold).to.equal(15000);
+      expect(globalStateAccount.vhrThreshold.toNumber()).to.equal(15000);
       expect(globalStateAccount.circuitBreakerActive).to.be.false;
     });
 
@@ -98,6 +119,56 @@ describe("ars-core unit tests", () => {
         expect(error.toString()).to.include("InvalidEpochDuration");
       }
     });
+
+    it("should fail with VHR threshold below 100%", async () => {
+      try {
+        await program.methods
+          .initialize(new anchor.BN(86400), 200, 9999)
+          .accounts({
+            globalState,
+            iliOracle,
+            authority: authority.publicKey,
+            reserveVault: reserveVault.publicKey,
+            aruMint,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([authority])
+          .rpc();
+        
+        expect.fail("Should have thrown error");
+      } catch (error) {
+        expect(error.toString()).to.include("InvalidVHRThreshold");
+      }
+    });
+  });
+
+  describe("migrate_global_state", () => {
+    it("should refuse to migrate a global state already on the current layout", async () => {
+      const before = await provider.connection.getAccountInfo(globalState);
+
+      try {
+        await program.methods
+          .migrateGlobalState()
+          .accounts({
+            globalState,
+            authority: authority.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([authority])
+          .rpc();
+
+        expect.fail("Should have thrown error");
+      } catch (error) {
+        expect(error.toString()).to.include("AlreadyMigrated");
+      }
+
+      const after = await provider.connection.getAccountInfo(globalState);
+      expect(after.data.equals(before.data)).to.be.true;
+
+      // The widened threshold reads back unchanged from the current layout
+      const globalStateAccount = await program.account.globalState.fetch(globalState);
+      expect(globalStateAccount.vhrThreshold.toNumber()).to.equal(15000);
+    });
   });
 
   describe("admin transfer", () => {
@@ -287,6 +358,7 @@ describe("ars-core unit tests", () => {
           iliOracle,
           globalState,
           agentRegistry: agentRegistry1,
+          rewardPool: null,
           agent: agent1.publicKey,
         })
         .signers([agent1])
@@ -316,6 +388,7 @@ describe("ars-core unit tests", () => {
             iliOracle,
             globalState,
             agentRegistry: agentRegistry1,
+            rewardPool: null,
             agent: agent1.publicKey,
           })
           .signers([agent1])
@@ -354,7 +427,7 @@ describe("ars-core unit tests", () => {
       const votingPeriod = new anchor.BN(86400); // 24 hours
 
       await program.methods
-        .createProposal(policyType, Array.from(policyParams), votingPeriod)
+        .createProposal(policyType, Array.from(policyParams), votingPeriod, { quadraticStake: {} })
         .accounts({
           globalState,
           proposal,
@@ -374,7 +447,7 @@ describe("ars-core unit tests", () => {
       
       try {
         await program.methods
-          .createProposal({ mintAru: {} }, [1, 2, 3], invalidVotingPeriod)
+          .createProposal({ mintAru: {} }, [1, 2, 3], invalidVotingPeriod, { quadraticStake: {} })
           .accounts({
             globalState,
             proposal,
@@ -391,15 +464,20 @@ describe("ars-core unit tests", () => {
     });
 
     it("should cast vote with quadratic voting power", async () => {
-      const stakeAmount = new anchor.BN(10_000_000_000); // 10,000 ARU
+      // Voting power comes from the voter's ars-token stake checkpoint at the
+      // proposal snapshot slot (10,000 ARU staked before proposal creation)
       const voteYes = true;
 
       await program.methods
-        .voteOnProposal(voteYes, stakeAmount)
+        .voteOnProposal(voteYes)
         .accounts({
+          globalState,
           proposal,
           agentRegistry: proposerRegistry,
+          voterCheckpoints: checkpointsPda(proposer.publicKey),
+          voteRecord: await voteRecordPda(proposal, proposer.publicKey),
           voter: proposer.publicKey,
+          systemProgram: SystemProgram.programId,
         })
         .signers([proposer])
         .rpc();
@@ -409,6 +487,142 @@ describe("ars-core unit tests", () => {
       // Quadratic voting power = sqrt(10,000,000,000) = 100,000
       expect(proposalAccount.quadraticYes.toNumber()).to.be.greaterThan(0);
     });
+
+    it("should reject a second vote from the same voter", async () => {
+      try {
+        await program.methods
+          .voteOnProposal(false)
+          .accounts({
+            globalState,
+            proposal,
+            agentRegistry: proposerRegistry,
+            voterCheckpoints: checkpointsPda(proposer.publicKey),
+            voteRecord: await voteRecordPda(proposal, proposer.publicKey),
+            voter: proposer.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([proposer])
+          .rpc();
+
+        expect.fail("Should have thrown error");
+      } catch (error) {
+        expect(error.toString()).to.include("already in use");
+      }
+    });
+
+    it("should require a veARU lock to vote on vote-escrow proposals", async () => {
+      const globalStateAccount = await program.account.globalState.fetch(globalState);
+      const [veProposal] = PublicKey.findProgramAddressSync(
+        [Buffer.from("proposal"), globalStateAccount.proposalCounter.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      );
+
+      await program.methods
+        .createProposal({ rebalanceVault: {} }, [], new anchor.BN(86400), { voteEscrow: {} })
+        .accounts({
+          globalState,
+          proposal: veProposal,
+          proposer: proposer.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([proposer])
+        .rpc();
+
+      try {
+        await program.methods
+          .voteOnProposal(true)
+          .accounts({
+            globalState,
+            proposal: veProposal,
+            agentRegistry: proposerRegistry,
+            voterCheckpoints: checkpointsPda(proposer.publicKey),
+            voteEscrow: null,
+            voteRecord: await voteRecordPda(veProposal, proposer.publicKey),
+            voter: proposer.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([proposer])
+          .rpc();
+
+        expect.fail("Should have thrown error");
+      } catch (error) {
+        expect(error.toString()).to.include("InvalidVotingPowerSource");
+      }
+    });
+
+    it("should record the snapshot slot at proposal creation", async () => {
+      const proposalAccount = await program.account.policyProposal.fetch(proposal);
+      const currentSlot = await provider.connection.getSlot();
+      expect(proposalAccount.snapshotSlot.toNumber()).to.be.lessThan(currentSlot);
+    });
+
+    it("should fail to execute proposal before voting period ends", async () => {
+      try {
+        await program.methods
+          .executeProposal()
+          .accounts({
+            globalState,
+            proposal,
+            mintState: null,
+          })
+          .rpc();
+
+        expect.fail("Should have thrown error");
+      } catch (error) {
+        expect(error.toString()).to.include("VotingPeriodNotEnded");
+      }
+    });
+
+    it("should reject UpdateParameters proposal with undecodable params", async () => {
+      const globalStateAccount = await program.account.globalState.fetch(globalState);
+      const [nextProposal] = PublicKey.findProgramAddressSync(
+        [Buffer.from("proposal"), globalStateAccount.proposalCounter.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      );
+
+      try {
+        await program.methods
+          .createProposal({ updateParameters: {} }, [99, 1], new anchor.BN(86400), { quadraticStake: {} })
+          .accounts({
+            globalState,
+            proposal: nextProposal,
+            proposer: proposer.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([proposer])
+          .rpc();
+
+        expect.fail("Should have thrown error");
+      } catch (error) {
+        expect(error.toString()).to.include("InvalidPolicyParams");
+      }
+    });
+
+    it("should accept a stability fee UpdateParameters proposal", async () => {
+      const globalStateAccount = await program.account.globalState.fetch(globalState);
+      const [nextProposal] = PublicKey.findProgramAddressSync(
+        [Buffer.from("proposal"), globalStateAccount.proposalCounter.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      );
+
+      // ParameterUpdate::StabilityFeeBps(50): variant 0, u16 LE
+      const policyParams = [0, 50, 0];
+
+      await program.methods
+        .createProposal({ updateParameters: {} }, policyParams, new anchor.BN(86400), { quadraticStake: {} })
+        .accounts({
+          globalState,
+          proposal: nextProposal,
+          proposer: proposer.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([proposer])
+        .rpc();
+
+      const proposalAccount = await program.account.policyProposal.fetch(nextProposal);
+      expect(proposalAccount.policyType).to.deep.equal({ updateParameters: {} });
+      expect(Array.from(proposalAccount.policyParams)).to.deep.equal(policyParams);
+    });
   });
 
   describe("circuit breaker", () => {
@@ -540,4 +754,124 @@ describe("ars-core unit tests", () => {
       }
     });
   });
+
+  describe("agent rewards", () => {
+    let rewardPool: PublicKey;
+    let rewardVault: PublicKey;
+
+    before(async () => {
+      [rewardPool] = PublicKey.findProgramAddressSync(
+        [Buffer.from("agent_rewards")],
+        program.programId
+      );
+      [rewardVault] = PublicKey.findProgramAddressSync(
+        [Buffer.from("agent_reward_vault")],
+        program.programId
+      );
+    });
+
+    it("should fail to initialize the reward pool with unauthorized signer", async () => {
+      const unauthorized = Keypair.generate();
+
+      try {
+        await program.methods
+          .initializeAgentRewardPool()
+          .accounts({
+            globalState,
+            rewardPool,
+            rewardVault,
+            aruMint,
+            authority: unauthorized.publicKey,
+          })
+          .signers([unauthorized])
+          .rpc();
+
+        expect.fail("Should have thrown error");
+      } catch (error) {
+        expect(error.toString()).to.include("Unauthorized");
+      }
+    });
+
+    it("should initialize an empty reward pool", async () => {
+      await program.methods
+        .initializeAgentRewardPool()
+        .accounts({
+          globalState,
+          rewardPool,
+          rewardVault,
+          aruMint,
+          authority: authority.publicKey,
+        })
+        .signers([authority])
+        .rpc();
+
+      const pool = await program.account.agentRewardPool.fetch(rewardPool);
+      expect(pool.rewardVault.toString()).to.equal(rewardVault.toString());
+      expect(pool.unallocated.toNumber()).to.equal(0);
+      expect(pool.rewardPerUpdate.toNumber()).to.equal(0);
+    });
+
+    it("should fail to claim with no pending rewards", async () => {
+      const agent = Keypair.generate();
+      const [agentRegistry] = PublicKey.findProgramAddressSync(
+        [Buffer.from("agent"), agent.publicKey.toBuffer()],
+        program.programId
+      );
+
+      try {
+        await program.methods
+          .claimAgentRewards()
+          .accounts({
+            rewardPool,
+            rewardVault,
+            agentRegistry,
+            agentTokenAccount: rewardVault,
+            agent: agent.publicKey,
+          })
+          .signers([agent])
+          .rpc();
+
+        expect.fail("Should have thrown error");
+      } catch (error) {
+        expect(error.toString()).to.match(/NoRewardsToClaim|AccountNotInitialized/);
+      }
+    });
+  });
+
+  describe("global settlement", () => {
+    it("should start with settlement inactive", async () => {
+      const globalStateAccount = await program.account.globalState.fetch(globalState);
+      expect(globalStateAccount.settlementActive).to.be.false;
+      expect(globalStateAccount.settlementStartedAt.toNumber()).to.equal(0);
+    });
+
+    it("should fail to initiate settlement with unauthorized signer", async () => {
+      const unauthorized = Keypair.generate();
+      const [mintState] = PublicKey.findProgramAddressSync(
+        [Buffer.from("mint_state"), authority.publicKey.toBuffer()],
+        tokenProgram.programId
+      );
+
+      try {
+        await program.methods
+          .initiateGlobalSettlement()
+          .accounts({
+            globalState,
+            mintState,
+            iliOracle,
+            authority: unauthorized.publicKey,
+            arsTokenProgram: tokenProgram.programId,
+          })
+          .signers([unauthorized])
+          .rpc();
+
+        expect.fail("Should have thrown error");
+      } catch (error) {
+        expect(error.toString()).to.match(/Unauthorized|AccountNotInitialized|InvalidAsset/);
+      }
+
+      const globalStateAccount = await program.account.globalState.fetch(globalState);
+      expect(globalStateAccount.settlementActive).to.be.false;
+    });
+  });
 });
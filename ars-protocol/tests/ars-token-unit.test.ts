@@ -505,4 +505,937 @@ describe("ars-token unit tests", () => {
       }
     });
   });
+
+  describe("savings vault", () => {
+    let savingsVault: PublicKey;
+    let shareMint: PublicKey;
+    let savingsAru: PublicKey;
+    let userAru: PublicKey;
+    let userShares: PublicKey;
+
+    before(async () => {
+      [savingsVault] = PublicKey.findProgramAddressSync(
+        [Buffer.from("savings_vault"), mintState.toBuffer()],
+        program.programId
+      );
+      [shareMint] = PublicKey.findProgramAddressSync(
+        [Buffer.from("share_mint"), savingsVault.toBuffer()],
+        program.programId
+      );
+      [savingsAru] = PublicKey.findProgramAddressSync(
+        [Buffer.from("savings_aru"), savingsVault.toBuffer()],
+        program.programId
+      );
+
+      userAru = await createAccount(
+        provider.connection,
+        authority,
+        aruMint,
+        authority.publicKey,
+        Keypair.generate()
+      );
+
+      await program.methods
+        .mintAru(new anchor.BN(2_000_000)) // 2 ARU
+        .accounts({
+          mintState,
+          aruMint,
+          destination: userAru,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+    });
+
+    it("should fail to initialize savings vault with invalid rate", async () => {
+      try {
+        await program.methods
+          .initializeSavingsVault(15000)
+          .accounts({
+            mintState,
+            savingsVault,
+            shareMint,
+            aruVault: savingsAru,
+            aruMint,
+            authority: authority.publicKey,
+          })
+          .signers([authority])
+          .rpc();
+
+        expect.fail("Should have thrown error");
+      } catch (error) {
+        expect(error.toString()).to.include("InvalidSavingsRate");
+      }
+    });
+
+    it("should initialize savings vault at a 1:1 exchange rate", async () => {
+      await program.methods
+        .initializeSavingsVault(400) // 4% APR
+        .accounts({
+          mintState,
+          savingsVault,
+          shareMint,
+          aruVault: savingsAru,
+          aruMint,
+          authority: authority.publicKey,
+        })
+        .signers([authority])
+        .rpc();
+
+      const vaultAccount = await program.account.savingsVault.fetch(savingsVault);
+      expect(vaultAccount.savingsRateBps).to.equal(400);
+      expect(vaultAccount.exchangeRate.toString()).to.equal("1000000000000");
+      expect(vaultAccount.totalShares.toNumber()).to.equal(0);
+
+      userShares = await createAccount(
+        provider.connection,
+        authority,
+        shareMint,
+        authority.publicKey
+      );
+    });
+
+    it("should issue sARU shares on deposit", async () => {
+      await program.methods
+        .depositSavings(new anchor.BN(1_000_000))
+        .accounts({
+          savingsVault,
+          shareMint,
+          aruVault: savingsAru,
+          userAruAccount: userAru,
+          userShareAccount: userShares,
+          user: authority.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([authority])
+        .rpc();
+
+      const vaultAccount = await program.account.savingsVault.fetch(savingsVault);
+      const shares = await getAccount(provider.connection, userShares);
+      expect(Number(shares.amount)).to.equal(vaultAccount.totalShares.toNumber());
+      expect(vaultAccount.totalShares.toNumber()).to.be.at.most(1_000_000);
+    });
+
+    it("should never grow the exchange rate beyond funded ARU", async () => {
+      await program.methods
+        .updateSavingsRate(800)
+        .accounts({
+          mintState,
+          savingsVault,
+          aruVault: savingsAru,
+          authority: authority.publicKey,
+        })
+        .signers([authority])
+        .rpc();
+
+      const vaultAccount = await program.account.savingsVault.fetch(savingsVault);
+      const funded = await getAccount(provider.connection, savingsAru);
+      const owed = vaultAccount.totalShares
+        .mul(vaultAccount.exchangeRate)
+        .div(new anchor.BN("1000000000000"));
+      expect(owed.lte(new anchor.BN(funded.amount.toString()))).to.be.true;
+      expect(vaultAccount.savingsRateBps).to.equal(800);
+    });
+
+    it("should reject savings rate updates from non-authority", async () => {
+      const attacker = Keypair.generate();
+
+      try {
+        await program.methods
+          .updateSavingsRate(10000)
+          .accounts({
+            mintState,
+            savingsVault,
+            aruVault: savingsAru,
+            authority: attacker.publicKey,
+          })
+          .signers([attacker])
+          .rpc();
+
+        expect.fail("Should have thrown error");
+      } catch (error) {
+        expect(error.toString()).to.include("Unauthorized");
+      }
+    });
+
+    it("should redeem shares for ARU at the current exchange rate", async () => {
+      const sharesBefore = await getAccount(provider.connection, userShares);
+      const aruBefore = await getAccount(provider.connection, userAru);
+
+      await program.methods
+        .withdrawSavings(new anchor.BN(sharesBefore.amount.toString()))
+        .accounts({
+          savingsVault,
+          shareMint,
+          aruVault: savingsAru,
+          userAruAccount: userAru,
+          userShareAccount: userShares,
+          user: authority.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([authority])
+        .rpc();
+
+      const sharesAfter = await getAccount(provider.connection, userShares);
+      const aruAfter = await getAccount(provider.connection, userAru);
+      expect(Number(sharesAfter.amount)).to.equal(0);
+      expect(Number(aruAfter.amount)).to.be.at.least(Number(aruBefore.amount));
+    });
+  });
+
+  describe("stake checkpoints", () => {
+    let stakeVault: PublicKey;
+    let checkpoints: PublicKey;
+    let ownerAru: PublicKey;
+
+    before(async () => {
+      [stakeVault] = PublicKey.findProgramAddressSync(
+        [Buffer.from("stake_vault"), mintState.toBuffer()],
+        program.programId
+      );
+      [checkpoints] = PublicKey.findProgramAddressSync(
+        [Buffer.from("checkpoints"), mintState.toBuffer(), authority.publicKey.toBuffer()],
+        program.programId
+      );
+
+      ownerAru = await createAccount(
+        provider.connection,
+        authority,
+        aruMint,
+        authority.publicKey,
+        Keypair.generate()
+      );
+
+      await program.methods
+        .mintAru(new anchor.BN(3_000_000))
+        .accounts({
+          mintState,
+          aruMint,
+          destination: ownerAru,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+
+      await program.methods
+        .initializeStakeVault()
+        .accounts({
+          mintState,
+          stakeVault,
+          aruMint,
+          authority: authority.publicKey,
+        })
+        .signers([authority])
+        .rpc();
+
+      await program.methods
+        .initializeCheckpoints()
+        .accounts({
+          mintState,
+          checkpoints,
+          owner: authority.publicKey,
+        })
+        .signers([authority])
+        .rpc();
+    });
+
+    it("should record a checkpoint on stake", async () => {
+      await program.methods
+        .stake(new anchor.BN(2_000_000))
+        .accounts({
+          mintState,
+          checkpoints,
+          stakeVault,
+          ownerAruAccount: ownerAru,
+          owner: authority.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([authority])
+        .rpc();
+
+      const account = await program.account.balanceCheckpoints.fetch(checkpoints);
+      expect(account.checkpoints.length).to.equal(1);
+      expect(account.checkpoints[0].balance.toNumber()).to.equal(2_000_000);
+      expect(account.aruMint.toString()).to.equal(aruMint.toString());
+    });
+
+    it("should record a lower checkpoint on unstake", async () => {
+      await program.methods
+        .unstake(new anchor.BN(500_000))
+        .accounts({
+          mintState,
+          checkpoints,
+          stakeVault,
+          ownerAruAccount: ownerAru,
+          owner: authority.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([authority])
+        .rpc();
+
+      const account = await program.account.balanceCheckpoints.fetch(checkpoints);
+      const latest = account.checkpoints[account.checkpoints.length - 1];
+      expect(latest.balance.toNumber()).to.equal(1_500_000);
+      expect(latest.slot.toNumber()).to.be.at.least(account.checkpoints[0].slot.toNumber());
+    });
+
+    it("should fail to unstake more than the staked balance", async () => {
+      try {
+        await program.methods
+          .unstake(new anchor.BN(10_000_000))
+          .accounts({
+            mintState,
+            checkpoints,
+            stakeVault,
+            ownerAruAccount: ownerAru,
+            owner: authority.publicKey,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .signers([authority])
+          .rpc();
+
+        expect.fail("Should have thrown error");
+      } catch (error) {
+        expect(error.toString()).to.include("InsufficientStake");
+      }
+    });
+  });
+
+  describe("vote escrow", () => {
+    const MAX_LOCK = 4 * 31_536_000;
+    let stakeVault: PublicKey;
+    let voteEscrow: PublicKey;
+    let ownerAru: PublicKey;
+
+    before(async () => {
+      [stakeVault] = PublicKey.findProgramAddressSync(
+        [Buffer.from("stake_vault"), mintState.toBuffer()],
+        program.programId
+      );
+      [voteEscrow] = PublicKey.findProgramAddressSync(
+        [Buffer.from("ve_lock"), mintState.toBuffer(), authority.publicKey.toBuffer()],
+        program.programId
+      );
+
+      ownerAru = await createAccount(
+        provider.connection,
+        authority,
+        aruMint,
+        authority.publicKey,
+        Keypair.generate()
+      );
+
+      await program.methods
+        .mintAru(new anchor.BN(3_000_000))
+        .accounts({
+          mintState,
+          aruMint,
+          destination: ownerAru,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+    });
+
+    it("should reject locks longer than the maximum term", async () => {
+      const now = Math.floor(Date.now() / 1000);
+
+      try {
+        await program.methods
+          .createLock(new anchor.BN(1_000_000), new anchor.BN(now + MAX_LOCK + 86400))
+          .accounts({
+            mintState,
+            voteEscrow,
+            stakeVault,
+            ownerAruAccount: ownerAru,
+            owner: authority.publicKey,
+          })
+          .signers([authority])
+          .rpc();
+
+        expect.fail("Should have thrown error");
+      } catch (error) {
+        expect(error.toString()).to.include("InvalidLockDuration");
+      }
+    });
+
+    it("should create a lock with decaying veARU weight", async () => {
+      const now = Math.floor(Date.now() / 1000);
+      const unlockTime = now + MAX_LOCK / 2;
+
+      await program.methods
+        .createLock(new anchor.BN(1_000_000), new anchor.BN(unlockTime))
+        .accounts({
+          mintState,
+          voteEscrow,
+          stakeVault,
+          ownerAruAccount: ownerAru,
+          owner: authority.publicKey,
+        })
+        .signers([authority])
+        .rpc();
+
+      const lock = await program.account.voteEscrow.fetch(voteEscrow);
+      expect(lock.amount.toNumber()).to.equal(1_000_000);
+      expect(lock.unlockTime.toNumber()).to.equal(unlockTime);
+      expect(lock.checkpoints.length).to.equal(1);
+      expect(lock.checkpoints[0].amount.toNumber()).to.equal(1_000_000);
+      expect(lock.checkpoints[0].unlockTime.toNumber()).to.equal(unlockTime);
+    });
+
+    it("should reject a second lock while one is active", async () => {
+      const now = Math.floor(Date.now() / 1000);
+
+      try {
+        await program.methods
+          .createLock(new anchor.BN(1_000_000), new anchor.BN(now + MAX_LOCK / 2))
+          .accounts({
+            mintState,
+            voteEscrow,
+            stakeVault,
+            ownerAruAccount: ownerAru,
+            owner: authority.publicKey,
+          })
+          .signers([authority])
+          .rpc();
+
+        expect.fail("Should have thrown error");
+      } catch (error) {
+        expect(error.toString()).to.include("LockAlreadyExists");
+      }
+    });
+
+    it("should increase the locked amount", async () => {
+      await program.methods
+        .increaseLockAmount(new anchor.BN(500_000))
+        .accounts({
+          mintState,
+          voteEscrow,
+          stakeVault,
+          ownerAruAccount: ownerAru,
+          owner: authority.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([authority])
+        .rpc();
+
+      const lock = await program.account.voteEscrow.fetch(voteEscrow);
+      expect(lock.amount.toNumber()).to.equal(1_500_000);
+      const latest = lock.checkpoints[lock.checkpoints.length - 1];
+      expect(latest.amount.toNumber()).to.equal(1_500_000);
+    });
+
+    it("should only extend the unlock time forward", async () => {
+      const lock = await program.account.voteEscrow.fetch(voteEscrow);
+
+      try {
+        await program.methods
+          .extendLock(lock.unlockTime.subn(1))
+          .accounts({
+            mintState,
+            voteEscrow,
+            stakeVault,
+            ownerAruAccount: ownerAru,
+            owner: authority.publicKey,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .signers([authority])
+          .rpc();
+
+        expect.fail("Should have thrown error");
+      } catch (error) {
+        expect(error.toString()).to.include("InvalidLockDuration");
+      }
+
+      await program.methods
+        .extendLock(lock.unlockTime.addn(86400))
+        .accounts({
+          mintState,
+          voteEscrow,
+          stakeVault,
+          ownerAruAccount: ownerAru,
+          owner: authority.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([authority])
+        .rpc();
+
+      const extended = await program.account.voteEscrow.fetch(voteEscrow);
+      expect(extended.unlockTime.toNumber()).to.equal(lock.unlockTime.toNumber() + 86400);
+      const latest = extended.checkpoints[extended.checkpoints.length - 1];
+      expect(latest.unlockTime.toNumber()).to.equal(lock.unlockTime.toNumber() + 86400);
+      expect(latest.amount.toNumber()).to.equal(1_500_000);
+    });
+
+    it("should fail to withdraw before unlock", async () => {
+      try {
+        await program.methods
+          .withdrawLock()
+          .accounts({
+            mintState,
+            voteEscrow,
+            stakeVault,
+            ownerAruAccount: ownerAru,
+            owner: authority.publicKey,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .signers([authority])
+          .rpc();
+
+        expect.fail("Should have thrown error");
+      } catch (error) {
+        expect(error.toString()).to.include("LockNotExpired");
+      }
+    });
+  });
+
+  describe("vesting", () => {
+    let beneficiary: Keypair;
+    let vesting: PublicKey;
+    let escrow: PublicKey;
+    let fundingAru: PublicKey;
+    let beneficiaryAru: PublicKey;
+
+    before(async () => {
+      beneficiary = Keypair.generate();
+      [vesting] = PublicKey.findProgramAddressSync(
+        [Buffer.from("vesting"), mintState.toBuffer(), beneficiary.publicKey.toBuffer()],
+        program.programId
+      );
+      [escrow] = PublicKey.findProgramAddressSync(
+        [Buffer.from("vesting_escrow"), vesting.toBuffer()],
+        program.programId
+      );
+
+      fundingAru = await createAccount(
+        provider.connection,
+        authority,
+        aruMint,
+        authority.publicKey,
+        Keypair.generate()
+      );
+      beneficiaryAru = await createAccount(
+        provider.connection,
+        authority,
+        aruMint,
+        beneficiary.publicKey,
+        Keypair.generate()
+      );
+
+      await program.methods
+        .mintAru(new anchor.BN(1_000_000))
+        .accounts({
+          mintState,
+          aruMint,
+          destination: fundingAru,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+    });
+
+    it("should reject a cliff after the end of vesting", async () => {
+      const now = Math.floor(Date.now() / 1000);
+
+      try {
+        await program.methods
+          .createVesting(
+            new anchor.BN(1_000_000),
+            new anchor.BN(now),
+            new anchor.BN(now + 2 * 86400),
+            new anchor.BN(now + 86400),
+            true
+          )
+          .accounts({
+            mintState,
+            vesting,
+            escrow,
+            beneficiary: beneficiary.publicKey,
+            aruMint,
+            fundingAccount: fundingAru,
+            authority: authority.publicKey,
+          })
+          .signers([authority])
+          .rpc();
+
+        expect.fail("Should have thrown error");
+      } catch (error) {
+        expect(error.toString()).to.include("InvalidVestingSchedule");
+      }
+    });
+
+    it("should escrow the grant and release nothing before the cliff", async () => {
+      const now = Math.floor(Date.now() / 1000);
+
+      await program.methods
+        .createVesting(
+          new anchor.BN(1_000_000),
+          new anchor.BN(now),
+          new anchor.BN(now + 86400),
+          new anchor.BN(now + 4 * 86400),
+          true
+        )
+        .accounts({
+          mintState,
+          vesting,
+          escrow,
+          beneficiary: beneficiary.publicKey,
+          aruMint,
+          fundingAccount: fundingAru,
+          authority: authority.publicKey,
+        })
+        .signers([authority])
+        .rpc();
+
+      const escrowAccount = await getAccount(provider.connection, escrow);
+      expect(Number(escrowAccount.amount)).to.equal(1_000_000);
+
+      try {
+        await program.methods
+          .claimVested()
+          .accounts({
+            vesting,
+            escrow,
+            beneficiaryTokenAccount: beneficiaryAru,
+            beneficiary: beneficiary.publicKey,
+          })
+          .signers([beneficiary])
+          .rpc();
+
+        expect.fail("Should have thrown error");
+      } catch (error) {
+        expect(error.toString()).to.include("NothingToClaim");
+      }
+    });
+
+    it("should refund the unvested balance on cancel", async () => {
+      await program.methods
+        .cancelVesting()
+        .accounts({
+          mintState,
+          vesting,
+          escrow,
+          refundAccount: fundingAru,
+          authority: authority.publicKey,
+        })
+        .signers([authority])
+        .rpc();
+
+      const vestingAccount = await program.account.vestingAccount.fetch(vesting);
+      expect(vestingAccount.cancelled).to.be.true;
+      expect(vestingAccount.totalAmount.toNumber()).to.equal(0);
+
+      const funding = await getAccount(provider.connection, fundingAru);
+      expect(Number(funding.amount)).to.equal(1_000_000);
+    });
+  });
+
+  describe("emission schedule", () => {
+    let emissionSchedule: PublicKey;
+    let rewardPool: PublicKey;
+    let distributor: Keypair;
+
+    before(async () => {
+      distributor = Keypair.generate();
+      [emissionSchedule] = PublicKey.findProgramAddressSync(
+        [Buffer.from("emission_schedule"), mintState.toBuffer()],
+        program.programId
+      );
+
+      rewardPool = await createAccount(
+        provider.connection,
+        authority,
+        aruMint,
+        authority.publicKey,
+        Keypair.generate()
+      );
+    });
+
+    it("should reject a decay above 100%", async () => {
+      try {
+        await program.methods
+          .initializeEmissionSchedule(new anchor.BN(10_000), 10001, distributor.publicKey)
+          .accounts({
+            mintState,
+            emissionSchedule,
+            rewardPool,
+            authority: authority.publicKey,
+          })
+          .signers([authority])
+          .rpc();
+
+        expect.fail("Should have thrown error");
+      } catch (error) {
+        expect(error.toString()).to.include("InvalidEmissionDecay");
+      }
+    });
+
+    it("should emit once per epoch and decay the next emission", async () => {
+      await program.methods
+        .initializeEmissionSchedule(new anchor.BN(10_000), 1000, distributor.publicKey)
+        .accounts({
+          mintState,
+          emissionSchedule,
+          rewardPool,
+          authority: authority.publicKey,
+        })
+        .signers([authority])
+        .rpc();
+
+      const before = await program.account.mintState.fetch(mintState);
+
+      await program.methods
+        .emitEpochRewards()
+        .accounts({
+          mintState,
+          emissionSchedule,
+          aruMint,
+          rewardPool,
+          distributor: distributor.publicKey,
+        })
+        .signers([distributor])
+        .rpc();
+
+      // Emissions are bounded by whatever is left of the epoch mint cap
+      const schedule = await program.account.emissionSchedule.fetch(emissionSchedule);
+      expect(schedule.totalEmitted.toNumber()).to.be.at.most(10_000);
+      expect(schedule.emissionPerEpoch.toNumber()).to.equal(9_000);
+
+      const after = await program.account.mintState.fetch(mintState);
+      expect(after.epochEmitted.toNumber()).to.equal(
+        before.epochEmitted.toNumber() + schedule.totalEmitted.toNumber()
+      );
+
+      try {
+        await program.methods
+          .emitEpochRewards()
+          .accounts({
+            mintState,
+            emissionSchedule,
+            aruMint,
+            rewardPool,
+            distributor: distributor.publicKey,
+          })
+          .signers([distributor])
+          .rpc();
+
+        expect.fail("Should have thrown error");
+      } catch (error) {
+        expect(error.toString()).to.include("EmissionAlreadyDone");
+      }
+    });
+  });
+
+  describe("minters", () => {
+    let minter: Keypair;
+    let minterAccount: PublicKey;
+    let destination: PublicKey;
+
+    before(async () => {
+      minter = Keypair.generate();
+      [minterAccount] = PublicKey.findProgramAddressSync(
+        [Buffer.from("minter"), mintState.toBuffer(), minter.publicKey.toBuffer()],
+        program.programId
+      );
+
+      destination = await createAccount(
+        provider.connection,
+        authority,
+        aruMint,
+        authority.publicKey,
+        Keypair.generate()
+      );
+    });
+
+    it("should register a minter with a ceiling", async () => {
+      await program.methods
+        .registerMinter(minter.publicKey, new anchor.BN(1_000_000))
+        .accounts({
+          mintState,
+          minterAccount,
+          authority: authority.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([authority])
+        .rpc();
+
+      const account = await program.account.minter.fetch(minterAccount);
+      expect(account.minter.toString()).to.equal(minter.publicKey.toString());
+      expect(account.mintCeiling.toNumber()).to.equal(1_000_000);
+      expect(account.outstanding.toNumber()).to.equal(0);
+    });
+
+    it("should reject ceiling changes from non-authority", async () => {
+      try {
+        await program.methods
+          .setMinterCeiling(new anchor.BN("18446744073709551615"))
+          .accounts({
+            mintState,
+            minterAccount,
+            authority: minter.publicKey,
+          })
+          .signers([minter])
+          .rpc();
+
+        expect.fail("Should have thrown error");
+      } catch (error) {
+        expect(error.toString()).to.include("Unauthorized");
+      }
+    });
+
+    it("should not mint beyond the minter ceiling", async () => {
+      try {
+        await program.methods
+          .minterMint(new anchor.BN(1_000_001))
+          .accounts({
+            mintState,
+            minterAccount,
+            aruMint,
+            destination,
+            minter: minter.publicKey,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .signers([minter])
+          .rpc();
+
+        expect.fail("Should have thrown error");
+      } catch (error) {
+        expect(error.toString()).to.include("MinterCeilingExceeded");
+      }
+    });
+
+    it("should only let the registered minter mint", async () => {
+      const impostor = Keypair.generate();
+
+      try {
+        await program.methods
+          .minterMint(new anchor.BN(1))
+          .accounts({
+            mintState,
+            minterAccount,
+            aruMint,
+            destination,
+            minter: impostor.publicKey,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .signers([impostor])
+          .rpc();
+
+        expect.fail("Should have thrown error");
+      } catch (error) {
+        expect(error.toString()).to.match(/Unauthorized|ConstraintSeeds/);
+      }
+    });
+
+    it("should not burn more than the minter has outstanding", async () => {
+      try {
+        await program.methods
+          .minterBurn(new anchor.BN(1))
+          .accounts({
+            mintState,
+            minterAccount,
+            aruMint,
+            source: destination,
+            owner: authority.publicKey,
+            minter: minter.publicKey,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .signers([authority, minter])
+          .rpc();
+
+        expect.fail("Should have thrown error");
+      } catch (error) {
+        expect(error.toString()).to.include("MinterDebtExceeded");
+      }
+    });
+  });
+
+  describe("global settlement freeze", () => {
+    let frozenAuthority: Keypair;
+    let frozenMintState: PublicKey;
+    let frozenMint: PublicKey;
+    let destination: PublicKey;
+
+    before(async () => {
+      // A separate mint state, so freezing it leaves the suite's ARU mintable
+      frozenAuthority = Keypair.generate();
+      const signature = await provider.connection.requestAirdrop(
+        frozenAuthority.publicKey,
+        2 * LAMPORTS_PER_SOL
+      );
+      await provider.connection.confirmTransaction(signature);
+
+      [frozenMintState] = PublicKey.findProgramAddressSync(
+        [Buffer.from("mint_state"), frozenAuthority.publicKey.toBuffer()],
+        program.programId
+      );
+      frozenMint = await createMint(
+        provider.connection,
+        frozenAuthority,
+        frozenMintState,
+        null,
+        6
+      );
+      destination = await createAccount(
+        provider.connection,
+        frozenAuthority,
+        frozenMint,
+        frozenAuthority.publicKey
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(86400), 200, 200)
+        .accounts({
+          mintState: frozenMintState,
+          authority: frozenAuthority.publicKey,
+          aruMint: frozenMint,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([frozenAuthority])
+        .rpc();
+    });
+
+    it("should reject freezes from non-authority", async () => {
+      try {
+        await program.methods
+          .freezeMinting()
+          .accounts({
+            mintState: frozenMintState,
+            authority: authority.publicKey,
+          })
+          .signers([authority])
+          .rpc();
+
+        expect.fail("Should have thrown error");
+      } catch (error) {
+        expect(error.toString()).to.include("Unauthorized");
+      }
+    });
+
+    it("should block all minting once frozen", async () => {
+      await program.methods
+        .freezeMinting()
+        .accounts({
+          mintState: frozenMintState,
+          authority: frozenAuthority.publicKey,
+        })
+        .signers([frozenAuthority])
+        .rpc();
+
+      const mintStateAccount = await program.account.mintState.fetch(frozenMintState);
+      expect(mintStateAccount.mintingFrozen).to.be.true;
+
+      try {
+        await program.methods
+          .mintAru(new anchor.BN(1))
+          .accounts({
+            mintState: frozenMintState,
+            aruMint: frozenMint,
+            destination,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .rpc();
+
+        expect.fail("Should have thrown error");
+      } catch (error) {
+        expect(error.toString()).to.include("MintingFrozen");
+      }
+    });
+  });
 });
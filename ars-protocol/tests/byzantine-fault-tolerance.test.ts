@@ -133,6 +133,7 @@ describe("Byzantine Fault Tolerance Tests", () => {
             iliOracle,
             globalState,
             agentRegistry: agentRegistries[i],
+            rewardPool: null,
             agent: agents[i].publicKey,
           })
           .signers([agents[i]])
@@ -147,6 +148,7 @@ describe("Byzantine Fault Tolerance Tests", () => {
             iliOracle,
             globalState,
             agentRegistry: agentRegistries[i],
+            rewardPool: null,
             agent: agents[i].publicKey,
           })
           .signers([agents[i]])
@@ -170,6 +172,7 @@ describe("Byzantine Fault Tolerance Tests", () => {
             iliOracle,
             globalState,
             agentRegistry: agentRegistries[i],
+            rewardPool: null,
             agent: agents[i].publicKey,
           })
           .signers([agents[i]])
@@ -195,6 +198,7 @@ describe("Byzantine Fault Tolerance Tests", () => {
             iliOracle,
             globalState,
             agentRegistry: agentRegistries[i],
+            rewardPool: null,
             agent: agents[i].publicKey,
           })
           .signers([agents[i]])
@@ -209,6 +213,7 @@ describe("Byzantine Fault Tolerance Tests", () => {
             iliOracle,
             globalState,
             agentRegistry: agentRegistries[i + 2],
+            rewardPool: null,
             agent: agents[i + 2].publicKey,
           })
           .signers([agents[i + 2]])
@@ -233,6 +238,7 @@ describe("Byzantine Fault Tolerance Tests", () => {
           iliOracle,
           globalState,
           agentRegistry: agentRegistries[0],
+          rewardPool: null,
           agent: agents[0].publicKey,
         })
         .signers([agents[0]])
@@ -244,6 +250,7 @@ describe("Byzantine Fault Tolerance Tests", () => {
           iliOracle,
           globalState,
           agentRegistry: agentRegistries[1],
+          rewardPool: null,
           agent: agents[1].publicKey,
         })
         .signers([agents[1]])
@@ -319,6 +326,7 @@ describe("Byzantine Fault Tolerance Tests", () => {
             iliOracle,
             globalState,
             agentRegistry,
+            rewardPool: null,
             agent: agent.publicKey,
           })
           .signers([agent])
@@ -340,6 +348,7 @@ describe("Byzantine Fault Tolerance Tests", () => {
             iliOracle,
             globalState,
             agentRegistry,
+            rewardPool: null,
             agent: agent.publicKey,
           })
           .signers([agent])
@@ -361,6 +370,7 @@ describe("Byzantine Fault Tolerance Tests", () => {
           iliOracle,
           globalState,
           agentRegistry,
+          rewardPool: null,
           agent: agent.publicKey,
         })
         .signers([agent])
@@ -374,6 +384,7 @@ describe("Byzantine Fault Tolerance Tests", () => {
             iliOracle,
             globalState,
             agentRegistry,
+            rewardPool: null,
             agent: agent.publicKey,
           })
           .signers([agent])
@@ -458,6 +469,7 @@ describe("Byzantine Fault Tolerance Tests", () => {
             iliOracle,
             globalState,
             agentRegistry,
+            rewardPool: null,
             agent: wrongSigner.publicKey, // Wrong signer
           })
           .signers([wrongSigner])
@@ -479,6 +491,7 @@ describe("Byzantine Fault Tolerance Tests", () => {
           iliOracle,
           globalState,
           agentRegistry,
+          rewardPool: null,
           agent: agent.publicKey,
         })
         .signers([agent])
@@ -492,6 +505,7 @@ describe("Byzantine Fault Tolerance Tests", () => {
             iliOracle,
             globalState,
             agentRegistry,
+            rewardPool: null,
             agent: agent.publicKey,
           })
           .signers([agent])
@@ -517,6 +531,7 @@ describe("Byzantine Fault Tolerance Tests", () => {
             iliOracle,
             globalState,
             agentRegistry: unregisteredRegistry,
+            rewardPool: null,
             agent: unregisteredAgent.publicKey,
           })
           .signers([unregisteredAgent])
@@ -605,6 +620,7 @@ describe("Byzantine Fault Tolerance Tests", () => {
             iliOracle,
             globalState,
             agentRegistry: agentRegistries[i],
+            rewardPool: null,
             agent: agents[i].publicKey,
           })
           .signers([agents[i]])
@@ -619,6 +635,7 @@ describe("Byzantine Fault Tolerance Tests", () => {
             iliOracle,
             globalState,
             agentRegistry: agentRegistries[i],
+            rewardPool: null,
             agent: agents[i].publicKey,
           })
           .signers([agents[i]])
@@ -647,6 +664,7 @@ describe("Byzantine Fault Tolerance Tests", () => {
             iliOracle,
             globalState,
             agentRegistry: agentRegistries[i],
+            rewardPool: null,
             agent: agents[i].publicKey,
           })
           .signers([agents[i]])
@@ -661,6 +679,7 @@ describe("Byzantine Fault Tolerance Tests", () => {
             iliOracle,
             globalState,
             agentRegistry: agentRegistries[i],
+            rewardPool: null,
             agent: agents[i].publicKey,
           })
           .signers([agents[i]])
@@ -692,6 +711,7 @@ describe("Byzantine Fault Tolerance Tests", () => {
               iliOracle,
               globalState,
               agentRegistry: agentRegistries[i],
+              rewardPool: null,
               agent: agents[i].publicKey,
             })
             .signers([agents[i]])
@@ -706,6 +726,7 @@ describe("Byzantine Fault Tolerance Tests", () => {
               iliOracle,
               globalState,
               agentRegistry: agentRegistries[i],
+              rewardPool: null,
               agent: agents[i].publicKey,
             })
             .signers([agents[i]])
@@ -47,6 +47,9 @@ describe("Devnet Instructions Test", () => {
   let globalState: web3.PublicKey;
   let iliOracle: web3.PublicKey;
   let reserveVault: web3.PublicKey;
+  let assetRegistry: web3.PublicKey;
+  let usdcAsset: web3.PublicKey;
+  let usdcPosition: web3.PublicKey;
   let mintState: web3.PublicKey;
   
   // Token accounts
@@ -57,6 +60,11 @@ describe("Devnet Instructions Test", () => {
   let vaultUsdcAccount: web3.PublicKey;
   let userUsdcAccount: web3.PublicKey;
 
+  // USDC/USD price account; defaults to the local Switchboard-format fixture
+  const usdcPriceFeed = new web3.PublicKey(
+    process.env.USDC_PRICE_FEED ?? "7nezmvQvV3n1EuxFKeX1AQuWrG5eFGDagZnVcHnTf9R2"
+  );
+
   before(async () => {
     console.log("\n🚀 Setting up devnet test environment...");
     console.log(`Authority: ${authority.toString()}`);
@@ -127,6 +135,11 @@ describe("Devnet Instructions Test", () => {
       arsReserveProgram.programId
     );
 
+    [assetRegistry] = web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("asset_registry"), reserveVault.toBuffer()],
+      arsReserveProgram.programId
+    );
+
     [mintState] = web3.PublicKey.findProgramAddressSync(
       [Buffer.from("mint_state"), authority.toBuffer()],
       arsTokenProgram.programId
@@ -170,6 +183,15 @@ describe("Devnet Instructions Test", () => {
       web3.Keypair.generate()
     );
 
+    [usdcAsset] = web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("asset"), reserveVault.toBuffer(), usdcMint.toBuffer()],
+      arsReserveProgram.programId
+    );
+    [usdcPosition] = web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("position"), usdcAsset.toBuffer(), authority.toBuffer()],
+      arsReserveProgram.programId
+    );
+
     vaultUsdcAccount = await createAccount(
       provider.connection,
       provider.wallet.payer,
@@ -263,11 +285,6 @@ describe("Devnet Instructions Test", () => {
     it("Should initialize ars-reserve vault", async () => {
       console.log("\n🔧 Initializing ars-reserve...");
 
-      // Create dummy vault accounts
-      const solVault = web3.Keypair.generate().publicKey;
-      const msolVault = web3.Keypair.generate().publicKey;
-      const jitosolVault = web3.Keypair.generate().publicKey;
-
       const tx = await arsReserveProgram.methods
         .initialize(
           15000, // 150% min VHR
@@ -275,11 +292,9 @@ describe("Devnet Instructions Test", () => {
         )
         .accounts({
           vault: reserveVault,
+          assetRegistry,
+          mintState,
           authority,
-          usdcVault: vaultUsdcAccount,
-          solVault,
-          msolVault,
-          jitosolVault,
           systemProgram: web3.SystemProgram.programId,
         })
         .rpc();
@@ -288,11 +303,43 @@ describe("Devnet Instructions Test", () => {
 
       const vaultAccount = await arsReserveProgram.account.reserveVault.fetch(reserveVault);
       assert.equal(vaultAccount.authority.toString(), authority.toString());
-      assert.equal(vaultAccount.minVhr, 15000);
+      assert.equal(vaultAccount.minVhr.toNumber(), 15000);
       
       console.log("✅ ars-reserve initialized successfully");
     });
 
+    it("Should register USDC as a reserve asset", async () => {
+      console.log("\n🔧 Adding USDC to the asset registry...");
+
+      const tx = await arsReserveProgram.methods
+        .addAsset({
+          targetWeightBps: 4000, // 40% target weight
+          minWeightBps: 3000, // 30% min weight
+          maxWeightBps: 5000, // 50% max weight
+          volatilityThresholdBps: 200, // 2% volatility threshold
+          oracleSource: usdcPriceFeed,
+          maxPriceAge: new BN(10 * 365 * 86400),
+          maxConfidenceBps: 100, // 1% max confidence interval
+          haircutBps: 0,
+        })
+        .accounts({
+          vault: reserveVault,
+          assetRegistry,
+          assetConfig: usdcAsset,
+          mint: usdcMint,
+          assetVault: vaultUsdcAccount,
+          authority,
+          systemProgram: web3.SystemProgram.programId,
+        })
+        .rpc();
+
+      console.log(`✅ Transaction: ${tx}`);
+
+      const registry = await arsReserveProgram.account.assetRegistry.fetch(assetRegistry);
+      assert.equal(registry.assets.length, 1);
+      assert.equal(registry.totalTargetWeightBps, 4000);
+    });
+
     it("Should initialize ars-token mint state", async () => {
       console.log("\n🔧 Initializing ars-token...");
 
@@ -427,6 +474,7 @@ describe("Devnet Instructions Test", () => {
           iliOracle,
           globalState,
           agentRegistry,
+          rewardPool: null,
           agent: agent1.publicKey,
         })
         .signers([agent1])
@@ -453,6 +501,7 @@ describe("Devnet Instructions Test", () => {
           iliOracle,
           globalState,
           agentRegistry,
+          rewardPool: null,
           agent: agent2.publicKey,
         })
         .signers([agent2])
@@ -479,6 +528,7 @@ describe("Devnet Instructions Test", () => {
           iliOracle,
           globalState,
           agentRegistry,
+          rewardPool: null,
           agent: agent3.publicKey,
         })
         .signers([agent3])
@@ -518,7 +568,8 @@ describe("Devnet Instructions Test", () => {
         .createProposal(
           { mintAru: {} }, // PolicyType enum
           Array.from(policyParams),
-          votingPeriod
+          votingPeriod,
+          { quadraticStake: {} } // VotingPowerSource enum
         )
         .accounts({
           globalState,
@@ -545,14 +596,23 @@ describe("Devnet Instructions Test", () => {
         arsCoreProgram.programId
       );
 
-      const stakeAmount = new BN(1_000_000_000); // 1,000 ARU
-
+      // Voting power is agent 1's staked ARU at the proposal snapshot (1,000 ARU)
       const tx = await arsCoreProgram.methods
-        .voteOnProposal(true, stakeAmount)
+        .voteOnProposal(true)
         .accounts({
+          globalState,
           proposal: proposalPda,
           agentRegistry,
+          voterCheckpoints: web3.PublicKey.findProgramAddressSync(
+            [Buffer.from("checkpoints"), mintState.toBuffer(), agent1.publicKey.toBuffer()],
+            arsTokenProgram.programId
+          )[0],
+          voteRecord: web3.PublicKey.findProgramAddressSync(
+            [Buffer.from("vote_record"), proposalId.toArrayLike(Buffer, "le", 8), agent1.publicKey.toBuffer()],
+            arsCoreProgram.programId
+          )[0],
           voter: agent1.publicKey,
+          systemProgram: web3.SystemProgram.programId,
         })
         .signers([agent1])
         .rpc();
@@ -577,14 +637,23 @@ describe("Devnet Instructions Test", () => {
         arsCoreProgram.programId
       );
 
-      const stakeAmount = new BN(500_000_000); // 500 ARU
-
+      // Voting power is agent 2's staked ARU at the proposal snapshot (500 ARU)
       const tx = await arsCoreProgram.methods
-        .voteOnProposal(false, stakeAmount)
+        .voteOnProposal(false)
         .accounts({
+          globalState,
           proposal: proposalPda,
           agentRegistry,
+          voterCheckpoints: web3.PublicKey.findProgramAddressSync(
+            [Buffer.from("checkpoints"), mintState.toBuffer(), agent2.publicKey.toBuffer()],
+            arsTokenProgram.programId
+          )[0],
+          voteRecord: web3.PublicKey.findProgramAddressSync(
+            [Buffer.from("vote_record"), proposalId.toArrayLike(Buffer, "le", 8), agent2.publicKey.toBuffer()],
+            arsCoreProgram.programId
+          )[0],
           voter: agent2.publicKey,
+          systemProgram: web3.SystemProgram.programId,
         })
         .signers([agent2])
         .rpc();
@@ -604,10 +673,26 @@ describe("Devnet Instructions Test", () => {
 
       const depositAmount = new BN(100_000_000); // 100 USDC
 
+      await arsReserveProgram.methods
+        .openPosition()
+        .accounts({
+          assetConfig: usdcAsset,
+          position: usdcPosition,
+          owner: authority,
+          systemProgram: web3.SystemProgram.programId,
+        })
+        .rpc();
+
       const tx = await arsReserveProgram.methods
         .deposit(depositAmount)
         .accounts({
           vault: reserveVault,
+          assetConfig: usdcAsset,
+          position: usdcPosition,
+          priceFeed: usdcPriceFeed,
+          mintState,
+          aruMint,
+          iliOracle,
           user: authority,
           userTokenAccount: userUsdcAccount,
           vaultTokenAccount: vaultUsdcAccount,
@@ -630,10 +715,33 @@ describe("Devnet Instructions Test", () => {
 
       const withdrawAmount = new BN(50_000_000); // 50 USDC
 
+      await arsReserveProgram.methods
+        .initializeWithdrawalQueue(10000, new BN(1_000_000))
+        .accounts({
+          vault: reserveVault,
+          withdrawalQueue: web3.PublicKey.findProgramAddressSync(
+            [Buffer.from("withdrawal_queue"), reserveVault.toBuffer()],
+            arsReserveProgram.programId
+          )[0],
+          authority,
+          systemProgram: web3.SystemProgram.programId,
+        })
+        .rpc();
+
       const tx = await arsReserveProgram.methods
         .withdraw(withdrawAmount)
         .accounts({
           vault: reserveVault,
+          assetConfig: usdcAsset,
+          position: usdcPosition,
+          withdrawalQueue: web3.PublicKey.findProgramAddressSync(
+            [Buffer.from("withdrawal_queue"), reserveVault.toBuffer()],
+            arsReserveProgram.programId
+          )[0],
+          priceFeed: usdcPriceFeed,
+          mintState,
+          aruMint,
+          iliOracle,
           user: authority,
           userTokenAccount: userUsdcAccount,
           vaultTokenAccount: vaultUsdcAccount,
@@ -137,7 +137,8 @@ describe("Economic Attack Simulations", () => {
         .createProposal(
           { mintAru: {} },
           [1, 2, 3, 4],
-          new anchor.BN(86400)
+          new anchor.BN(86400),
+          { quadraticStake: {} }
         )
         .accounts({
           globalState,
@@ -150,11 +151,21 @@ describe("Economic Attack Simulations", () => {
 
       // Attacker votes with full stake
       await coreProgram.methods
-        .voteOnProposal(true, new anchor.BN(1_000_000_000_000))
+        .voteOnProposal(true)
         .accounts({
+          globalState,
           proposal,
           agentRegistry: attackerRegistry,
+          voterCheckpoints: PublicKey.findProgramAddressSync(
+            [Buffer.from("checkpoints"), mintState.toBuffer(), attacker.publicKey.toBuffer()],
+            tokenProgram.programId
+          )[0],
+          voteRecord: PublicKey.findProgramAddressSync(
+            [Buffer.from("vote_record"), globalStateAccount.proposalCounter.toArrayLike(Buffer, "le", 8), attacker.publicKey.toBuffer()],
+            coreProgram.programId
+          )[0],
           voter: attacker.publicKey,
+          systemProgram: SystemProgram.programId,
         })
         .signers([attacker])
         .rpc();
@@ -204,7 +215,8 @@ describe("Economic Attack Simulations", () => {
             .createProposal(
               { mintAru: {} },
               [i],
-              new anchor.BN(86400)
+              new anchor.BN(86400),
+              { quadraticStake: {} }
             )
             .accounts({
               globalState,
@@ -243,6 +255,7 @@ describe("Economic Attack Simulations", () => {
             iliOracle,
             globalState,
             agentRegistry: attackerRegistry,
+            rewardPool: null,
             agent: attacker.publicKey,
           })
           .signers([attacker])
@@ -299,6 +312,7 @@ describe("Economic Attack Simulations", () => {
             iliOracle,
             globalState,
             agentRegistry: attackerRegistry,
+            rewardPool: null,
             agent: attacker.publicKey,
           })
           .signers([attacker])
@@ -327,6 +341,29 @@ describe("Economic Attack Simulations", () => {
           .withdraw(largeWithdrawal)
           .accounts({
             vault,
+            assetConfig: PublicKey.findProgramAddressSync(
+              [Buffer.from("asset"), vault.toBuffer(), aruMint.toBuffer()],
+              reserveProgram.programId
+            )[0],
+            position: PublicKey.findProgramAddressSync(
+              [
+                Buffer.from("position"),
+                PublicKey.findProgramAddressSync(
+                  [Buffer.from("asset"), vault.toBuffer(), aruMint.toBuffer()],
+                  reserveProgram.programId
+                )[0].toBuffer(),
+                attacker.publicKey.toBuffer(),
+              ],
+              reserveProgram.programId
+            )[0],
+            withdrawalQueue: PublicKey.findProgramAddressSync(
+              [Buffer.from("withdrawal_queue"), vault.toBuffer()],
+              reserveProgram.programId
+            )[0],
+            priceFeed: PublicKey.default,
+            mintState,
+            aruMint,
+            iliOracle,
             user: attacker.publicKey,
             userTokenAccount: await createAccount(
               provider.connection,
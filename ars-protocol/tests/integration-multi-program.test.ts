@@ -74,21 +74,28 @@ describe("Multi-program integration tests", () => {
 
   describe("ars-core + ars-reserve interaction", () => {
     let usdcVault: PublicKey;
-    let solVault: Keypair;
-    let msolVault: Keypair;
-    let jitosolVault: Keypair;
+    let assetRegistry: PublicKey;
+    let usdcAsset: PublicKey;
+    // Local Switchboard-format USDC/USD fixture (see Anchor.toml)
+    const USDC_USD_FEED = new PublicKey("7nezmvQvV3n1EuxFKeX1AQuWrG5eFGDagZnVcHnTf9R2");
 
     before(async () => {
-      solVault = Keypair.generate();
-      msolVault = Keypair.generate();
-      jitosolVault = Keypair.generate();
+      [assetRegistry] = PublicKey.findProgramAddressSync(
+        [Buffer.from("asset_registry"), vault.toBuffer()],
+        reserveProgram.programId
+      );
+      [usdcAsset] = PublicKey.findProgramAddressSync(
+        [Buffer.from("asset"), vault.toBuffer(), usdcMint.toBuffer()],
+        reserveProgram.programId
+      );
 
-      // Create USDC vault
+      // Create USDC vault owned by the reserve vault PDA
       usdcVault = await createAccount(
         provider.connection,
         authority,
         usdcMint,
-        authority.publicKey
+        vault,
+        Keypair.generate()
       );
 
       // Initialize core
@@ -117,11 +124,32 @@ describe("Multi-program integration tests", () => {
         )
         .accounts({
           vault,
+          assetRegistry,
+          mintState,
+          authority: authority.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([authority])
+        .rpc();
+
+      await reserveProgram.methods
+        .addAsset({
+          targetWeightBps: 10000,
+          minWeightBps: 0,
+          maxWeightBps: 10000,
+          volatilityThresholdBps: 500,
+          oracleSource: USDC_USD_FEED,
+          maxPriceAge: new anchor.BN(10 * 365 * 86400), // fixture has a fixed publish time
+          maxConfidenceBps: 100,
+          haircutBps: 0,
+        })
+        .accounts({
+          vault,
+          assetRegistry,
+          assetConfig: usdcAsset,
+          mint: usdcMint,
+          assetVault: usdcVault,
           authority: authority.publicKey,
-          usdcVault,
-          solVault: solVault.publicKey,
-          msolVault: msolVault.publicKey,
-          jitosolVault: jitosolVault.publicKey,
           systemProgram: SystemProgram.programId,
         })
         .signers([authority])
@@ -139,7 +167,8 @@ describe("Multi-program integration tests", () => {
         .createProposal(
           { rebalanceVault: {} },
           [1, 2, 3, 4],
-          new anchor.BN(86400)
+          new anchor.BN(86400),
+          { quadraticStake: {} }
         )
         .accounts({
           globalState,
@@ -199,11 +228,22 @@ describe("Multi-program integration tests", () => {
         user.publicKey
       );
 
+      const [position] = PublicKey.findProgramAddressSync(
+        [Buffer.from("position"), usdcAsset.toBuffer(), user.publicKey.toBuffer()],
+        reserveProgram.programId
+      );
+
       try {
         await reserveProgram.methods
           .deposit(new anchor.BN(1_000_000))
           .accounts({
             vault,
+            assetConfig: usdcAsset,
+            position,
+            priceFeed: USDC_USD_FEED,
+            mintState,
+            aruMint,
+            iliOracle,
             user: user.publicKey,
             userTokenAccount,
             vaultTokenAccount: usdcVault,
@@ -238,6 +278,7 @@ describe("Multi-program integration tests", () => {
             [Buffer.from("agent"), agent1.publicKey.toBuffer()],
             coreProgram.programId
           )[0],
+          rewardPool: null,
           agent: agent1.publicKey,
         })
         .signers([agent1])
@@ -251,11 +292,13 @@ describe("Multi-program integration tests", () => {
       
       // 5. Verify VHR is recalculated
       const vaultAccount = await reserveProgram.account.reserveVault.fetch(vault);
-      expect(vaultAccount.vhr).to.be.greaterThan(0);
+      expect(vaultAccount.vhr.gtn(0)).to.be.true;
     });
   });
 
   describe("ars-core + ars-token interaction", () => {
+    let stabilityFeeMinter: PublicKey;
+
     before(async () => {
       // Initialize token program
       await tokenProgram.methods
@@ -272,6 +315,22 @@ describe("Multi-program integration tests", () => {
         })
         .signers([authority])
         .rpc();
+
+      // Stability fees are minted through a minter registered for the global state PDA
+      [stabilityFeeMinter] = PublicKey.findProgramAddressSync(
+        [Buffer.from("minter"), mintState.toBuffer(), globalState.toBuffer()],
+        tokenProgram.programId
+      );
+      await tokenProgram.methods
+        .registerMinter(globalState, new anchor.BN(1_000_000_000))
+        .accounts({
+          mintState,
+          minterAccount: stabilityFeeMinter,
+          authority: authority.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([authority])
+        .rpc();
     });
 
     it("should execute proposal to mint ARU tokens", async () => {
@@ -290,7 +349,8 @@ describe("Multi-program integration tests", () => {
         .createProposal(
           { mintAru: {} },
           Array.from(policyParams),
-          new anchor.BN(86400)
+          new anchor.BN(86400),
+          { quadraticStake: {} }
         )
         .accounts({
           globalState,
@@ -346,7 +406,8 @@ describe("Multi-program integration tests", () => {
         .createProposal(
           { burnAru: {} },
           Array.from(policyParams),
-          new anchor.BN(86400)
+          new anchor.BN(86400),
+          { quadraticStake: {} }
         )
         .accounts({
           globalState,
@@ -384,7 +445,8 @@ describe("Multi-program integration tests", () => {
         .createProposal(
           { startNewEpoch: {} },
           [],
-          new anchor.BN(86400)
+          new anchor.BN(86400),
+          { quadraticStake: {} }
         )
         .accounts({
           globalState,
@@ -402,6 +464,135 @@ describe("Multi-program integration tests", () => {
       const mintStateAfter = await tokenProgram.account.mintState.fetch(mintState);
       // expect(mintStateAfter.currentEpoch.toNumber()).to.equal(currentEpoch + 1);
     });
+
+    it("should collect accrued stability fees into the reserve within the minter ceiling", async () => {
+      // Reserve-owned ARU account receives collected fees
+      const reserveAruAccount = await createAccount(
+        provider.connection,
+        authority,
+        aruMint,
+        vault,
+        Keypair.generate()
+      );
+
+      const globalStateBefore = await coreProgram.account.globalState.fetch(globalState);
+      const mintStateBefore = await tokenProgram.account.mintState.fetch(mintState);
+
+      await coreProgram.methods
+        .collectStabilityFee()
+        .accounts({
+          globalState,
+          mintState,
+          aruMint,
+          destination: reserveAruAccount,
+          savingsDestination: null,
+          minterAccount: stabilityFeeMinter,
+          arsTokenProgram: tokenProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+
+      const globalStateAfter = await coreProgram.account.globalState.fetch(globalState);
+      const mintStateAfter = await tokenProgram.account.mintState.fetch(mintState);
+      const collected = (await getAccount(provider.connection, reserveAruAccount)).amount;
+
+      expect(globalStateAfter.lastFeeAccrual.toNumber())
+        .to.be.at.least(globalStateBefore.lastFeeAccrual.toNumber());
+      expect(globalStateAfter.stabilityFeeIndex.gte(globalStateBefore.stabilityFeeIndex)).to.be.true;
+
+      // Whatever was minted must fit under the minter's ceiling
+      const minter = await tokenProgram.account.minter.fetch(stabilityFeeMinter);
+      expect(minter.outstanding.lte(minter.mintCeiling)).to.be.true;
+      expect(minter.outstanding.toNumber()).to.equal(Number(collected));
+      expect(Number(collected)).to.equal(
+        mintStateAfter.totalSupply.sub(mintStateBefore.totalSupply).toNumber()
+      );
+    });
+
+    it("should reject stability fee collection into a non-reserve account", async () => {
+      const foreignAruAccount = await createAccount(
+        provider.connection,
+        authority,
+        aruMint,
+        Keypair.generate().publicKey
+      );
+
+      try {
+        await coreProgram.methods
+          .collectStabilityFee()
+          .accounts({
+            globalState,
+            mintState,
+            aruMint,
+            destination: foreignAruAccount,
+            savingsDestination: null,
+            minterAccount: stabilityFeeMinter,
+            arsTokenProgram: tokenProgram.programId,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .rpc();
+
+        expect.fail("Should have thrown error");
+      } catch (error) {
+        expect(error.toString()).to.include("Unauthorized");
+      }
+    });
+
+    it("should reject stability fee collection against a non-canonical MintState", async () => {
+      // Anyone can initialize an ars-token MintState for the real ARU mint under their own key
+      const attacker = Keypair.generate();
+      await provider.connection.requestAirdrop(attacker.publicKey, anchor.web3.LAMPORTS_PER_SOL);
+      await new Promise(resolve => setTimeout(resolve, 1000));
+
+      const [fakeMintState] = PublicKey.findProgramAddressSync(
+        [Buffer.from("mint_state"), attacker.publicKey.toBuffer()],
+        tokenProgram.programId
+      );
+      await tokenProgram.methods
+        .initialize(new anchor.BN(86400), 200, 200)
+        .accounts({
+          mintState: fakeMintState,
+          authority: attacker.publicKey,
+          aruMint,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([attacker])
+        .rpc();
+
+      const reserveAruAccount = await createAccount(
+        provider.connection,
+        authority,
+        aruMint,
+        vault,
+        Keypair.generate()
+      );
+      const globalStateBefore = await coreProgram.account.globalState.fetch(globalState);
+
+      try {
+        await coreProgram.methods
+          .collectStabilityFee()
+          .accounts({
+            globalState,
+            mintState: fakeMintState,
+            aruMint,
+            destination: reserveAruAccount,
+            savingsDestination: null,
+            minterAccount: stabilityFeeMinter,
+            arsTokenProgram: tokenProgram.programId,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .rpc();
+
+        expect.fail("Should have thrown error");
+      } catch (error) {
+        expect(error.toString()).to.include("InvalidAsset");
+      }
+
+      const globalStateAfter = await coreProgram.account.globalState.fetch(globalState);
+      expect(globalStateAfter.lastFeeAccrual.toString()).to.equal(
+        globalStateBefore.lastFeeAccrual.toString()
+      );
+    });
   });
 
   describe("full governance workflow", () => {
@@ -494,6 +685,7 @@ describe("Multi-program integration tests", () => {
           iliOracle,
           globalState,
           agentRegistry: agentRegistry1,
+          rewardPool: null,
           agent: agent1.publicKey,
         })
         .signers([agent1])
@@ -505,6 +697,7 @@ describe("Multi-program integration tests", () => {
           iliOracle,
           globalState,
           agentRegistry: agentRegistry2,
+          rewardPool: null,
           agent: agent2.publicKey,
         })
         .signers([agent2])
@@ -516,6 +709,7 @@ describe("Multi-program integration tests", () => {
           iliOracle,
           globalState,
           agentRegistry: agentRegistry3,
+          rewardPool: null,
           agent: agent3.publicKey,
         })
         .signers([agent3])
@@ -536,7 +730,8 @@ describe("Multi-program integration tests", () => {
         .createProposal(
           { mintAru: {} },
           [1, 2, 3, 4],
-          new anchor.BN(86400)
+          new anchor.BN(86400),
+          { quadraticStake: {} }
         )
         .accounts({
           globalState,
@@ -549,21 +744,41 @@ describe("Multi-program integration tests", () => {
 
       // Step 3: Agents vote on proposal (quadratic voting)
       await coreProgram.methods
-        .voteOnProposal(true, new anchor.BN(10_000_000_000))
+        .voteOnProposal(true)
         .accounts({
+          globalState,
           proposal,
           agentRegistry: agentRegistry1,
+          voterCheckpoints: PublicKey.findProgramAddressSync(
+            [Buffer.from("checkpoints"), mintState.toBuffer(), agent1.publicKey.toBuffer()],
+            tokenProgram.programId
+          )[0],
+          voteRecord: PublicKey.findProgramAddressSync(
+            [Buffer.from("vote_record"), globalStateAccount.proposalCounter.toArrayLike(Buffer, "le", 8), agent1.publicKey.toBuffer()],
+            coreProgram.programId
+          )[0],
           voter: agent1.publicKey,
+          systemProgram: SystemProgram.programId,
         })
         .signers([agent1])
         .rpc();
 
       await coreProgram.methods
-        .voteOnProposal(true, new anchor.BN(10_000_000_000))
+        .voteOnProposal(true)
         .accounts({
+          globalState,
           proposal,
           agentRegistry: agentRegistry2,
+          voterCheckpoints: PublicKey.findProgramAddressSync(
+            [Buffer.from("checkpoints"), mintState.toBuffer(), agent2.publicKey.toBuffer()],
+            tokenProgram.programId
+          )[0],
+          voteRecord: PublicKey.findProgramAddressSync(
+            [Buffer.from("vote_record"), globalStateAccount.proposalCounter.toArrayLike(Buffer, "le", 8), agent2.publicKey.toBuffer()],
+            coreProgram.programId
+          )[0],
           voter: agent2.publicKey,
+          systemProgram: SystemProgram.programId,
         })
         .signers([agent2])
         .rpc();
@@ -583,7 +798,7 @@ describe("Multi-program integration tests", () => {
       
       // Verify protocol state is consistent
       expect(mintStateAccount.totalSupply.toNumber()).to.be.greaterThanOrEqual(0);
-      expect(vaultAccount.vhr).to.be.greaterThan(0);
+      expect(vaultAccount.vhr.gtn(0)).to.be.true;
     });
 
     it("should handle Byzantine fault tolerance in governance", async () => {
@@ -599,6 +814,7 @@ describe("Multi-program integration tests", () => {
           iliOracle,
           globalState,
           agentRegistry: agentRegistry1,
+          rewardPool: null,
           agent: agent1.publicKey,
         })
         .signers([agent1])
@@ -610,6 +826,7 @@ describe("Multi-program integration tests", () => {
           iliOracle,
           globalState,
           agentRegistry: agentRegistry2,
+          rewardPool: null,
           agent: agent2.publicKey,
         })
         .signers([agent2])
@@ -622,6 +839,7 @@ describe("Multi-program integration tests", () => {
           iliOracle,
           globalState,
           agentRegistry: agentRegistry3,
+          rewardPool: null,
           agent: agent3.publicKey,
         })
         .signers([agent3])
@@ -631,6 +849,48 @@ describe("Multi-program integration tests", () => {
       const iliOracleAccount = await coreProgram.account.iliOracle.fetch(iliOracle);
       expect(iliOracleAccount.currentIli.toNumber()).to.equal(honestIli);
     });
+
+    it("should value ARU at the ILI price in the reserve", async () => {
+      // A realistic ILI of 1.05, published as 4-decimal fixed point
+      const ili = 10_500;
+      const timestamp = Math.floor(Date.now() / 1000);
+
+      for (const [agent, agentRegistry] of [
+        [agent1, agentRegistry1],
+        [agent2, agentRegistry2],
+        [agent3, agentRegistry3],
+      ] as [Keypair, PublicKey][]) {
+        await coreProgram.methods
+          .submitIliUpdate(new anchor.BN(ili), new anchor.BN(timestamp))
+          .accounts({
+            iliOracle,
+            globalState,
+            agentRegistry,
+            rewardPool: null,
+            agent: agent.publicKey,
+          })
+          .signers([agent])
+          .rpc();
+      }
+
+      await reserveProgram.methods
+        .refreshVhr()
+        .accounts({
+          vault,
+          mintState,
+          aruMint,
+          iliOracle,
+        })
+        .rpc();
+
+      // $1.05 in 6-decimal USD, applied to the 6-decimal ARU supply
+      const vaultAccount = await reserveProgram.account.reserveVault.fetch(vault);
+      const mintStateAccount = await tokenProgram.account.mintState.fetch(mintState);
+      expect(vaultAccount.aruPriceUsd.toNumber()).to.equal(1_050_000);
+      expect(vaultAccount.liabilitiesUsd.toNumber()).to.equal(
+        Math.floor((mintStateAccount.totalSupply.toNumber() * 1_050_000) / 1_000_000)
+      );
+    });
   });
 
   describe("cross-program state consistency", () => {
@@ -1,8 +1,9 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { ArsCore } from "../target/types/ars_core";
-import { PublicKey, Keypair, SystemProgram } from "@solana/web3.js";
-import { TOKEN_PROGRAM_ID, createMint, createAccount, mintTo } from "@solana/spl-token";
+import { MockPercolator } from "../target/types/mock_percolator";
+import { PublicKey, Keypair, SystemProgram, Transaction, TransactionInstruction } from "@solana/web3.js";
+import { TOKEN_PROGRAM_ID, createMint, createAccount, mintTo, getAccount } from "@solana/spl-token";
 import { assert } from "chai";
 
 describe("Percolator Integration Tests", () => {
@@ -10,23 +11,30 @@ describe("Percolator Integration Tests", () => {
   anchor.setProvider(provider);
 
   const program = anchor.workspace.ArsCore as Program<ArsCore>;
+  // Local stand-in deployed at the Percolator program ID
+  const mockPercolator = anchor.workspace.MockPercolator as Program<MockPercolator>;
+  const PERCOLATOR_PROGRAM_ID = mockPercolator.programId;
   
-  // Percolator devnet addresses
-  const PERCOLATOR_PROGRAM_ID = new PublicKey("46iB4ET4WpqfTXAqGSmyBczLBgVhd1sHre93KtU3sTg9");
-  const PERCOLATOR_SLAB = new PublicKey("AcF3Q3UMHqx2xZR2Ty6pNvfCaogFmsLEqyMACQ2c4UPK");
-  const PERCOLATOR_VAULT = new PublicKey("D7QrsrJ4emtsw5LgPGY2coM5K9WPPVgQNJVr5TbK7qtU");
+  const slab = Keypair.generate();
+  const PERCOLATOR_SLAB = slab.publicKey;
+  let PERCOLATOR_VAULT: PublicKey;
+  let lp: Keypair;
   
   let authority: Keypair;
   let agent: Keypair;
   let mint: PublicKey;
   let arsTokenAccount: PublicKey;
+  let agentTokenAccount: PublicKey;
+  let stakeEscrow: PublicKey;
   let globalStatePda: PublicKey;
   let iliOraclePda: PublicKey;
   let agentRegistryPda: PublicKey;
+  let percolatorAuthorityPda: PublicKey;
   
   before(async () => {
     authority = Keypair.generate();
     agent = Keypair.generate();
+    lp = Keypair.generate();
     
     // Airdrop SOL
     await provider.connection.requestAirdrop(authority.publicKey, 10 * anchor.web3.LAMPORTS_PER_SOL);
@@ -42,12 +50,19 @@ describe("Percolator Integration Tests", () => {
       9
     );
     
+    // Collateral is held by the ars-core PDA that signs Percolator CPIs
+    [percolatorAuthorityPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("percolator_authority")],
+      program.programId
+    );
+    
     // Create token account
     arsTokenAccount = await createAccount(
       provider.connection,
       authority,
       mint,
-      authority.publicKey
+      percolatorAuthorityPda,
+      Keypair.generate()
     );
     
     // Mint tokens
@@ -72,18 +87,78 @@ describe("Percolator Integration Tests", () => {
     );
     
     [agentRegistryPda] = PublicKey.findProgramAddressSync(
-      [Buffer.from("agent_registry"), agent.publicKey.toBuffer()],
+      [Buffer.from("agent"), agent.publicKey.toBuffer()],
       program.programId
     );
+    
+    // Agent stake is escrowed with the global state PDA
+    agentTokenAccount = await createAccount(
+      provider.connection,
+      agent,
+      mint,
+      agent.publicKey
+    );
+    await mintTo(
+      provider.connection,
+      authority,
+      mint,
+      agentTokenAccount,
+      authority,
+      100_000_000_000
+    );
+    stakeEscrow = await createAccount(
+      provider.connection,
+      agent,
+      mint,
+      globalStatePda
+    );
+    
+    // Slab vault owned by the mock's vault authority PDA; ARS is user 0, `lp` is LP 1
+    const [vaultAuthority] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vault"), PERCOLATOR_SLAB.toBuffer()],
+      PERCOLATOR_PROGRAM_ID
+    );
+    PERCOLATOR_VAULT = await createAccount(
+      provider.connection,
+      authority,
+      mint,
+      vaultAuthority,
+      Keypair.generate()
+    );
+    
+    await mockPercolator.methods
+      .initializeSlab(percolatorAuthorityPda, new anchor.BN(1_000_000))
+      .accounts({
+        slab: slab.publicKey,
+        mint,
+        vault: PERCOLATOR_VAULT,
+        admin: authority.publicKey,
+      })
+      .signers([slab, authority])
+      .rpc();
+    
+    await mockPercolator.methods
+      .initAccount(percolatorAuthorityPda, false)
+      .accounts({ slab: PERCOLATOR_SLAB, admin: authority.publicKey })
+      .signers([authority])
+      .rpc();
+    
+    await mockPercolator.methods
+      .initAccount(lp.publicKey, true)
+      .accounts({ slab: PERCOLATOR_SLAB, admin: authority.publicKey })
+      .signers([authority])
+      .rpc();
   });
   
   it("Initializes ARS protocol", async () => {
     await program.methods
-      .initialize()
+      .initialize(new anchor.BN(86400), 200, 15000)
       .accounts({
         globalState: globalStatePda,
         iliOracle: iliOraclePda,
         authority: authority.publicKey,
+        reserveVault: Keypair.generate().publicKey,
+        aruMint: mint,
         systemProgram: SystemProgram.programId,
       })
       .signers([authority])
@@ -97,10 +172,11 @@ describe("Percolator Integration Tests", () => {
     await program.methods
       .registerAgent(new anchor.BN(100_000_000_000)) // 100 ARU stake
       .accounts({
-        globalState: globalStatePda,
         agentRegistry: agentRegistryPda,
         agent: agent.publicKey,
-        authority: agent.publicKey,
+        agentTokenAccount,
+        stakeEscrow,
+        tokenProgram: TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
       })
       .signers([agent])
@@ -108,7 +184,7 @@ describe("Percolator Integration Tests", () => {
     
     const agentRegistry = await program.account.agentRegistry.fetch(agentRegistryPda);
     assert.equal(agentRegistry.isActive, true);
-    assert.equal(agentRegistry.tier, 3); // Platinum tier
+    assert.deepEqual(agentRegistry.agentTier, { bronze: {} });
   });
   
   it("Allocates collateral to Percolator", async () => {
@@ -123,7 +199,7 @@ describe("Percolator Integration Tests", () => {
         percolatorDeposit: {
           slab: PERCOLATOR_SLAB,
           vault: PERCOLATOR_VAULT,
-          authority: authority.publicKey,
+          authority: percolatorAuthorityPda,
           arsTokenAccount: arsTokenAccount,
           tokenProgram: TOKEN_PROGRAM_ID,
           percolatorProgram: PERCOLATOR_PROGRAM_ID,
@@ -134,9 +210,11 @@ describe("Percolator Integration Tests", () => {
     
     console.log("Allocation tx:", tx);
     
-    // Verify event was emitted
-    const events = await program.account.globalState.fetch(globalStatePda);
-    // Note: Event verification would require parsing transaction logs
+    const slabState = await mockPercolator.account.slab.fetch(PERCOLATOR_SLAB);
+    assert.equal(slabState.accounts[0].capital.toString(), amount.toString());
+    
+    const vault = await getAccount(provider.connection, PERCOLATOR_VAULT);
+    assert.equal(vault.amount.toString(), amount.toString());
   });
   
   it("Updates Percolator oracle with ILI price", async () => {
@@ -144,13 +222,13 @@ describe("Percolator Integration Tests", () => {
     await program.methods
       .submitIliUpdate(
         new anchor.BN(10500), // ILI = 105%
-        Buffer.from(new Uint8Array(64)), // Mock signature
-        new anchor.BN(Date.now() / 1000)
+        new anchor.BN(Math.floor(Date.now() / 1000))
       )
       .accounts({
         globalState: globalStatePda,
         iliOracle: iliOraclePda,
         agentRegistry: agentRegistryPda,
+        rewardPool: null,
         agent: agent.publicKey,
       })
       .signers([agent])
@@ -165,7 +243,7 @@ describe("Percolator Integration Tests", () => {
         authority: authority.publicKey,
         percolatorPush: {
           slab: PERCOLATOR_SLAB,
-          authority: authority.publicKey,
+          authority: percolatorAuthorityPda,
           percolatorProgram: PERCOLATOR_PROGRAM_ID,
         },
       })
@@ -173,11 +251,15 @@ describe("Percolator Integration Tests", () => {
       .rpc();
     
     console.log("Oracle update tx:", tx);
+    
+    // ILI is 4-decimal, Percolator prices are e6: 10500 (1.05) -> 1_050_000
+    const slabState = await mockPercolator.account.slab.fetch(PERCOLATOR_SLAB);
+    assert.equal(slabState.priceE6.toString(), "1050000");
   });
   
   it("Executes trade on Percolator", async () => {
     const userIdx = 0;
-    const lpIdx = 0;
+    const lpIdx = 1;
     const size = new anchor.BN(1000); // Long 1000 units
     
     // Derive oracle account (mock for test)
@@ -186,19 +268,23 @@ describe("Percolator Integration Tests", () => {
     const tx = await program.methods
       .executePercolatorTrade(userIdx, lpIdx, size)
       .accounts({
-        agentRegistry: agentRegistryPda,
-        authority: agent.publicKey,
+        globalState: globalStatePda,
+        authority: authority.publicKey,
         percolatorTrade: {
           slab: PERCOLATOR_SLAB,
           oracle: oracleAccount,
-          authority: agent.publicKey,
+          authority: percolatorAuthorityPda,
           percolatorProgram: PERCOLATOR_PROGRAM_ID,
         },
       })
-      .signers([agent])
+      .signers([authority])
       .rpc();
     
     console.log("Trade tx:", tx);
+    
+    const slabState = await mockPercolator.account.slab.fetch(PERCOLATOR_SLAB);
+    assert.equal(slabState.accounts[0].position.toString(), "1000");
+    assert.equal(slabState.accounts[1].position.toString(), "-1000");
   });
   
   it("Withdraws from Percolator", async () => {
@@ -223,7 +309,7 @@ describe("Percolator Integration Tests", () => {
           slab: PERCOLATOR_SLAB,
           vault: PERCOLATOR_VAULT,
           vaultAuthority: vaultAuthority,
-          authority: authority.publicKey,
+          authority: percolatorAuthorityPda,
           arsTokenAccount: arsTokenAccount,
           oracle: oracleAccount,
           tokenProgram: TOKEN_PROGRAM_ID,
@@ -234,6 +320,12 @@ describe("Percolator Integration Tests", () => {
       .rpc();
     
     console.log("Withdrawal tx:", tx);
+    
+    const slabState = await mockPercolator.account.slab.fetch(PERCOLATOR_SLAB);
+    assert.equal(slabState.accounts[0].capital.toString(), "500000000");
+    
+    const arsAccount = await getAccount(provider.connection, arsTokenAccount);
+    assert.equal(arsAccount.amount.toString(), "99500000000");
   });
   
   it("Rejects allocation from non-admin", async () => {
@@ -249,7 +341,7 @@ describe("Percolator Integration Tests", () => {
           percolatorDeposit: {
             slab: PERCOLATOR_SLAB,
             vault: PERCOLATOR_VAULT,
-            authority: agent.publicKey,
+            authority: percolatorAuthorityPda,
             arsTokenAccount: arsTokenAccount,
             tokenProgram: TOKEN_PROGRAM_ID,
             percolatorProgram: PERCOLATOR_PROGRAM_ID,
@@ -264,38 +356,73 @@ describe("Percolator Integration Tests", () => {
     }
   });
   
-  it("Rejects trade from inactive agent", async () => {
-    const inactiveAgent = Keypair.generate();
-    await provider.connection.requestAirdrop(inactiveAgent.publicKey, 2 * anchor.web3.LAMPORTS_PER_SOL);
-    await new Promise(resolve => setTimeout(resolve, 1000));
-    
-    const [inactiveAgentPda] = PublicKey.findProgramAddressSync(
-      [Buffer.from("agent_registry"), inactiveAgent.publicKey.toBuffer()],
-      program.programId
-    );
-    
+  it("Rejects trade from non-admin", async () => {
     const oracleAccount = Keypair.generate().publicKey;
     
     try {
       await program.methods
         .executePercolatorTrade(0, 0, new anchor.BN(1000))
         .accounts({
-          agentRegistry: inactiveAgentPda,
-          authority: inactiveAgent.publicKey,
+          globalState: globalStatePda,
+          authority: agent.publicKey, // Non-admin
           percolatorTrade: {
             slab: PERCOLATOR_SLAB,
             oracle: oracleAccount,
-            authority: inactiveAgent.publicKey,
+            authority: percolatorAuthorityPda,
+            percolatorProgram: PERCOLATOR_PROGRAM_ID,
+          },
+        })
+        .signers([agent])
+        .rpc();
+      
+      assert.fail("Should have thrown unauthorized error");
+    } catch (err) {
+      assert.include(err.toString(), "Unauthorized");
+    }
+  });
+  
+  it("Rejects a zero-size trade", async () => {
+    try {
+      await program.methods
+        .executePercolatorTrade(0, 0, new anchor.BN(0))
+        .accounts({
+          globalState: globalStatePda,
+          authority: authority.publicKey,
+          percolatorTrade: {
+            slab: PERCOLATOR_SLAB,
+            oracle: Keypair.generate().publicKey,
+            authority: percolatorAuthorityPda,
             percolatorProgram: PERCOLATOR_PROGRAM_ID,
           },
         })
-        .signers([inactiveAgent])
+        .signers([authority])
         .rpc();
       
-      assert.fail("Should have thrown agent not active error");
+      assert.fail("Should have thrown invalid amount error");
+    } catch (err) {
+      assert.include(err.toString(), "InvalidAmount");
+    }
+  });
+  
+  it("Rejects a price push from anyone but the slab's oracle authority", async () => {
+    const data = Buffer.alloc(9);
+    data.writeUInt8(14, 0);
+    data.writeBigUInt64LE(BigInt(2_000_000), 1);
+    
+    const ix = new TransactionInstruction({
+      programId: PERCOLATOR_PROGRAM_ID,
+      keys: [
+        { pubkey: PERCOLATOR_SLAB, isSigner: false, isWritable: true },
+        { pubkey: authority.publicKey, isSigner: true, isWritable: false },
+      ],
+      data,
+    });
+    
+    try {
+      await provider.sendAndConfirm(new Transaction().add(ix), [authority]);
+      assert.fail("Should have thrown unauthorized error");
     } catch (err) {
-      // Expected to fail because agent is not registered
-      assert.ok(err);
+      assert.include(err.toString(), "Unauthorized");
     }
   });
 });